@@ -36,6 +36,15 @@ impl Appearance {
             Self::Image(o) => o.within(rect, c),
         }
     }
+
+    ///Returns the widths of border, None if it has no border.
+    pub(crate) fn border(&self) -> Option<Sides> {
+        match self {
+            Self::RectangleCurve(o) => Some(Sides::new(o.left, o.top, o.right, o.bottom)),
+            Self::RoundRectCurve(o) => Some(Sides::new(o.left, o.top, o.right, o.bottom)),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -45,7 +54,7 @@ pub(crate) struct Rectangle {
 
 impl Default for Rectangle {
     fn default() -> Self {
-        Self::new(*default_bg_color())
+        Self::new(Theme::default().bg_color())
     }
 }
 
@@ -74,7 +83,7 @@ pub(crate) struct RoundRectangle {
 
 impl Default for RoundRectangle {
     fn default() -> Self {
-        Self::new(*default_bg_color(), 10.0, 10.0)
+        Self::new(Theme::default().bg_color(), 10.0, 10.0)
     }
 }
 
@@ -105,6 +114,7 @@ impl RoundRectangle {
 #[derive(Debug)]
 pub(crate) struct RectangleCurve {
     pub(crate) color: Color,
+    pub(crate) fill: Color,
     pub(crate) left: f32,
     pub(crate) top: f32,
     pub(crate) right: f32,
@@ -113,7 +123,7 @@ pub(crate) struct RectangleCurve {
 
 impl Default for RectangleCurve {
     fn default() -> Self {
-        Self::new(*default_border_color(), 1.0, 1.0, 1.0, 1.0)
+        Self::new(Theme::default().border_color(), 1.0, 1.0, 1.0, 1.0)
     }
 }
 
@@ -121,6 +131,7 @@ impl RectangleCurve {
     pub(crate) fn new(color: Color, left: f32, top: f32, right: f32, bottom: f32) -> Self {
         Self {
             color,
+            fill: Theme::default().blank_color(),
             left,
             top,
             right,
@@ -137,7 +148,7 @@ impl RectangleCurve {
                 (self.left, self.top),
                 (-self.left - self.right, -self.top - self.bottom),
             );
-        paint.set_color(*default_blank_color());
+        paint.set_color(self.fill);
         t.surface.canvas().draw_rect(r.to_rect(), &paint);
     }
 
@@ -149,6 +160,7 @@ impl RectangleCurve {
 #[derive(Debug)]
 pub(crate) struct RoundRectCurve {
     pub(crate) color: Color,
+    pub(crate) fill: Color,
     pub(crate) x_rad: f32,
     pub(crate) y_rad: f32,
     pub(crate) left: f32,
@@ -159,7 +171,15 @@ pub(crate) struct RoundRectCurve {
 
 impl Default for RoundRectCurve {
     fn default() -> Self {
-        Self::new(*default_border_color(), 0.0, 0.0, 1.0, 1.0, 1.0, 1.0)
+        Self::new(
+            Theme::default().border_color(),
+            0.0,
+            0.0,
+            1.0,
+            1.0,
+            1.0,
+            1.0,
+        )
     }
 }
 
@@ -175,6 +195,7 @@ impl RoundRectCurve {
     ) -> Self {
         Self {
             color,
+            fill: Theme::default().blank_color(),
             x_rad,
             y_rad,
             left,
@@ -197,7 +218,7 @@ impl RoundRectCurve {
                 (self.left, self.top),
                 (-self.left - self.right, -self.top - self.bottom),
             );
-        paint.set_color(*default_blank_color());
+        paint.set_color(self.fill);
         t.surface.canvas().draw_rrect(self.to_rrect(&r), paint);
     }
 
@@ -289,4 +310,83 @@ impl AppearanceComposite {
         }
         false
     }
+
+    pub(crate) fn border(&self) -> Sides {
+        self.buffer
+            .iter()
+            .find_map(|o| o.border())
+            .unwrap_or_default()
+    }
+}
+
+///Represents the look of a draw unit, defaults are replaced by the visual attributes of element.
+#[derive(Clone, Debug)]
+pub(crate) struct Look {
+    pub(crate) background: Color,
+    pub(crate) border_color: Color,
+    pub(crate) border: Sides,
+    pub(crate) radius: f32,
+}
+
+impl Look {
+    pub(crate) fn fill(t: &Theme, background: Color) -> Self {
+        Self {
+            background,
+            border_color: t.border_color(),
+            border: Default::default(),
+            radius: 0.0,
+        }
+    }
+
+    ///A round one with the radius of theme.
+    pub(crate) fn round(t: &Theme, background: Color) -> Self {
+        Self {
+            radius: t.radius(),
+            ..Self::fill(t, background)
+        }
+    }
+
+    ///A blank one with the border of theme.
+    pub(crate) fn curve(t: &Theme) -> Self {
+        let n = t.border_width();
+        Self {
+            border: Sides::new(n, n, n, n),
+            ..Self::fill(t, t.blank_color())
+        }
+    }
+
+    pub(crate) fn with_attr(mut self, e: &Element) -> Self {
+        if let Some(o) = e.background() {
+            self.background = o.color();
+        }
+        if let Some(o) = e.border_color() {
+            self.border_color = o.color();
+        }
+        if let Some(o) = e.border_width() {
+            self.border = o.clone();
+        }
+        if let Some(o) = e.radius() {
+            self.radius = *o;
+        }
+        self
+    }
+
+    pub(crate) fn build(&self) -> AppearanceComposite {
+        let (c, r, b) = (self.background, self.radius, &self.border);
+        let (left, top, right, bottom) = (b.left(), b.top(), b.right(), b.bottom());
+        match (b.is_empty(), r > 0.0) {
+            (true, false) => Rectangle::new(c).into(),
+            (true, true) => RoundRectangle::new(c, r, r).into(),
+            (false, false) => RectangleCurve {
+                fill: c,
+                ..RectangleCurve::new(self.border_color, left, top, right, bottom)
+            }
+            .into(),
+            (false, true) => RoundRectCurve {
+                fill: c,
+                ..RoundRectCurve::new(self.border_color, r, r, left, top, right, bottom)
+            }
+            .into(),
+        }
+    }
 }
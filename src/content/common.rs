@@ -7,6 +7,7 @@ use skia_safe::codec::{
 use skia_safe::codecs::Decoder;
 use skia_safe::utils::text_utils::Align;
 use skia_safe::{EncodedImageFormat, Font, IRect, Image, Rect, SamplingOptions};
+use std::collections::HashSet;
 use std::io::Read;
 use std::ops::Add;
 use std::sync::Arc;
@@ -63,19 +64,75 @@ impl FixedRect {
         IRect::from(self)
     }
 
-    pub(crate) fn get_attr(&mut self, e: &Element, c: &mut LayoutCoord) {
+    ///Returns the rect inside `s`, the side is zero at least.
+    pub(crate) fn inset(&self, s: &Sides) -> Self {
+        let w = (self.side.width() - s.left() - s.right()).max(0.0);
+        let h = (self.side.height() - s.top() - s.bottom()).max(0.0);
+        Self::new(&self.pos + (s.left(), s.top()), RectSide::new(w, h))
+    }
+
+    ///`content` is the side which fits the content, for "auto" width or height.
+    pub(crate) fn get_attr(
+        &mut self,
+        e: &Element,
+        c: &mut LayoutCoord,
+        content: Option<&RectSide>,
+    ) {
+        let b = c.basis().of(e);
+        self.side.get_attr(e, c.upper_rect().side(), &b);
+        if let Some(o) = content {
+            let (w, h) = fit_side(e, c);
+            if w {
+                self.side.set_width(o.width());
+            }
+            if h {
+                self.side.set_height(o.height());
+            }
+        }
+        //the margin is a part of the space taken in layout, outside the element.
+        let m = e
+            .margin()
+            .map(|o| o.get(c.upper_rect().side(), &b))
+            .unwrap_or_default();
+        let outer = &self.side + (m.left() + m.right(), m.top() + m.bottom());
         if let Some(a) = e.position() {
-            self.pos = &c.upper_rect().pos + a;
+            self.pos = &c.upper_rect().pos + &a.get(c.upper_rect().side(), &b);
+        } else if c.is_flow() {
+            self.pos = c.next_flow(&outer);
         } else {
-            let a = e.ordinal().unwrap_or(&Ordinal::None);
-            if let Some(pos) = c.next(&a) {
-                self.pos = pos;
+            let span = (
+                e.column_span().copied().unwrap_or(1),
+                e.row_span().copied().unwrap_or(1),
+            );
+            let r = match e.place() {
+                Some(o) => c.area(o),
+                None => c.next(e.ordinal().unwrap_or(&Ordinal::None), span, &outer),
+            };
+            if let Some(r) = r {
+                //the cells covered decide the side unless it's set.
+                let r = r.inset(&Sides::new(
+                    0.0,
+                    0.0,
+                    m.left() + m.right(),
+                    m.top() + m.bottom(),
+                ));
+                let fill = e.place().is_some();
+                if (fill || e.column_span().is_some()) && e.width().is_none() {
+                    self.side.set_width(r.side.width());
+                }
+                if (fill || e.row_span().is_some()) && e.height().is_none() {
+                    self.side.set_height(r.side.height());
+                }
+                self.pos = r.pos;
             } else {
                 self.pos.set_x(f32::NAN);
                 self.pos.set_y(f32::NAN);
             }
         }
-        self.side.get_attr(e, c.upper_rect().side());
+        self.pos = &self.pos + (m.left(), m.top());
+        if let Some(z) = e.z() {
+            self.pos.set_z(c.upper_rect().pos.z() + z);
+        }
     }
 }
 
@@ -128,6 +185,18 @@ impl Add<((f32, f32, f32), (f32, f32))> for &FixedRect {
     }
 }
 
+///Returns whether the width and the height of element fit its content, they're "auto" or not set in flow layout.
+pub(crate) fn fit_side(e: &Element, c: &LayoutCoord) -> (bool, bool) {
+    let fit = |o: Option<&Length>| o.map_or(c.is_flow(), |o| o.is_auto());
+    (fit(e.width()), fit(e.height()))
+}
+
+///Returns true if the width or height of element fits its content.
+pub(crate) fn fits_content(e: &Element, c: &LayoutCoord) -> bool {
+    let (w, h) = fit_side(e, c);
+    w || h
+}
+
 impl From<Coord> for FixedRect {
     fn from(o: Coord) -> Self {
         Self::new(o, Default::default())
@@ -146,58 +215,270 @@ impl From<(f32, f32)> for FixedRect {
     }
 }
 
+//the subset laid out in a row or a column, it's measured at first and then placed.
+#[derive(Debug, Default)]
+struct FlowLayout {
+    flow: Flow,
+    gap: f32,
+    justify: Justify,
+    align: FlowAlign,
+    sides: Vec<RectSide>,
+    placed: Vec<Coord>,
+    n: usize,
+}
+
+impl FlowLayout {
+    //splits elements into lines, returns the ranges and the cross size of each one.
+    fn lines(&self, main: f32) -> Vec<(std::ops::Range<usize>, f32)> {
+        let mut r = Vec::new();
+        let (mut i, mut used, mut cross) = (0, 0.0, 0.0_f32);
+        for (j, o) in self.sides.iter().enumerate() {
+            let (m, c) = self.axis(o);
+            if self.flow.wrap() && j > i && used + self.gap + m > main {
+                r.push((i..j, cross));
+                (i, used, cross) = (j, 0.0, 0.0);
+            }
+            used += if j > i { self.gap + m } else { m };
+            cross = cross.max(c);
+        }
+        if i < self.sides.len() {
+            r.push((i..self.sides.len(), cross));
+        }
+        r
+    }
+
+    //the size on the main axis and on the cross axis.
+    fn axis(&self, o: &RectSide) -> (f32, f32) {
+        if self.flow.column() {
+            (o.height(), o.width())
+        } else {
+            (o.width(), o.height())
+        }
+    }
+
+    fn arrange(&mut self, rect: &FixedRect) {
+        let (main, _) = self.axis(rect.side());
+        let mut placed = Vec::with_capacity(self.sides.len());
+        let mut cross_at = 0.0;
+        for (range, cross) in self.lines(main) {
+            let v = &self.sides[range];
+            let used: f32 = v.iter().map(|o| self.axis(o).0).sum();
+            let free = main - used - self.gap * v.len().saturating_sub(1) as f32;
+            let (mut main_at, space) = self.justify.distribute(free, v.len(), self.gap);
+            for o in v {
+                let (m, c) = self.axis(o);
+                let c = cross_at + self.align.offset(cross, c);
+                let (x, y) = if self.flow.column() {
+                    (c, main_at)
+                } else {
+                    (main_at, c)
+                };
+                placed.push(Coord::new(rect.x() + x, rect.y() + y, rect.z()));
+                main_at += m + space;
+            }
+            cross_at += cross + self.gap;
+        }
+        self.placed = placed;
+        self.n = 0;
+    }
+}
+
 #[derive(Debug, Default, Getters)]
 pub(crate) struct LayoutCoord {
     x: Vec<f32>,
     x_n: usize,
+    //the end of the last column.
+    x_end: f32,
     y: Vec<f32>,
     y_n: usize,
+    y_end: f32,
+    columns: Option<Points>,
+    rows: Option<Points>,
+    //the sizes of the largest elements in each column and row, for auto tracks.
+    auto: (Vec<f32>, Vec<f32>),
+    //true until `arrange` is called, if the subset must be measured at first.
+    measuring: bool,
     #[getset(get = "pub(crate)")]
     upper_rect: FixedRect,
+    #[getset(get = "pub(crate)")]
+    basis: Basis,
+    //the basis of the element itself.
+    own_basis: Basis,
+    flow: Option<FlowLayout>,
+    areas: Option<Areas>,
+    //cells covered by elements placed already.
+    taken: HashSet<(usize, usize)>,
 }
 
 impl LayoutCoord {
-    pub(crate) fn get_attr(&mut self, e: &Element, rect: FixedRect) {
-        if let Some(a) = e.column() {
-            self.x = a.coord(rect.side().width(), rect.x());
+    ///`basis` is the one of page, which distances of the element and its subset refer to.
+    pub(crate) fn get_attr(&mut self, e: &Element, rect: FixedRect, basis: Basis) {
+        self.flow = e.flow().map(|o| FlowLayout {
+            flow: o.clone(),
+            gap: e.gap().copied().unwrap_or_default(),
+            justify: e.justify().copied().unwrap_or_default(),
+            align: e.align().copied().unwrap_or_default(),
+            ..Default::default()
+        });
+        self.columns = e.column().cloned();
+        self.rows = e.row().cloned();
+        self.auto = Default::default();
+        let auto = [&self.columns, &self.rows]
+            .iter()
+            .any(|o| o.as_ref().is_some_and(|o| o.has_auto()));
+        self.measuring = auto || self.flow.is_some();
+        self.areas = e.areas().cloned();
+        self.basis = basis;
+        self.own_basis = basis.of(e);
+        //the subset is laid out inside the border and the padding.
+        let mut inset = e.border_width().cloned().unwrap_or_default();
+        if let Some(o) = e.padding() {
+            inset = &inset + &o.get(rect.side(), &self.own_basis);
         }
+        self.upper_rect = rect.inset(&inset);
+        self.tracks();
+    }
+
+    //computes the start of each column and row, then starts from the first cell.
+    fn tracks(&mut self) {
+        let r = &self.upper_rect;
+        let b = &self.own_basis;
+        (self.x, self.x_end) = self
+            .columns
+            .as_ref()
+            .map(|a| a.coord(r.side().width(), r.x(), b, &self.auto.0))
+            .unwrap_or((Vec::new(), r.right()));
         if self.x.len() == 0 {
             self.x.push(0.0);
         }
-        if let Some(a) = e.row() {
-            self.y = a.coord(rect.side().height(), rect.y());
-        }
+        (self.y, self.y_end) = self
+            .rows
+            .as_ref()
+            .map(|a| a.coord(r.side().height(), r.y(), b, &self.auto.1))
+            .unwrap_or((Vec::new(), r.bottom()));
         if self.y.len() == 0 {
             self.y.push(0.0);
         }
-        self.upper_rect = rect;
+        self.x_n = 0;
+        self.y_n = 0;
+        self.taken.clear();
+    }
+
+    pub(crate) fn is_flow(&self) -> bool {
+        self.flow.is_some()
+    }
+
+    ///Returns true if the subset must be resized once to be measured, then `arrange` is called before it's laid out.
+    pub(crate) fn needs_measure(&self) -> bool {
+        self.measuring
+    }
+
+    //the position of the next element in flow, the sizes are recorded until `arrange` is called.
+    fn next_flow(&mut self, side: &RectSide) -> Coord {
+        let o = option_return!(self.flow.as_mut(), self.upper_rect.pos.clone());
+        if self.measuring {
+            o.sides.push(side.clone());
+            return self.upper_rect.pos.clone();
+        }
+        o.n += 1;
+        o.placed
+            .get(o.n - 1)
+            .cloned()
+            .unwrap_or(self.upper_rect.pos.clone())
     }
 
-    pub(crate) fn next(&mut self, ordinal: &Ordinal) -> Option<Coord> {
-        match ordinal {
+    ///Places the measured elements of flow layout, or sizes auto tracks by the measured elements,
+    ///each element gets its position in the same order then.
+    pub(crate) fn arrange(&mut self) {
+        if let Some(o) = self.flow.as_mut() {
+            o.arrange(&self.upper_rect);
+        }
+        self.measuring = false;
+        self.tracks();
+    }
+
+    ///Returns the cells covered by the next element, `span` is the number of columns and rows.
+    pub(crate) fn next(
+        &mut self,
+        ordinal: &Ordinal,
+        span: (usize, usize),
+        side: &RectSide,
+    ) -> Option<FixedRect> {
+        let (x_n, y_n) = match ordinal {
             Ordinal::Number(i) => {
                 let n = self.x.len();
-                self.next_xy(i % n, i / n)
+                (i % n, i / n)
+            }
+            Ordinal::X(x) => (*x, self.y_n),
+            Ordinal::Y(y) => (self.x_n, *y),
+            Ordinal::XY(x, y) => (*x, *y),
+            Ordinal::None => {
+                //skips cells covered by spanning elements.
+                while self.y_n < self.y.len() && self.taken.contains(&(self.x_n, self.y_n)) {
+                    self.move_to(self.x_n, self.y_n, 1);
+                }
+                (self.x_n, self.y_n)
             }
-            Ordinal::X(x) => self.next_xy(*x, self.y_n),
-            Ordinal::Y(y) => self.next_xy(self.x_n, *y),
-            Ordinal::XY(x, y) => self.next_xy(*x, *y),
-            Ordinal::None => self.next_xy(self.x_n, self.y_n),
+        };
+        if self.measuring {
+            self.measure(x_n, y_n, span, side);
         }
+        self.next_xy(x_n, y_n, span)
     }
 
-    fn next_xy(&mut self, x_n: usize, y_n: usize) -> Option<Coord> {
-        let x = self.x.get(x_n)?;
-        self.y.get(y_n).map(|y| {
-            if x_n + 1 == self.x.len() {
-                self.y_n = y_n + 1;
-                self.x_n = 0;
-            } else {
-                self.y_n = y_n;
-                self.x_n = x_n + 1;
+    //records the side of an element which is in one column or one row.
+    fn measure(&mut self, x_n: usize, y_n: usize, span: (usize, usize), side: &RectSide) {
+        let grow = |v: &mut Vec<f32>, n: usize, o: f32| {
+            if v.len() <= n {
+                v.resize(n + 1, 0.0);
             }
-            Coord::new(*x, *y, self.upper_rect.z())
-        })
+            v[n] = v[n].max(o);
+        };
+        if span.0 == 1 {
+            grow(&mut self.auto.0, x_n, side.width());
+        }
+        if span.1 == 1 {
+            grow(&mut self.auto.1, y_n, side.height());
+        }
+    }
+
+    ///Returns the cells covered by the named area.
+    pub(crate) fn area(&mut self, name: &str) -> Option<FixedRect> {
+        let (x_n, y_n, w, h) = self.areas.as_ref()?.find(name)?;
+        self.cells(x_n, y_n, w, h)
+    }
+
+    fn next_xy(&mut self, x_n: usize, y_n: usize, span: (usize, usize)) -> Option<FixedRect> {
+        let r = self.cells(x_n, y_n, span.0, span.1)?;
+        self.move_to(x_n, y_n, span.0);
+        Some(r)
+    }
+
+    fn move_to(&mut self, x_n: usize, y_n: usize, w: usize) {
+        if x_n + w >= self.x.len() {
+            self.y_n = y_n + 1;
+            self.x_n = 0;
+        } else {
+            self.y_n = y_n;
+            self.x_n = x_n + w;
+        }
+    }
+
+    //the cells are cut off at the last column and row.
+    fn cells(&mut self, x_n: usize, y_n: usize, w: usize, h: usize) -> Option<FixedRect> {
+        let x = *self.x.get(x_n)?;
+        let y = *self.y.get(y_n)?;
+        let x_end = (x_n + w).min(self.x.len());
+        let y_end = (y_n + h).min(self.y.len());
+        for i in x_n..x_end {
+            for j in y_n..y_end {
+                self.taken.insert((i, j));
+            }
+        }
+        let right = self.x.get(x_end).copied().unwrap_or(self.x_end);
+        let bottom = self.y.get(y_end).copied().unwrap_or(self.y_end);
+        let pos = Coord::new(x, y, self.upper_rect.z());
+        Some(FixedRect::new(pos, RectSide::new(right - x, bottom - y)))
     }
 }
 
@@ -235,6 +516,24 @@ impl ApplyFont {
         })
     }
 
+    ///Builds from the font attributes of an element, the font of theme is used if it has none.
+    pub(crate) fn from_attr(e: &Element, t: &Theme) -> Self {
+        let mut o = Self::default();
+        o.color = e.color().map(|c| c.color());
+        let family = e.font_family().or(t.font_family().as_ref());
+        let size = e.font_size().copied().or(t.font_size());
+        if family.is_none() && size.is_none() && e.font_weight().is_none() {
+            return o;
+        }
+        let family = family.map_or("", |s| s.as_str());
+        let weight = e.font_weight().copied().unwrap_or(400);
+        if let Some((name, font)) = get_applied_style(family, weight, size) {
+            o.name = name;
+            o.font = font;
+        }
+        o
+    }
+
     pub(crate) fn text_size(&self, text: &str, paint: &Paint) -> Rect {
         let text_size = self.font.measure_str(text, Some(paint));
         text_size.1
@@ -322,6 +621,8 @@ pub(crate) struct DrawText {
     align_pattern: AlignPattern,
     #[getset(get = "pub(crate)", get_mut = "pub(crate)")]
     apply_font: ApplyFont,
+    //the "padding" of element, between the border and text.
+    padding: Option<Sides>,
     time_meter: Chronograph,
     interval: f32,
 }
@@ -332,20 +633,46 @@ impl Default for DrawText {
             cursor: false,
             align_pattern: Default::default(),
             apply_font: Default::default(),
+            padding: None,
             time_meter: Chronograph::new(1000),
             interval: 20.0,
         }
     }
 }
 
+//the space between text and border of an element which fits its text, unless "padding" is set.
+const TEXT_PADDING: f32 = 6.0;
+
 impl DrawText {
+    ///Reads the padding of element, percentages refer to the side of the upper in `c`.
+    pub(crate) fn pad(&mut self, e: &Element, c: &LayoutCoord) {
+        let b = c.basis().of(e);
+        self.padding = e.padding().map(|o| o.get(c.upper_rect().side(), &b));
+    }
+
+    ///Returns the rect where text is, inside the padding.
+    pub(crate) fn text_rect(&self, rect: &FixedRect) -> FixedRect {
+        match &self.padding {
+            Some(o) => rect.inset(o),
+            None => rect.clone(),
+        }
+    }
+
+    ///Returns the side which fits the text, with the border of `painter` and the padding.
+    pub(crate) fn fit(&self, text: &str, painter: &AppearanceComposite) -> RectSide {
+        let w = self.apply_font.text_size(text, &Paint::default()).width();
+        let h = self.apply_font.font().spacing();
+        let n = TEXT_PADDING;
+        let p = self.padding.clone().unwrap_or(Sides::new(n, n, n, n));
+        let b = &painter.border() + &p;
+        RectSide::new(w + b.left() + b.right(), h + b.top() + b.bottom())
+    }
+
     pub(crate) fn draw(&mut self, rect: &FixedRect, text: &str, dcx: &mut DrawCtx) {
+        let rect = &self.text_rect(rect);
         let paint = &mut dcx.paint;
-        if let Some(color) = self.apply_font.color() {
-            paint.set_color(*color);
-        } else {
-            paint.set_color(*default_font_color());
-        }
+        let color = self.apply_font.color().unwrap_or(dcx.theme.font_color());
+        paint.set_color(color);
 
         let font = self.apply_font.font();
         let size = self.apply_font.text_size(text, &dcx.paint);
@@ -372,7 +699,7 @@ impl DrawText {
                 let paint = &mut dcx.paint;
                 let point0 = &c + (text_w + 2.0, (self.interval - text_h / 2.0) / 2.0);
                 let point1 = &point0 + (0.0, -self.interval);
-                paint.set_color(*default_cursor_color());
+                paint.set_color(dcx.theme.cursor_color());
                 dcx.surface.canvas().draw_line(point0, point1, paint);
             }
         }
@@ -400,10 +727,6 @@ pub(crate) enum ScrollBarType {
 #[derive(Debug, CopyGetters, Getters, MutGetters, Setters)]
 pub(crate) struct ScrollBar {
     scroll_bar_type: ScrollBarType,
-    #[getset(set = "pub(crate)")]
-    painter: AppearanceComposite,
-    #[getset(set = "pub(crate)")]
-    scroll_bar: AppearanceComposite,
     hor_show: bool,
     hor_rect: FixedRect,
     hor_f_offset: f32,
@@ -421,8 +744,6 @@ impl Default for ScrollBar {
     fn default() -> Self {
         Self {
             scroll_bar_type: ScrollBarType::Both,
-            painter: Rectangle::new(*default_scroll_bar2_color()).into(),
-            scroll_bar: Rectangle::new(*default_scroll_bar_color()).into(),
             hor_show: false,
             hor_rect: (0.0, 20.0).into(),
             hor_f_offset: 0.0,
@@ -529,24 +850,24 @@ impl ScrollBar {
     fn ver_draw(&mut self, dcx: &mut DrawCtx) {
         let rect = &self.ver_rect;
         if self.ver_show && !rect.is_empty() {
-            self.painter.draw(rect, dcx);
+            Rectangle::new(dcx.theme.scroll_bar2_color()).draw(rect, dcx);
             let r = FixedRect::new(
                 &**rect + (0.0, self.ver_f_offset),
                 RectSide::new(rect.side().width(), self.ver_f_length),
             );
-            self.scroll_bar.draw(&r, dcx);
+            Rectangle::new(dcx.theme.scroll_bar_color()).draw(&r, dcx);
         }
     }
 
     fn hor_draw(&mut self, dcx: &mut DrawCtx) {
         let rect = &self.hor_rect;
         if self.hor_show && !rect.is_empty() {
-            self.painter.draw(rect, dcx);
+            Rectangle::new(dcx.theme.scroll_bar2_color()).draw(rect, dcx);
             let r = FixedRect::new(
                 &**rect + (self.hor_f_offset, 0.0),
                 RectSide::new(self.hor_f_length, rect.side().height()),
             );
-            self.scroll_bar.draw(&r, dcx);
+            Rectangle::new(dcx.theme.scroll_bar_color()).draw(&r, dcx);
         }
     }
 
@@ -632,6 +953,17 @@ pub(super) fn get_image(rect: &RectSide, s: &str) -> Option<Image> {
     }
 }
 
+///Returns the natural side of an image file, without decoding it.
+pub(super) fn image_size(s: &str) -> Option<RectSide> {
+    let (w, h) = ImageReader::open(s)
+        .ok()?
+        .with_guessed_format()
+        .ok()?
+        .into_dimensions()
+        .ok()?;
+    Some(RectSide::new(w as f32, h as f32))
+}
+
 fn get_sk_format(f: ImageFormat) -> Option<EncodedImageFormat> {
     match f {
         ImageFormat::Avif => Some(EncodedImageFormat::AVIF),
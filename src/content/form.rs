@@ -6,43 +6,60 @@ pub(crate) struct Button {
     element: ElementKey,
     rect: FixedRect,
     painter: AppearanceComposite,
+    look: Look,
+    //the look while pressed.
+    look2: Look,
     draw_text: DrawText,
-    time_meter: Chronograph,
-    f: bool,
+    pressed: Option<Coord2D>,
 }
 
 impl Button {
-    pub(crate) fn new(element: ElementKey, eh: &ElementHolder) -> Self {
+    pub(crate) fn new(element: ElementKey, cx: &PageContext) -> Self {
+        let t = cx.theme();
+        let look = Look::round(t, t.button_color());
+        let look2 = Look::round(t, t.button2_color());
         Self {
             element,
             rect: (100.0, 60.0).into(),
-            painter: RoundRectangle {
-                color: *default_button_color(),
-                ..Default::default()
-            }
-            .into(),
+            painter: look.build(),
+            look,
+            look2,
             draw_text: AlignPattern::center_middle().into(),
-            time_meter: Chronograph::new(1000),
-            f: false,
+            pressed: None,
         }
     }
 
-    resize!();
+    resize!(self, e, c, cx => {
+        self.draw_text.pad(e, c);
+        let s = cx.text(self.element).unwrap_or_default();
+        fits_content(e, c).then(|| self.draw_text.fit(s, &self.painter))
+    });
+
+    //a button with background is darkened while pressed.
+    pub(crate) fn restyle(&mut self, e: &Element, t: &Theme) {
+        self.look = Look::round(t, t.button_color()).with_attr(e);
+        self.look2 = self.look.clone();
+        self.look2.background = match e.background() {
+            Some(o) => {
+                let c = o.color();
+                let f = |n: u8| (n as f32 * 0.8) as u8;
+                Color::from_argb(c.a(), f(c.r()), f(c.g()), f(c.b()))
+            }
+            None => t.button2_color(),
+        };
+        self.painter = if e.state().pressed() {
+            self.look2.build()
+        } else {
+            self.look.build()
+        };
+        *self.draw_text.apply_font_mut() = ApplyFont::from_attr(e, t);
+    }
 
     right_bottom!();
 
     pub(crate) fn draw(&mut self, dcx: &mut DrawCtx, cx: &mut PageContext) {
         draw_check!(self, cx);
 
-        if self.f && self.time_meter.elapsed() {
-            self.painter = RoundRectangle {
-                color: *default_button_color(),
-                ..Default::default()
-            }
-            .into();
-            self.f = false;
-        }
-
         self.painter.draw(&self.rect, dcx);
 
         if let Some(s) = cx.text(self.element) {
@@ -54,25 +71,23 @@ impl Button {
         match &acx.kind {
             ActionKind::Click(c, _) | ActionKind::Pressed(c, _) => {
                 if self.painter.within(&self.rect, c) {
-                    self.f = true;
-                    self.painter = RoundRectangle {
-                        color: *default_button2_color(),
-                        ..Default::default()
-                    }
-                    .into();
-                    self.time_meter.refresh();
+                    let c = c.clone();
                     acx.finish = true;
+                    if let Some(true) = cx.get(self.element).and_then(|e| e.disabled()) {
+                        return;
+                    }
+                    acx.focus(self.element, &c, cx);
+                    if let ActionKind::Click(..) = acx.kind {
+                        acx.push_event(EventKind::Click, self.element, Some(c));
+                    } else {
+                        self.pressed.replace(c);
+                    }
                     return;
                 }
             }
             ActionKind::Released(_) => {
-                if self.f {
-                    self.painter = RoundRectangle {
-                        color: *default_button_color(),
-                        ..Default::default()
-                    }
-                    .into();
-                    self.f = false;
+                if let Some(c) = released_over(&mut self.pressed, self.element, cx) {
+                    acx.push_event(EventKind::Click, self.element, Some(c));
                 }
             }
             _ => {}
@@ -80,6 +95,17 @@ impl Button {
     }
 }
 
+//a press is clicked only if the cursor is still over the element when it's released,
+//the hover state is tracked from the cursor and sweep actions.
+fn released_over(
+    pressed: &mut Option<Coord2D>,
+    element: ElementKey,
+    cx: &PageContext,
+) -> Option<Coord2D> {
+    let c = pressed.take()?;
+    cx.get(element)?.state().hover().then_some(c)
+}
+
 ///"Inp" represents input.
 #[derive(Debug)]
 pub(crate) struct Inp {
@@ -88,28 +114,27 @@ pub(crate) struct Inp {
     painter: AppearanceComposite,
     draw_text: DrawText,
     ops: Vec<Opt>,
-    f: bool,
 }
 
 impl Inp {
-    pub(crate) fn new(element: ElementKey, eh: &ElementHolder) -> Self {
-        let v = eh.subset_with_mark(element, Mark::OPTION);
+    pub(crate) fn new(element: ElementKey, cx: &PageContext) -> Self {
+        let v = cx.subset_with_mark(element, Mark::OPTION);
         let ops = v.into_iter().map(|o| Opt::new(o)).collect();
         Self {
             element,
             rect: (100.0, 30.0).into(),
-            painter: RectangleCurve {
-                color: *default_border_color(),
-                ..Default::default()
-            }
-            .into(),
+            painter: Look::curve(cx.theme()).build(),
             draw_text: Default::default(),
             ops,
-            f: false,
         }
     }
 
-    resize!();
+    resize!(self, e, c, cx => {
+        self.draw_text.pad(e, c);
+        fits_content(e, c).then(|| self.draw_text.fit(e.get_value_or_text(), &self.painter))
+    });
+
+    restyle!(t => Look::curve(t), draw_text);
 
     right_bottom!();
 
@@ -127,35 +152,39 @@ impl Inp {
         match &acx.kind {
             ActionKind::Click(c, _) | ActionKind::DoubleClick(c, _) | ActionKind::Pressed(c, _) => {
                 if self.painter.within(&self.rect, c) {
-                    self.f = true;
+                    let c = c.clone();
+                    acx.focus(self.element, &c, cx);
                     self.draw_text.set_cursor(true);
                     acx.finish = true;
                     return;
                 } else {
-                    self.f = false;
                     self.draw_text.set_cursor(false);
                 }
             }
             ActionKind::InputStr(s) => {
-                if self.f {
+                if cx.focused() == Some(self.element) {
                     if s.len() > 0 {
                         if let Some(a) = cx.get_mut(self.element).and_then(|e| e.value_or_insert())
                         {
                             a.push_str(&s);
+                            acx.push_event(EventKind::Change, self.element, None);
                         }
                     }
                     acx.finish = true;
                     return;
                 }
             }
-            ActionKind::DeleteFront(n) => {
-                if self.f {
-                    acx.finish = true;
-                    return;
-                }
-            }
-            ActionKind::DeleteBack(n) => {
-                if self.f {
+            ActionKind::DeleteFront(n) | ActionKind::DeleteBack(n) => {
+                if cx.focused() == Some(self.element) {
+                    let n = *n;
+                    if let Some(a) = cx.get_mut(self.element).and_then(|e| e.value_or_insert()) {
+                        if !a.is_empty() {
+                            for _ in 0..n {
+                                a.pop();
+                            }
+                            acx.push_event(EventKind::Change, self.element, None);
+                        }
+                    }
                     acx.finish = true;
                     return;
                 }
@@ -204,21 +233,23 @@ pub(crate) struct Pt {
 }
 
 impl Pt {
-    pub(crate) fn new(element: ElementKey, eh: &ElementHolder) -> Self {
+    pub(crate) fn new(element: ElementKey, cx: &PageContext) -> Self {
         Self {
             element,
             rect: (100.0, 100.0).into(),
-            painter: RectangleCurve {
-                color: *default_border_color(),
-                ..Default::default()
-            }
-            .into(),
+            painter: Look::curve(cx.theme()).build(),
             draw_text: Default::default(),
             scroll_bar: Default::default(),
         }
     }
 
-    resize!();
+    resize!(self, e, c, cx => {
+        self.draw_text.pad(e, c);
+        let s = cx.text(self.element).unwrap_or_default();
+        fits_content(e, c).then(|| self.draw_text.fit(s, &self.painter))
+    });
+
+    restyle!(t => Look::curve(t), draw_text);
 
     right_bottom!();
 
@@ -228,13 +259,16 @@ impl Pt {
         self.painter.draw(&self.rect, dcx);
 
         if let Some(s) = cx.text(self.element) {
-            dcx.paint.set_color(*default_font_color());
             let dt = &self.draw_text;
+            let color = dt.apply_font().color().unwrap_or(dcx.theme.font_color());
+            dcx.paint.set_color(color);
             let size = dt.apply_font().text_size(s, &dcx.paint);
             let h = self.rect.side().height().max(size.height());
             let max = (size.width(), h).into();
             let vision = self.scroll_bar.resize(&self.rect, &max);
-            let (c, a) = dt.align_pattern().font_xy(&self.rect, size.height());
+            let (c, a) = dt
+                .align_pattern()
+                .font_xy(&dt.text_rect(&self.rect), size.height());
             let font = dt.apply_font().font();
             dcx.draw_in_vision(&vision, &self.rect, |surface2, paint| {
                 surface2.canvas().draw_str_align(s, &c, font, paint, a);
@@ -268,27 +302,31 @@ pub(crate) struct Select {
     draw_text: DrawText,
     scroll_bar: ScrollBar,
     ops: Vec<Opt>,
+    pressed: Option<Coord2D>,
 }
 
 impl Select {
-    pub(crate) fn new(element: ElementKey, eh: &ElementHolder) -> Self {
-        let v = eh.subset_with_mark(element, Mark::OPTION);
+    pub(crate) fn new(element: ElementKey, cx: &PageContext) -> Self {
+        let v = cx.subset_with_mark(element, Mark::OPTION);
         let ops = v.into_iter().map(|o| Opt::new(o)).collect();
         Self {
             element,
             rect: (100.0, 100.0).into(),
-            painter: RectangleCurve {
-                color: *default_border_color(),
-                ..Default::default()
-            }
-            .into(),
+            painter: Look::curve(cx.theme()).build(),
             draw_text: Default::default(),
             scroll_bar: Default::default(),
             ops,
+            pressed: None,
         }
     }
 
-    resize!();
+    resize!(self, e, c, cx => {
+        self.draw_text.pad(e, c);
+        let s = cx.text(self.element).unwrap_or_default();
+        fits_content(e, c).then(|| self.draw_text.fit(s, &self.painter))
+    });
+
+    restyle!(t => Look::curve(t), draw_text);
 
     right_bottom!();
 
@@ -306,16 +344,51 @@ impl Select {
         match &acx.kind {
             ActionKind::Click(c, _) | ActionKind::DoubleClick(c, _) | ActionKind::Pressed(c, _) => {
                 if self.painter.within(&self.rect, c) {
+                    let c = c.clone();
                     self.draw_text.set_cursor(true);
                     acx.finish = true;
+                    if let Some(true) = cx.get(self.element).and_then(|e| e.disabled()) {
+                        return;
+                    }
+                    acx.focus(self.element, &c, cx);
+                    if let ActionKind::Pressed(..) = acx.kind {
+                        self.pressed.replace(c);
+                    } else {
+                        self.select_next(acx, cx);
+                    }
                     return;
                 } else {
                     self.draw_text.set_cursor(false);
                 }
             }
+            ActionKind::Released(_) => {
+                if released_over(&mut self.pressed, self.element, cx).is_some() {
+                    self.select_next(acx, cx);
+                }
+            }
             _ => {}
         }
     }
+
+    //selects the option after the selected one, or the first one after the last.
+    fn select_next(&mut self, acx: &mut ActionCtx, cx: &mut PageContext) {
+        if self.ops.is_empty() {
+            return;
+        }
+        let i = self
+            .ops
+            .iter()
+            .position(|o| cx.get(o.element).and_then(|e| e.selected()) == Some(&true));
+        let n = i.map_or(0, |i| (i + 1) % self.ops.len());
+        if i == Some(n) {
+            return;
+        }
+        if let Some(i) = i {
+            cx.remove_attribute(self.ops[i].element, &AttrName::SELECTED);
+        }
+        cx.set_attribute(self.ops[n].element, Attribute::SELECTED(true));
+        acx.push_event(EventKind::Change, self.element, None);
+    }
 }
 
 ///"Time" represents date time.
@@ -328,20 +401,22 @@ pub(crate) struct Time {
 }
 
 impl Time {
-    pub(crate) fn new(element: ElementKey, eh: &ElementHolder) -> Self {
+    pub(crate) fn new(element: ElementKey, cx: &PageContext) -> Self {
         Self {
             element,
             rect: (100.0, 100.0).into(),
-            painter: Rectangle {
-                color: *default_surface_color(),
-                ..Default::default()
-            }
-            .into(),
+            painter: Look::fill(cx.theme(), cx.theme().surface_color()).build(),
             draw_text: Default::default(),
         }
     }
 
-    resize!();
+    resize!(self, e, c, cx => {
+        self.draw_text.pad(e, c);
+        let s = cx.text(self.element).unwrap_or_default();
+        fits_content(e, c).then(|| self.draw_text.fit(s, &self.painter))
+    });
+
+    restyle!(t => Look::fill(t, t.surface_color()), draw_text);
 
     right_bottom!();
 
@@ -13,15 +13,11 @@ pub(crate) struct Audio {
 }
 
 impl Audio {
-    pub(crate) fn new(element: ElementKey, eh: &ElementHolder) -> Self {
+    pub(crate) fn new(element: ElementKey, cx: &PageContext) -> Self {
         Self {
             element,
             rect: (1000.0, 100.0).into(),
-            painter: Rectangle {
-                color: *default_surface_color(),
-                ..Default::default()
-            }
-            .into(),
+            painter: Look::fill(cx.theme(), cx.theme().surface_color()).build(),
             align_pattern: AlignPattern::center_middle(),
             reader: None,
             control: Default::default(),
@@ -30,11 +26,14 @@ impl Audio {
 
     pub(crate) fn resize(&mut self, c: &mut LayoutCoord, cx: &mut PageContext) {
         if let Some(e) = cx.get(self.element) {
-            self.rect.get_attr(&e, c);
+            self.rect.get_attr(&e, c, None);
             self.control.resize(&self.rect);
+            self.restyle(e, cx.theme());
         }
     }
 
+    restyle!(t => Look::fill(t, t.surface_color()));
+
     right_bottom!();
 
     pub(crate) fn draw(&mut self, dcx: &mut DrawCtx, cx: &mut PageContext) {
@@ -118,25 +117,37 @@ pub(crate) struct Img {
     align_pattern: AlignPattern,
     scroll_bar: ScrollBar,
     buffer: Option<Image>,
+    //the natural side of the image and the source it's read from.
+    natural: Option<(String, Option<RectSide>)>,
 }
 
 impl Img {
-    pub(crate) fn new(element: ElementKey, eh: &ElementHolder) -> Self {
+    pub(crate) fn new(element: ElementKey, cx: &PageContext) -> Self {
         Self {
             element,
             rect: (100.0, 100.0).into(),
-            painter: RectangleCurve {
-                color: *default_border_color(),
-                ..Default::default()
-            }
-            .into(),
+            painter: Look::curve(cx.theme()).build(),
             align_pattern: AlignPattern::center_middle(),
             scroll_bar: Default::default(),
             buffer: None,
+            natural: None,
         }
     }
 
-    resize!();
+    resize!(self, e, c, cx => cx
+        .text(self.element)
+        .filter(|_| fits_content(e, c))
+        .and_then(|s| self.natural_side(s)));
+
+    //the file is read again only if the source changed.
+    fn natural_side(&mut self, s: &str) -> Option<RectSide> {
+        if self.natural.as_ref().is_none_or(|o| o.0 != s) {
+            self.natural.replace((s.to_string(), image_size(s)));
+        }
+        self.natural.as_ref().and_then(|o| o.1.clone())
+    }
+
+    restyle!(t => Look::curve(t));
 
     right_bottom!();
 
@@ -188,14 +199,11 @@ pub(crate) struct Video {
 }
 
 impl Video {
-    pub(crate) fn new(element: ElementKey, eh: &ElementHolder) -> Self {
+    pub(crate) fn new(element: ElementKey, cx: &PageContext) -> Self {
         Self {
             element,
             rect: (100.0, 100.0).into(),
-            painter: Rectangle {
-                color: Color::from_rgb(0, 0, 0),
-            }
-            .into(),
+            painter: Look::fill(cx.theme(), Color::from_rgb(0, 0, 0)).build(),
             align_pattern: AlignPattern::center_middle(),
             reader: None,
             control: Default::default(),
@@ -204,11 +212,14 @@ impl Video {
 
     pub(crate) fn resize(&mut self, c: &mut LayoutCoord, cx: &mut PageContext) {
         if let Some(e) = cx.get(self.element) {
-            self.rect.get_attr(&e, c);
+            self.rect.get_attr(&e, c, None);
             self.control.resize(&self.rect);
+            self.restyle(e, cx.theme());
         }
     }
 
+    restyle!(t => Look::fill(t, Color::from_rgb(0, 0, 0)));
+
     right_bottom!();
 
     pub(crate) fn draw(&mut self, dcx: &mut DrawCtx, cx: &mut PageContext) {
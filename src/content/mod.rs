@@ -15,6 +15,10 @@ use crate::page::*;
 use crate::utils::*;
 use skia_safe::{Paint, Surface};
 use slotmap::{SlotMap, new_key_type};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::mem::take;
+use std::sync::Arc;
 
 pub(crate) struct Body {
     subset: Vec<DrawUnitKey>,
@@ -24,6 +28,7 @@ pub(crate) struct Body {
     layout: LayoutCoord,
     scroll_bar: ScrollBar,
     dh: DrawUnitHolder,
+    viewport: (f32, f32),
 }
 
 impl std::fmt::Debug for Body {
@@ -49,26 +54,27 @@ impl Body {
             layout: Default::default(),
             scroll_bar: Default::default(),
             dh: Default::default(),
+            viewport: (100.0, 100.0),
         };
-        o.build_subset(&cx, cx.body_key());
+        o.build_subset(cx, cx.body_key());
         o
     }
 
-    pub(crate) fn build_subset(&mut self, eh: &ElementHolder, key: ElementKey) {
-        if let Some(e) = eh.get(key) {
+    pub(crate) fn build_subset(&mut self, cx: &PageContext, key: ElementKey) {
+        if let Some(e) = cx.get(key) {
             for &k in e.subset() {
-                if let Some(e) = eh.get(k) {
-                    if let Some(o) = DrawUnit::new(k, e.mark_type(), eh) {
+                if let Some(e) = cx.get(k) {
+                    if let Some(o) = DrawUnit::new(k, e.mark_type(), cx) {
                         let dk = self.dh.insert(o);
                         self.subset.push(dk);
                         match self.dh[dk] {
                             DrawUnit::AREA(_) => {
-                                self.dh.build(eh, k, dk);
+                                self.dh.build(cx, k, dk);
                             }
                             _ => {}
                         }
                     } else {
-                        self.build_subset(eh, k);
+                        self.build_subset(cx, k);
                     }
                 }
             }
@@ -76,11 +82,16 @@ impl Body {
     }
 
     pub(crate) fn resize(&mut self, w: f32, h: f32, cx: &mut PageContext) {
+        self.viewport = (w, h);
         let k = RectSide::new(w, h);
         self.rect.set_side(k.clone());
         if let Some(e) = cx.body_element() {
-            self.rect.side_mut().get_attr(e, &k);
-            self.layout.get_attr(e, self.rect.clone());
+            let t = cx.theme();
+            let em = t.font_size().unwrap_or(default_font().size());
+            let b = Basis::new(w, h, em);
+            self.rect.side_mut().get_attr(e, &k, &b.of(e));
+            self.layout.get_attr(e, self.rect.clone(), b);
+            self.painter = Look::fill(t, t.bg_color()).with_attr(e).build();
         }
         self.dh.resize(&mut self.layout, &self.subset, cx);
     }
@@ -91,18 +102,73 @@ impl Body {
         self.resize(w, h, cx);
     }
 
+    ///Brings draw units in line with the element tree after `key` or its subset changed,
+    ///then lays out the nearest container again. `renew` is an element whose draw unit must be created again.
+    pub(crate) fn sync(
+        &mut self,
+        key: ElementKey,
+        renew: Option<ElementKey>,
+        cx: &mut PageContext,
+    ) {
+        let mut k = key;
+        loop {
+            if k == cx.body_key() {
+                let mut subset = take(&mut self.subset);
+                self.dh.rebuild(cx, k, renew, &mut subset);
+                self.subset = subset;
+                let (w, h) = self.viewport;
+                self.resize(w, h, cx);
+                return;
+            }
+            //an area fitting its subset changes its side, so the upper is laid out instead.
+            if let Some(dk) = self.dh.find_area(k).filter(|&dk| !self.dh.is_fitted(dk)) {
+                let (mut subset, mut layout, rect) = match self.dh.get_mut(dk) {
+                    Some(DrawUnit::AREA(o)) => {
+                        (take(&mut o.subset), take(&mut o.layout), o.rect.clone())
+                    }
+                    _ => return,
+                };
+                self.dh.rebuild(cx, k, renew, &mut subset);
+                if let Some(e) = cx.get(k) {
+                    let b = *layout.basis();
+                    layout.get_attr(e, rect, b);
+                }
+                self.dh.resize(&mut layout, &subset, cx);
+                if let Some(DrawUnit::AREA(o)) = self.dh.get_mut(dk) {
+                    o.subset = subset;
+                    o.layout = layout;
+                }
+                return;
+            }
+            k = option_return!(cx.get(k).and_then(|e| *e.upper()));
+        }
+    }
+
+    ///Applies the changes recorded in page context.
+    pub(crate) fn sync_changes(&mut self, cx: &mut PageContext) {
+        for (k, renew) in cx.take_changes() {
+            self.sync(k, renew, cx);
+        }
+        for k in cx.take_restyles() {
+            self.dh.restyle(k, cx);
+        }
+    }
+
     pub(crate) fn draw(&mut self, mut dcx: DrawCtx, cx: &mut PageContext) {
         if self.rect.is_empty() {
             return;
         }
 
-        dcx.surface.canvas().clear(*default_bg_color());
+        let c = dcx.theme.bg_color();
+        dcx.surface.canvas().clear(c);
 
         self.painter.draw(&self.rect, &mut dcx);
         dcx.surface.canvas().save();
 
+        self.dh.overlay.clear();
         self.dh
             .draw_rect(&self.rect, &mut self.scroll_bar, &self.subset, &mut dcx, cx);
+        self.dh.draw_overlay(&mut dcx, cx);
         dcx.surface.canvas().save();
     }
 
@@ -136,56 +202,270 @@ impl Body {
             }
             _ => {}
         }
-        let (x, y) = self.scroll_bar.vision_var();
-        acx.kind.set_var_cursor(x, y);
-        self.dh.consume_action(&self.subset, &mut acx, cx);
+        self.dh.consume_overlay(&mut acx, cx);
+        if !acx.is_finished() {
+            let (x, y) = self.scroll_bar.vision_var();
+            acx.kind.set_var_cursor(x, y);
+            self.dh.consume_action(&self.subset, &mut acx, cx);
+        }
+        //a press which focuses nothing blurs the focused element.
+        if let ActionKind::Click(..) | ActionKind::DoubleClick(..) | ActionKind::Pressed(..) =
+            acx.kind
+        {
+            if !acx.focused {
+                acx.blur(cx);
+            }
+        }
+    }
+
+    ///Returns the layout of an element, None if it isn't drawn by itself.
+    pub(crate) fn layout_of(&self, key: ElementKey, cx: &PageContext) -> Option<Layout> {
+        let mut r = None;
+        self.visit(cx, &mut |k, _, l| {
+            if k == key {
+                r.get_or_insert(*l);
+            }
+        });
+        r
+    }
+
+    ///Writes the layout of body and the elements drawn, one line for each, indented by depth.
+    pub(crate) fn dump_layout(&self, cx: &PageContext) -> String {
+        let mut s = String::new();
+        self.visit(cx, &mut |k, depth, l| {
+            if let Some(e) = cx.get(k) {
+                let _ = write!(s, "{}{}", "  ".repeat(depth), e.mark_type().as_str());
+                if let Some(id) = e.id() {
+                    let _ = write!(s, "#{id}");
+                }
+                let _ = writeln!(s, " {l}");
+            }
+        });
+        s
+    }
+
+    //visits body and the draw units in subset order.
+    fn visit(&self, cx: &PageContext, f: &mut impl FnMut(ElementKey, usize, &Layout)) {
+        let r = &self.rect;
+        let l = Layout {
+            rect: r.into(),
+            relative: Bounds::new(0.0, 0.0, r.side().width(), r.side().height()),
+            clip: None,
+            scroll: self.scroll_bar.vision_var(),
+            z: r.z(),
+        };
+        f(cx.body_key(), 0, &l);
+        self.dh.visit(&self.subset, r, &l, 1, cx, f);
     }
 }
 
 #[derive(Default)]
 struct DrawUnitHolder {
     data: SlotMap<DrawUnitKey, DrawUnit>,
+    //units on the overlay layer met in the last drawing, with the scroll offset above them.
+    overlay: Vec<(DrawUnitKey, (f32, f32))>,
+    //the scroll offset of the subset being drawn.
+    offset: (f32, f32),
 }
 
 deref!(DrawUnitHolder, SlotMap<DrawUnitKey, DrawUnit>, data);
 
 impl DrawUnitHolder {
-    fn build(&mut self, eh: &ElementHolder, key: ElementKey, dkey: DrawUnitKey) {
-        if let Some(e) = eh.get(key) {
+    fn build(&mut self, cx: &PageContext, key: ElementKey, dkey: DrawUnitKey) {
+        if let Some(e) = cx.get(key) {
             for &k in e.subset() {
-                if let Some(h) = eh.get(k) {
-                    if let Some(o) = DrawUnit::new(k, h.mark_type(), eh) {
+                if let Some(h) = cx.get(k) {
+                    if let Some(o) = DrawUnit::new(k, h.mark_type(), cx) {
                         let dk = self.data.insert(o);
                         match &mut self.data[dkey] {
                             DrawUnit::AREA(o) => {
                                 o.subset.push(dk);
-                                self.build(eh, k, dk);
+                                self.build(cx, k, dk);
                             }
                             _ => {}
                         }
                     } else {
-                        self.build(eh, k, dkey);
+                        self.build(cx, k, dkey);
                     }
                 }
             }
         }
     }
 
-    fn resize(&mut self, c: &mut LayoutCoord, ks: &[DrawUnitKey], cx: &mut PageContext) {
-        let mut r = None;
+    //the look of a draw unit is built again without layout.
+    fn restyle(&mut self, key: ElementKey, cx: &PageContext) {
+        if let Some(o) = self.data.values_mut().find(|o| o.element() == key) {
+            o.restyle(cx);
+        }
+    }
+
+    fn is_fitted(&self, k: DrawUnitKey) -> bool {
+        matches!(self.data.get(k), Some(DrawUnit::AREA(o)) if o.fitted.is_some())
+    }
+
+    fn find_area(&self, key: ElementKey) -> Option<DrawUnitKey> {
+        self.data.iter().find_map(|(k, o)| match o {
+            DrawUnit::AREA(a) if a.element == key => Some(k),
+            _ => None,
+        })
+    }
+
+    //builds draw units of the element's subset again, keeps the units of elements which are still there.
+    fn rebuild(
+        &mut self,
+        cx: &PageContext,
+        key: ElementKey,
+        renew: Option<ElementKey>,
+        subset: &mut Vec<DrawUnitKey>,
+    ) {
+        let mut m = HashMap::new();
+        self.detach(subset, &mut m);
+        if let Some(dk) = renew.and_then(|k| m.remove(&k)) {
+            self.data.remove(dk);
+        }
+        subset.clear();
+        self.assemble(cx, key, &mut m, subset);
+        for (_, dk) in m {
+            self.data.remove(dk);
+        }
+    }
+
+    fn detach(&mut self, ks: &[DrawUnitKey], m: &mut HashMap<ElementKey, DrawUnitKey>) {
         for &k in ks {
             if let Some(o) = self.data.get_mut(k) {
-                o.resize(c, cx);
-                match o {
-                    DrawUnit::AREA(o) => {
-                        r.replace(&mut *o as *mut Area);
-                    }
-                    _ => {}
+                m.insert(o.element(), k);
+                if let DrawUnit::AREA(o) = o {
+                    let v = take(&mut o.subset);
+                    self.detach(&v, m);
+                }
+            }
+        }
+    }
+
+    fn assemble(
+        &mut self,
+        cx: &PageContext,
+        key: ElementKey,
+        m: &mut HashMap<ElementKey, DrawUnitKey>,
+        r: &mut Vec<DrawUnitKey>,
+    ) {
+        let e = option_return!(cx.get(key));
+        for &k in e.subset() {
+            let h = if let Some(h) = cx.get(k) {
+                h
+            } else {
+                continue;
+            };
+            let dk = if let Some(dk) = m.remove(&k) {
+                dk
+            } else if let Some(o) = DrawUnit::new(k, h.mark_type(), cx) {
+                self.data.insert(o)
+            } else {
+                self.assemble(cx, k, m, r);
+                continue;
+            };
+            r.push(dk);
+            if let Some(DrawUnit::AREA(_)) = self.data.get(dk) {
+                let mut v = Vec::new();
+                self.assemble(cx, k, m, &mut v);
+                if let Some(DrawUnit::AREA(o)) = self.data.get_mut(dk) {
+                    o.subset = v;
                 }
             }
-            if let Some(o) = r.take() {
-                let o = unsafe { &mut *o };
+        }
+    }
+
+    fn resize(&mut self, c: &mut LayoutCoord, ks: &[DrawUnitKey], cx: &mut PageContext) {
+        //a flow or auto tracks measure the subset at first, then place it.
+        let measured = c.needs_measure();
+        if measured {
+            for &k in ks {
+                self.resize_unit(k, c, false, cx);
+            }
+            c.arrange();
+        }
+        for &k in ks {
+            self.resize_unit(k, c, measured, cx);
+        }
+    }
+
+    //an area fitting its subset lays it out at first, `reuse` moves the subset laid out by the measuring pass instead.
+    fn resize_unit(
+        &mut self,
+        k: DrawUnitKey,
+        c: &mut LayoutCoord,
+        reuse: bool,
+        cx: &mut PageContext,
+    ) {
+        let o = match self.data.get_mut(k) {
+            Some(DrawUnit::AREA(o)) => &mut *o as *mut Area,
+            Some(o) => return o.resize(c, cx),
+            None => return,
+        };
+        let o = unsafe { &mut *o };
+        let (w, h) = option_return!(cx.get(o.element).map(|e| fit_side(e, c)));
+        if !w && !h {
+            o.fitted = None;
+            o.resize(c, cx, None);
+            if !c.needs_measure() {
+                self.resize(&mut o.layout, &o.subset, cx);
+            }
+            return;
+        }
+        let content = match o.fitted.clone().filter(|_| reuse) {
+            Some(o) => o,
+            None => {
+                //the fitted sides take all of the upper while the subset is laid out.
+                let mut r = o.rect.clone();
+                let b = *c.basis();
+                if let Some(e) = cx.get(o.element) {
+                    r.side_mut().get_attr(e, c.upper_rect().side(), &b.of(e));
+                    if w {
+                        r.side_mut().set_width(c.upper_rect().side().width());
+                    }
+                    if h {
+                        r.side_mut().set_height(c.upper_rect().side().height());
+                    }
+                    o.layout.get_attr(e, r.clone(), b);
+                }
                 self.resize(&mut o.layout, &o.subset, cx);
+                let inner = o.layout.upper_rect();
+                let rb = self
+                    .right_bottom(&o.subset, cx)
+                    .unwrap_or(Coord2D::new(inner.x(), inner.y()));
+                RectSide::new(
+                    rb.x() - r.x() + r.right() - inner.right(),
+                    rb.y() - r.y() + r.bottom() - inner.bottom(),
+                )
+            }
+        };
+        let from = (o.layout.upper_rect().x(), o.layout.upper_rect().y());
+        o.resize(c, cx, Some(&content));
+        o.fitted.replace(content);
+        let to = o.layout.upper_rect();
+        let d = (to.x() - from.0, to.y() - from.1);
+        self.translate(&o.subset, d);
+    }
+
+    //moves draw units and their subsets without laying them out again.
+    fn translate(&mut self, ks: &[DrawUnitKey], d: (f32, f32)) {
+        if d == (0.0, 0.0) {
+            return;
+        }
+        for &k in ks {
+            let o = if let Some(o) = self.data.get_mut(k) {
+                o
+            } else {
+                continue;
+            };
+            let r = o.rect_mut();
+            *r = &*r + d;
+            if let DrawUnit::AREA(o) = o {
+                let v = take(&mut o.subset);
+                self.translate(&v, d);
+                if let Some(DrawUnit::AREA(o)) = self.data.get_mut(k) {
+                    o.subset = v;
+                }
             }
         }
     }
@@ -204,9 +484,13 @@ impl DrawUnitHolder {
             let surface = &mut dcx.surface;
             let info = surface.image_info().with_dimensions(vision.right_bottom());
             if let Some(s) = surface.new_surface(&info) {
-                let mut d = DrawCtx::new(s);
+                let mut d = DrawCtx::new(s, dcx.theme.clone());
                 d.surface.canvas().clip_rect(vision.to_rect(), None, None);
+                let (x, y) = scroll_bar.vision_var();
+                let offset = self.offset;
+                self.offset = (offset.0 + x, offset.1 + y);
                 self.draw_subset(ks, &mut d, cx);
+                self.offset = offset;
 
                 if let Some(i) = d.surface.image_snapshot_with_bounds(vision.to_irect()) {
                     surface.canvas().draw_image(i, &***rect, None);
@@ -219,6 +503,10 @@ impl DrawUnitHolder {
     fn right_bottom(&mut self, ks: &[DrawUnitKey], cx: &mut PageContext) -> Option<Coord2D> {
         let mut c: Option<Coord2D> = None;
         for &k in ks {
+            //the overlay layer doesn't extend the scroll region.
+            if self.is_overlay(k, cx) {
+                continue;
+            }
             if let Some(r) = self.data.get(k).and_then(|o| o.right_bottom(cx)) {
                 if let Some(c) = c.as_mut() {
                     if c.x() < r.x() {
@@ -236,34 +524,177 @@ impl DrawUnitHolder {
     }
 
     fn draw_subset(&mut self, ks: &[DrawUnitKey], dcx: &mut DrawCtx, cx: &mut PageContext) {
+        for k in self.stacked(ks) {
+            //the overlay layer is drawn after all, out of the clip of the upper.
+            if self.is_overlay(k, cx) {
+                self.overlay.push((k, self.offset));
+                continue;
+            }
+            self.draw_unit(k, dcx, cx);
+        }
+    }
+
+    fn draw_unit(&mut self, k: DrawUnitKey, dcx: &mut DrawCtx, cx: &mut PageContext) {
         let mut r = None;
-        for &k in ks {
-            if let Some(o) = self.get_mut(k) {
-                o.draw(dcx, cx);
-                match o {
-                    DrawUnit::AREA(o) => {
-                        r.replace(&mut *o as *mut Area);
-                    }
-                    _ => {}
+        //a translucent unit is drawn with its subset into a layer.
+        let alpha = self
+            .get(k)
+            .and_then(|o| cx.get(o.element()))
+            .and_then(|e| e.opacity().copied())
+            .filter(|a| *a < 1.0);
+        if let Some(a) = alpha {
+            dcx.surface.canvas().save_layer_alpha_f(None, a);
+        }
+        if let Some(o) = self.get_mut(k) {
+            o.draw(dcx, cx);
+            match o {
+                DrawUnit::AREA(o) => {
+                    r.replace(&mut *o as *mut Area);
                 }
+                _ => {}
             }
-            if let Some(o) = r.take() {
-                let o = unsafe { &mut *o };
-                self.draw_rect(&o.rect, &mut o.scroll_bar, &o.subset, dcx, cx);
+        }
+        if let Some(o) = r.take() {
+            let o = unsafe { &mut *o };
+            self.draw_rect(&o.rect, &mut o.scroll_bar, &o.subset, dcx, cx);
+        }
+        if alpha.is_some() {
+            dcx.surface.canvas().restore();
+        }
+    }
+
+    //draws the overlay layer met in drawing the tree, then records the order drawn for actions.
+    fn draw_overlay(&mut self, dcx: &mut DrawCtx, cx: &mut PageContext) {
+        let v = take(&mut self.overlay);
+        let mut drawn = Vec::with_capacity(v.len());
+        self.draw_stacking(v, &mut drawn, dcx, cx);
+        self.overlay = drawn;
+        self.offset = (0.0, 0.0);
+    }
+
+    //draws overlays ordered by z, the overlays met inside each one are ordered among themselves and drawn right above it.
+    fn draw_stacking(
+        &mut self,
+        mut v: Vec<(DrawUnitKey, (f32, f32))>,
+        drawn: &mut Vec<(DrawUnitKey, (f32, f32))>,
+        dcx: &mut DrawCtx,
+        cx: &mut PageContext,
+    ) {
+        v.sort_by(|a, b| {
+            self.data[a.0]
+                .rect()
+                .z()
+                .total_cmp(&self.data[b.0].rect().z())
+        });
+        for (k, (x, y)) in v {
+            self.offset = (x, y);
+            dcx.surface.canvas().save();
+            dcx.surface.canvas().translate((-x, -y));
+            self.draw_unit(k, dcx, cx);
+            dcx.surface.canvas().restore();
+            drawn.push((k, (x, y)));
+            let inner = take(&mut self.overlay);
+            self.draw_stacking(inner, drawn, dcx, cx);
+        }
+    }
+
+    //returns the keys ordered by z, the ones with the same z keep the order of subset.
+    fn stacked(&self, ks: &[DrawUnitKey]) -> Vec<DrawUnitKey> {
+        let mut v = ks.to_vec();
+        v.sort_by(|a, b| {
+            let z = |k| self.data.get(k).map(|o| o.rect().z()).unwrap_or_default();
+            z(*a).total_cmp(&z(*b))
+        });
+        v
+    }
+
+    //`upper` is the rect of the area holding `ks`, and `above` is its layout.
+    fn visit(
+        &self,
+        ks: &[DrawUnitKey],
+        upper: &FixedRect,
+        above: &Layout,
+        depth: usize,
+        cx: &PageContext,
+        f: &mut impl FnMut(ElementKey, usize, &Layout),
+    ) {
+        //the subset is drawn moved by the scroll offsets above, inside the rect of the upper.
+        let x = upper.x() - above.rect.x + above.scroll.0;
+        let y = upper.y() - above.rect.y + above.scroll.1;
+        let clip = match above.clip {
+            Some(o) => o.intersect(&above.rect),
+            None => above.rect,
+        };
+        for &k in ks {
+            let o = if let Some(o) = self.data.get(k) {
+                o
+            } else {
+                continue;
+            };
+            let r = o.rect();
+            let (w, h) = (r.side().width(), r.side().height());
+            let l = Layout {
+                rect: Bounds::new(r.x() - x, r.y() - y, w, h),
+                relative: Bounds::new(r.x() - upper.x(), r.y() - upper.y(), w, h),
+                clip: (!self.is_overlay(k, cx)).then_some(clip),
+                scroll: match o {
+                    DrawUnit::AREA(a) => a.scroll_bar.vision_var(),
+                    _ => (0.0, 0.0),
+                },
+                z: r.z(),
+            };
+            f(o.element(), depth, &l);
+            if let DrawUnit::AREA(a) = o {
+                self.visit(&a.subset, &a.rect, &l, depth + 1, cx, f);
             }
         }
     }
 
+    fn is_overlay(&self, k: DrawUnitKey, cx: &PageContext) -> bool {
+        self.data
+            .get(k)
+            .and_then(|o| cx.get(o.element()))
+            .and_then(|e| e.overlay().copied())
+            .unwrap_or_default()
+    }
+
     fn consume_action(&mut self, ks: &[DrawUnitKey], acx: &mut ActionCtx, cx: &mut PageContext) {
-        let mut v = ks.iter();
+        let v = self.stacked(ks);
+        let mut v = v.iter();
         while let Some(&k) = v.next_back() {
+            //the overlay layer has got the action already.
+            if self.is_overlay(k, cx) {
+                continue;
+            }
+            let o = if let Some(o) = self.data.get_mut(k) {
+                o as *mut DrawUnit
+            } else {
+                continue;
+            };
+            acx.remove(k);
+            acx.hit(unsafe { &*o });
+            unsafe { &mut *o }.consume_action(self, acx, cx);
+            if acx.is_finished() {
+                return;
+            }
+        }
+    }
+
+    //the overlay layer gets the action before the tree, the top one first.
+    fn consume_overlay(&mut self, acx: &mut ActionCtx, cx: &mut PageContext) {
+        let v = self.overlay.clone();
+        let mut v = v.iter();
+        while let Some(&(k, (x, y))) = v.next_back() {
             let o = if let Some(o) = self.data.get_mut(k) {
                 o as *mut DrawUnit
             } else {
                 continue;
             };
+            acx.kind.set_var_cursor(x, y);
             acx.remove(k);
+            acx.hit(unsafe { &*o });
             unsafe { &mut *o }.consume_action(self, acx, cx);
+            acx.kind.set_var_cursor(-x, -y);
             if acx.is_finished() {
                 return;
             }
@@ -308,26 +739,42 @@ impl std::fmt::Debug for DrawUnit {
 }
 
 impl DrawUnit {
-    pub(crate) fn new(key: ElementKey, mark_type: &Mark, eh: &ElementHolder) -> Option<Self> {
+    pub(crate) fn new(key: ElementKey, mark_type: &Mark, cx: &PageContext) -> Option<Self> {
         match mark_type {
-            Mark::AREA => Some(Self::AREA(Area::new(key, eh))),
-            Mark::AUDIO => Some(Self::AUDIO(Audio::new(key, eh))),
-            Mark::BUTTON => Some(Self::BUTTON(Button::new(key, eh))),
-            Mark::CANVAS => Some(Self::CANVAS(Canv::new(key, eh))),
-            Mark::IFRAME => Some(Self::IFRAME(Iframe::new(key, eh))),
-            Mark::IMG => Some(Self::IMG(Img::new(key, eh))),
-            Mark::INP => Some(Self::INP(Inp::new(key, eh))),
-            Mark::PT => Some(Self::PT(Pt::new(key, eh))),
-            Mark::SELECT => Some(Self::SELECT(Select::new(key, eh))),
-            Mark::TIME => Some(Self::TIME(Time::new(key, eh))),
-            Mark::VIDEO => Some(Self::VIDEO(Video::new(key, eh))),
+            Mark::AREA => Some(Self::AREA(Area::new(key, cx))),
+            Mark::AUDIO => Some(Self::AUDIO(Audio::new(key, cx))),
+            Mark::BUTTON => Some(Self::BUTTON(Button::new(key, cx))),
+            Mark::CANVAS => Some(Self::CANVAS(Canv::new(key, cx))),
+            Mark::IFRAME => Some(Self::IFRAME(Iframe::new(key, cx))),
+            Mark::IMG => Some(Self::IMG(Img::new(key, cx))),
+            Mark::INP => Some(Self::INP(Inp::new(key, cx))),
+            Mark::PT => Some(Self::PT(Pt::new(key, cx))),
+            Mark::SELECT => Some(Self::SELECT(Select::new(key, cx))),
+            Mark::TIME => Some(Self::TIME(Time::new(key, cx))),
+            Mark::VIDEO => Some(Self::VIDEO(Video::new(key, cx))),
             _ => None,
         }
     }
 
+    pub(crate) fn element(&self) -> ElementKey {
+        match self {
+            Self::AREA(o) => o.element,
+            Self::AUDIO(o) => o.element,
+            Self::BUTTON(o) => o.element,
+            Self::CANVAS(o) => o.element,
+            Self::IFRAME(o) => o.element,
+            Self::IMG(o) => o.element,
+            Self::INP(o) => o.element,
+            Self::PT(o) => o.element,
+            Self::SELECT(o) => o.element,
+            Self::TIME(o) => o.element,
+            Self::VIDEO(o) => o.element,
+        }
+    }
+
     pub(crate) fn resize(&mut self, c: &mut LayoutCoord, cx: &mut PageContext) {
         match self {
-            Self::AREA(o) => o.resize(c, cx),
+            Self::AREA(o) => o.resize(c, cx, o.fitted.clone().as_ref()),
             Self::AUDIO(o) => o.resize(c, cx),
             Self::BUTTON(o) => o.resize(c, cx),
             Self::CANVAS(o) => o.resize(c, cx),
@@ -341,6 +788,72 @@ impl DrawUnit {
         }
     }
 
+    pub(crate) fn restyle(&mut self, cx: &PageContext) {
+        let e = option_return!(cx.get(self.element()));
+        let t = cx.theme();
+        match self {
+            Self::AREA(o) => o.restyle(e, t),
+            Self::AUDIO(o) => o.restyle(e, t),
+            Self::BUTTON(o) => o.restyle(e, t),
+            Self::CANVAS(o) => o.restyle(e, t),
+            Self::IFRAME(o) => o.restyle(e, t),
+            Self::IMG(o) => o.restyle(e, t),
+            Self::INP(o) => o.restyle(e, t),
+            Self::PT(o) => o.restyle(e, t),
+            Self::SELECT(o) => o.restyle(e, t),
+            Self::TIME(o) => o.restyle(e, t),
+            Self::VIDEO(o) => o.restyle(e, t),
+        }
+    }
+
+    pub(crate) fn rect(&self) -> &FixedRect {
+        match self {
+            Self::AREA(o) => &o.rect,
+            Self::AUDIO(o) => &o.rect,
+            Self::BUTTON(o) => &o.rect,
+            Self::CANVAS(o) => &o.rect,
+            Self::IFRAME(o) => &o.rect,
+            Self::IMG(o) => &o.rect,
+            Self::INP(o) => &o.rect,
+            Self::PT(o) => &o.rect,
+            Self::SELECT(o) => &o.rect,
+            Self::TIME(o) => &o.rect,
+            Self::VIDEO(o) => &o.rect,
+        }
+    }
+
+    fn rect_mut(&mut self) -> &mut FixedRect {
+        match self {
+            Self::AREA(o) => &mut o.rect,
+            Self::AUDIO(o) => &mut o.rect,
+            Self::BUTTON(o) => &mut o.rect,
+            Self::CANVAS(o) => &mut o.rect,
+            Self::IFRAME(o) => &mut o.rect,
+            Self::IMG(o) => &mut o.rect,
+            Self::INP(o) => &mut o.rect,
+            Self::PT(o) => &mut o.rect,
+            Self::SELECT(o) => &mut o.rect,
+            Self::TIME(o) => &mut o.rect,
+            Self::VIDEO(o) => &mut o.rect,
+        }
+    }
+
+    pub(crate) fn within(&self, c: &Coord2D) -> bool {
+        match self {
+            Self::AREA(o) => o.rect.within(c),
+            Self::AUDIO(o) => o.rect.within(c),
+            Self::BUTTON(o) => o.rect.within(c),
+            Self::CANVAS(o) => o.rect.within(c),
+            Self::IFRAME(o) => o.rect.within(c),
+            Self::IMG(o) => o.rect.within(c),
+            Self::INP(o) => o.rect.within(c),
+            Self::PT(o) => o.rect.within(c),
+            Self::SELECT(o) => o.rect.within(c),
+            Self::TIME(o) => o.rect.within(c),
+            Self::VIDEO(o) => o.rect.within(c),
+        }
+    }
+
     pub(crate) fn right_bottom(&self, cx: &mut PageContext) -> Option<Coord2D> {
         match self {
             Self::AREA(o) => o.right_bottom(cx),
@@ -404,10 +917,12 @@ struct Area {
     align_pattern: AlignPattern,
     layout: LayoutCoord,
     scroll_bar: ScrollBar,
+    //the side which fits the subset, None if the side doesn't depend on it.
+    fitted: Option<RectSide>,
 }
 
 impl Area {
-    pub(crate) fn new(element: ElementKey, eh: &ElementHolder) -> Self {
+    pub(crate) fn new(element: ElementKey, cx: &PageContext) -> Self {
         Self {
             element,
             subset: Default::default(),
@@ -416,16 +931,26 @@ impl Area {
             align_pattern: Default::default(),
             layout: Default::default(),
             scroll_bar: Default::default(),
+            fitted: None,
         }
     }
 
-    pub(crate) fn resize(&mut self, c: &mut LayoutCoord, cx: &mut PageContext) {
+    ///`content` is the side which fits the subset for "auto" width or height.
+    pub(crate) fn resize(
+        &mut self,
+        c: &mut LayoutCoord,
+        cx: &mut PageContext,
+        content: Option<&RectSide>,
+    ) {
         if let Some(e) = cx.get(self.element) {
-            self.rect.get_attr(&e, c);
-            self.layout.get_attr(&e, self.rect.clone());
+            self.rect.get_attr(&e, c, content);
+            self.layout.get_attr(&e, self.rect.clone(), *c.basis());
+            self.restyle(e, cx.theme());
         }
     }
 
+    restyle!(t => Look::fill(t, t.bg_color()));
+
     right_bottom!();
 
     pub(crate) fn draw(&mut self, dcx: &mut DrawCtx, cx: &mut PageContext) {
@@ -499,19 +1024,101 @@ impl Area {
     }
 }
 
+///Represents a rect in pixels.
+#[derive(Clone, Copy, Debug, Default, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct Bounds {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+}
+
+impl std::fmt::Display for Bounds {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({},{},{},{})", self.x, self.y, self.width, self.height)
+    }
+}
+
+impl From<&FixedRect> for Bounds {
+    fn from(o: &FixedRect) -> Self {
+        Self::new(o.x(), o.y(), o.side().width(), o.side().height())
+    }
+}
+
+impl Bounds {
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    ///Returns the part inside both, the side is zero if they don't meet.
+    pub fn intersect(&self, o: &Self) -> Self {
+        let x = self.x.max(o.x);
+        let y = self.y.max(o.y);
+        let w = (self.x + self.width).min(o.x + o.width) - x;
+        let h = (self.y + self.height).min(o.y + o.height) - y;
+        Self::new(x, y, w.max(0.0), h.max(0.0))
+    }
+}
+
+///Represents where an element is laid out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct Layout {
+    ///The rect in page coordinates, where it's drawn and hit after scrolling.
+    rect: Bounds,
+    ///The rect relative to the rect of its upper "area" or "body", before scrolling.
+    relative: Bounds,
+    ///The region it's clipped to in page coordinates, None for "body" and the overlay layer.
+    clip: Option<Bounds>,
+    ///The scroll offset of its subset, zero unless it's "body" or "area".
+    scroll: (f32, f32),
+    ///The stacking order among its siblings in the same stacking context, a higher one is drawn above.
+    z: f32,
+}
+
+impl std::fmt::Display for Layout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "rect: {}, relative: {}, clip: ",
+            self.rect, self.relative
+        )?;
+        match &self.clip {
+            Some(o) => write!(f, "{o}")?,
+            None => write!(f, "none")?,
+        }
+        write!(
+            f,
+            ", scroll: ({},{}), z: {}",
+            self.scroll.0, self.scroll.1, self.z
+        )
+    }
+}
+
 //------------------------------------------------------------------------------------------
 
 pub(crate) struct DrawCtx {
     surface: Surface,
     paint: Paint,
+    theme: Arc<Theme>,
 }
 
 impl DrawCtx {
-    pub(crate) fn new(surface: Surface) -> Self {
+    pub(crate) fn new(surface: Surface, theme: Arc<Theme>) -> Self {
         let mut paint = Paint::default();
-        paint.set_color(*default_bg_color());
+        paint.set_color(theme.bg_color());
         paint.set_anti_alias(true);
-        Self { surface, paint }
+        Self {
+            surface,
+            paint,
+            theme,
+        }
     }
 
     fn draw_in_rect(&mut self, rect: &FixedRect, mut f: impl FnMut(&mut Surface, &Paint)) {
@@ -549,6 +1156,11 @@ pub(crate) struct ActionCtx<'a> {
     callback: &'a mut Vec<DrawUnitKey>,
     new_callback: Vec<DrawUnitKey>,
     current: DrawUnitKey,
+    events: &'a mut Vec<Event>,
+    //elements under the cursor.
+    hits: &'a mut Vec<ElementKey>,
+    //an element took the focus with the action.
+    focused: bool,
 }
 
 impl<'a> Drop for ActionCtx<'a> {
@@ -559,13 +1171,21 @@ impl<'a> Drop for ActionCtx<'a> {
 }
 
 impl<'a> ActionCtx<'a> {
-    pub(crate) fn new(kind: ActionKind, callback: &'a mut Vec<DrawUnitKey>) -> Self {
+    pub(crate) fn new(
+        kind: ActionKind,
+        callback: &'a mut Vec<DrawUnitKey>,
+        events: &'a mut Vec<Event>,
+        hits: &'a mut Vec<ElementKey>,
+    ) -> Self {
         Self {
             kind,
             finish: false,
             callback,
             new_callback: Default::default(),
             current: Default::default(),
+            events,
+            hits,
+            focused: false,
         }
     }
 
@@ -583,4 +1203,38 @@ impl<'a> ActionCtx<'a> {
     fn push_callback(&mut self) {
         self.new_callback.push(self.current);
     }
+
+    fn push_event(&mut self, kind: EventKind, target: ElementKey, coord: Option<Coord2D>) {
+        self.events.push(Event::new(kind, target, coord));
+    }
+
+    //moves the focus to the element, the one focused before gets "blur" first.
+    fn focus(&mut self, element: ElementKey, c: &Coord2D, cx: &mut PageContext) {
+        self.focused = true;
+        if cx.focused() == Some(element) {
+            return;
+        }
+        self.blur(cx);
+        cx.set_focused(Some(element));
+        self.push_event(EventKind::Focus, element, Some(c.clone()));
+    }
+
+    //pushes "blur" for the focused element, and "invalid" if it's a required input left empty.
+    fn blur(&mut self, cx: &mut PageContext) {
+        let k = option_return!(cx.set_focused(None));
+        self.push_event(EventKind::Blur, k, None);
+        let e = option_return!(cx.get(k));
+        if e.mark_type() == &Mark::INP
+            && e.required() == Some(&true)
+            && e.value().map_or(true, |s| s.is_empty())
+        {
+            self.push_event(EventKind::Invalid, k, None);
+        }
+    }
+
+    fn hit(&mut self, o: &DrawUnit) {
+        if self.kind.coord().is_some_and(|c| o.within(c)) {
+            self.hits.push(o.element());
+        }
+    }
 }
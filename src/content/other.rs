@@ -10,17 +10,19 @@ pub(crate) struct Canv {
 }
 
 impl Canv {
-    pub(crate) fn new(element: ElementKey, eh: &ElementHolder) -> Self {
+    pub(crate) fn new(element: ElementKey, cx: &PageContext) -> Self {
         Self {
             element,
             rect: (100.0, 100.0).into(),
-            painter: Default::default(),
+            painter: Look::fill(cx.theme(), cx.theme().bg_color()).build(),
             scroll_bar: Default::default(),
         }
     }
 
     resize!();
 
+    restyle!(t => Look::fill(t, t.bg_color()));
+
     right_bottom!();
 
     pub(crate) fn draw(&mut self, dcx: &mut DrawCtx, cx: &mut PageContext) {
@@ -56,16 +58,18 @@ pub(crate) struct Iframe {
 }
 
 impl Iframe {
-    pub(crate) fn new(element: ElementKey, eh: &ElementHolder) -> Self {
+    pub(crate) fn new(element: ElementKey, cx: &PageContext) -> Self {
         Self {
             element,
             rect: (100.0, 100.0).into(),
-            painter: Default::default(),
+            painter: Look::fill(cx.theme(), cx.theme().bg_color()).build(),
         }
     }
 
     resize!();
 
+    restyle!(t => Look::fill(t, t.bg_color()));
+
     right_bottom!();
 
     pub(crate) fn draw(&mut self, dcx: &mut DrawCtx, cx: &mut PageContext) {
@@ -3,6 +3,7 @@ A module for error.
 */
 
 use crate::utils::*;
+use std::fmt::Write;
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
@@ -19,12 +20,85 @@ pub enum ErrorKind {
     Window,
 }
 
+///Represents stable error code.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ErrorCode {
+    IllegalChar,
+    InvalidMark,
+    NoAttributeName,
+    InvalidAttribute,
+    InvalidValue,
+    InvalidCombiner,
+    NoPage,
+    UnmatchedTag,
+    InvalidCondition,
+    UndefinedVariable,
+    CyclicVariable,
+    InvalidState,
+    InvalidKeyframe,
+    UnmatchedRule,
+    InapplicableAttribute,
+    OverriddenDeclaration,
+    DuplicateId,
+}
+
+impl ErrorCode {
+    ///Returns a string slice.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::IllegalChar => "E001",
+            Self::InvalidMark => "E002",
+            Self::NoAttributeName => "E003",
+            Self::InvalidAttribute => "E004",
+            Self::InvalidValue => "E005",
+            Self::InvalidCombiner => "E006",
+            Self::NoPage => "E007",
+            Self::UnmatchedTag => "E008",
+            Self::InvalidCondition => "E009",
+            Self::UndefinedVariable => "E010",
+            Self::CyclicVariable => "E011",
+            Self::InvalidState => "E012",
+            Self::InvalidKeyframe => "E013",
+            Self::UnmatchedRule => "E014",
+            Self::InapplicableAttribute => "E015",
+            Self::OverriddenDeclaration => "E016",
+            Self::DuplicateId => "E017",
+        }
+    }
+
+    ///Returns a short description.
+    pub fn describe(&self) -> &str {
+        match self {
+            Self::IllegalChar => "illegal char",
+            Self::InvalidMark => "invalid mark",
+            Self::NoAttributeName => "no attribute name",
+            Self::InvalidAttribute => "invalid attribute",
+            Self::InvalidValue => "invalid value",
+            Self::InvalidCombiner => "invalid combiner",
+            Self::NoPage => "no page",
+            Self::UnmatchedTag => "unmatched tag",
+            Self::InvalidCondition => "invalid condition",
+            Self::UndefinedVariable => "undefined variable",
+            Self::CyclicVariable => "cyclic variable",
+            Self::InvalidState => "invalid state",
+            Self::InvalidKeyframe => "invalid keyframe",
+            Self::UnmatchedRule => "unmatched rule",
+            Self::InapplicableAttribute => "inapplicable attribute",
+            Self::OverriddenDeclaration => "overridden declaration",
+            Self::DuplicateId => "duplicate id",
+        }
+    }
+}
+
 ///Represents error.
 #[derive(Getters)]
 pub struct Error {
     #[getset(get = "pub")]
     kind: ErrorKind,
+    code: Option<ErrorCode>,
     p: Option<(usize, usize)>,
+    n: usize,
     s: String,
 }
 
@@ -32,7 +106,9 @@ impl Error {
     fn format(kind: ErrorKind, a: usize, b: usize, s: impl ToString) -> Self {
         Self {
             kind,
+            code: None,
             p: Some((a, b)),
+            n: 0,
             s: s.to_string(),
         }
     }
@@ -40,10 +116,122 @@ impl Error {
     fn new(kind: ErrorKind, s: impl ToString) -> Self {
         Self {
             kind,
+            code: None,
             p: None,
+            n: 0,
             s: s.to_string(),
         }
     }
+
+    ///Returns error code.
+    pub fn code(&self) -> Option<ErrorCode> {
+        self.code
+    }
+
+    ///Returns zero-based row and column in source.
+    pub fn position(&self) -> Option<(usize, usize)> {
+        self.p
+    }
+
+    ///Returns the number of chars in source.
+    pub fn length(&self) -> usize {
+        self.n.max(1)
+    }
+
+    ///Returns message.
+    pub fn message(&self) -> &str {
+        &self.s
+    }
+
+    pub(crate) fn with_code(mut self, code: ErrorCode) -> Self {
+        self.code.replace(code);
+        self
+    }
+
+    pub(crate) fn with_span(mut self, p: (usize, usize), n: usize) -> Self {
+        self.p.replace(p);
+        self.n = n;
+        self
+    }
+
+    //moves position when source is a part of another source starts at `o`.
+    pub(crate) fn offset(&mut self, o: (usize, usize)) {
+        if let Some((a, b)) = self.p {
+            if a == 0 {
+                self.p.replace((o.0, o.1 + b));
+            } else {
+                self.p.replace((o.0 + a, b));
+            }
+        }
+    }
+
+    ///Renders it with the line of source where it happened.
+    pub fn render(&self, src: &str) -> String {
+        let mut r = String::from("error");
+        if let Some(o) = self.code {
+            let _ = write!(r, "[{}]", o.as_str());
+        }
+        let _ = writeln!(r, ": {:?} {}", self.kind, self.s);
+        if let Some((a, b)) = self.p {
+            let line = src.lines().nth(a).unwrap_or_default();
+            let n = (a + 1).to_string();
+            let pad = " ".repeat(n.len());
+            let _ = writeln!(r, "{pad}--> {}:{}", a + 1, b + 1);
+            let _ = writeln!(r, "{pad} |");
+            let _ = writeln!(r, "{n} | {line}");
+            let _ = write!(r, "{pad} | ");
+            for c in line.chars().take(b) {
+                r.push(if c == '\t' { c } else { ' ' });
+            }
+            r.push_str(&"^".repeat(self.length()));
+            r.push('\n');
+        }
+        r
+    }
+
+    ///Returns JSON text, row and column are one-based.
+    pub fn to_json(&self) -> String {
+        let mut r = String::from("{");
+        let _ = write!(r, "\"kind\":\"{:?}\",\"code\":", self.kind);
+        if let Some(o) = self.code {
+            let _ = write!(r, "\"{}\",", o.as_str());
+        } else {
+            r.push_str("null,");
+        }
+        if let Some((a, b)) = self.p {
+            let _ = write!(
+                r,
+                "\"line\":{},\"column\":{},\"length\":{},",
+                a + 1,
+                b + 1,
+                self.length()
+            );
+        } else {
+            r.push_str("\"line\":null,\"column\":null,\"length\":null,");
+        }
+        r.push_str("\"message\":");
+        json_str(&self.s, &mut r);
+        r.push('}');
+        r
+    }
+}
+
+fn json_str(s: &str, r: &mut String) {
+    r.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => r.push_str("\\\""),
+            '\\' => r.push_str("\\\\"),
+            '\n' => r.push_str("\\n"),
+            '\r' => r.push_str("\\r"),
+            '\t' => r.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(r, "\\u{:04x}", c as u32);
+            }
+            _ => r.push(c),
+        }
+    }
+    r.push('"');
 }
 
 impl std::fmt::Debug for Error {
@@ -55,6 +243,9 @@ impl std::fmt::Debug for Error {
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?} Error. ", self.kind)?;
+        if let Some(o) = self.code {
+            write!(f, "[{}] ", o.as_str())?;
+        }
         if let Some(p) = self.p {
             write!(f, "{:?} ", p)?;
         }
@@ -95,6 +286,28 @@ pub struct ErrorHolder {
 
 deref!(ErrorHolder, Vec<Error>, v);
 
+impl ErrorHolder {
+    ///Renders all errors with the lines of source where they happened.
+    pub fn render(&self, src: &str) -> String {
+        let mut r = String::new();
+        for o in &self.v {
+            r.push_str(&o.render(src));
+            r.push('\n');
+        }
+        r
+    }
+
+    ///Returns JSON array.
+    pub fn to_json(&self) -> String {
+        let v: Vec<String> = self.v.iter().map(|o| o.to_json()).collect();
+        format!("[{}]", v.join(","))
+    }
+
+    pub(crate) fn offset(&mut self, o: (usize, usize)) {
+        self.v.iter_mut().for_each(|e| e.offset(o));
+    }
+}
+
 impl std::fmt::Debug for ErrorHolder {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self)
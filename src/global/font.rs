@@ -1,3 +1,4 @@
+use skia_safe::font_style::{Slant, Weight, Width};
 use skia_safe::{Font, FontMgr, FontStyle};
 use std::collections::HashMap;
 use std::sync::{Arc, LazyLock, OnceLock, RwLock};
@@ -35,10 +36,6 @@ impl FontHolder {
     }
 }
 
-pub(super) fn set_default_font(font: Font) {
-    let _ = DEFAULT_FONT.set(Arc::new(font));
-}
-
 pub(crate) fn default_font() -> Arc<Font> {
     DEFAULT_FONT
         .get_or_init(|| Arc::new(get_default_font()))
@@ -46,19 +43,38 @@ pub(crate) fn default_font() -> Arc<Font> {
 }
 
 pub(crate) fn get_applied(s: &str) -> Option<Arc<Font>> {
+    get_or_load(s, || get_font(s))
+}
+
+///Returns the key and the font of a family, weight and size. The family of default font is used if `family` is empty.
+pub(crate) fn get_applied_style(
+    family: &str,
+    weight: u16,
+    size: Option<f32>,
+) -> Option<(String, Arc<Font>)> {
+    let family = if family.is_empty() {
+        default_font().typeface().family_name()
+    } else {
+        family.to_string()
+    };
+    let k = format!("{family}:{weight}:{}", size.unwrap_or_default());
+    get_or_load(&k, || get_font_style(&family, weight, size)).map(|o| (k, o))
+}
+
+fn get_or_load(k: &str, f: impl FnOnce() -> Option<Font>) -> Option<Arc<Font>> {
     let mut n = false;
     if let Ok(o) = APPLIED_FONTS.read() {
-        n = !o.contains_key(s);
+        n = !o.contains_key(k);
     }
     if n {
         if let Ok(mut o) = APPLIED_FONTS.write() {
-            if let Some(f) = get_font(s) {
-                o.insert(s, f);
+            if let Some(f) = f() {
+                o.insert(k, f);
             }
         }
     }
     if let Ok(o) = APPLIED_FONTS.read() {
-        return o.get(s);
+        return o.get(k);
     }
     None
 }
@@ -75,6 +91,13 @@ pub(super) fn get_font(s: &str) -> Option<Font> {
         .map(|tf| Font::from_typeface(tf, None))
 }
 
+fn get_font_style(s: &str, weight: u16, size: Option<f32>) -> Option<Font> {
+    let style = FontStyle::new(Weight::from(weight as i32), Width::NORMAL, Slant::Upright);
+    FontMgr::default()
+        .match_family_style(s, style)
+        .map(|tf| Font::from_typeface(tf, size))
+}
+
 fn get_default_font() -> Font {
     let fm = FontMgr::default();
     let tf = fm
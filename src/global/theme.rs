@@ -0,0 +1,136 @@
+use super::*;
+use std::sync::OnceLock;
+
+//the theme of new pages, set by the deprecated InitialFont.
+static INITIAL_THEME: OnceLock<Theme> = OnceLock::new();
+
+///Represents the palette, default font and metrics of a page, the visual attributes of elements take precedence over them.
+#[derive(Clone, CopyGetters, Debug, Getters, PartialEq, Setters)]
+pub struct Theme {
+    #[getset(get_copy = "pub", set = "pub")]
+    bg_color: Color,
+    #[getset(get_copy = "pub", set = "pub")]
+    border_color: Color,
+    #[getset(get_copy = "pub", set = "pub")]
+    font_color: Color,
+    ///The background of time and media.
+    #[getset(get_copy = "pub", set = "pub")]
+    surface_color: Color,
+    #[getset(get_copy = "pub", set = "pub")]
+    button_color: Color,
+    ///The background of a button while pressed.
+    #[getset(get_copy = "pub", set = "pub")]
+    button2_color: Color,
+    ///The background of input, pt and select.
+    #[getset(get_copy = "pub", set = "pub")]
+    blank_color: Color,
+    #[getset(get_copy = "pub", set = "pub")]
+    cursor_color: Color,
+    ///The thumb of scroll bar.
+    #[getset(get_copy = "pub", set = "pub")]
+    scroll_bar_color: Color,
+    ///The track of scroll bar.
+    #[getset(get_copy = "pub", set = "pub")]
+    scroll_bar2_color: Color,
+    ///The default font family, the system one is used if it is None.
+    #[getset(get = "pub", set = "pub")]
+    font_family: Option<String>,
+    #[getset(get_copy = "pub", set = "pub")]
+    font_size: Option<f32>,
+    ///The radius of a button.
+    #[getset(get_copy = "pub", set = "pub")]
+    radius: f32,
+    ///The border width of input, pt and select.
+    #[getset(get_copy = "pub", set = "pub")]
+    border_width: f32,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        INITIAL_THEME.get().cloned().unwrap_or_else(Self::light)
+    }
+}
+
+impl Theme {
+    pub fn light() -> Self {
+        Self {
+            bg_color: Color::from_rgb(255, 255, 255),
+            border_color: Color::from_rgb(0, 0, 0),
+            font_color: Color::from_rgb(0, 0, 0),
+            surface_color: Color::from_rgb(200, 200, 200),
+            button_color: Color::from_rgb(100, 200, 100),
+            button2_color: Color::from_rgb(100, 100, 200),
+            blank_color: Color::from_rgb(255, 255, 255),
+            cursor_color: Color::from_rgb(100, 100, 100),
+            scroll_bar_color: Color::from_rgb(200, 200, 200),
+            scroll_bar2_color: Color::from_rgb(0, 100, 100),
+            font_family: None,
+            font_size: None,
+            radius: 10.0,
+            border_width: 1.0,
+        }
+    }
+
+    pub fn dark() -> Self {
+        Self {
+            bg_color: Color::from_rgb(30, 30, 30),
+            border_color: Color::from_rgb(90, 90, 90),
+            font_color: Color::from_rgb(230, 230, 230),
+            surface_color: Color::from_rgb(50, 50, 50),
+            button_color: Color::from_rgb(40, 120, 70),
+            button2_color: Color::from_rgb(50, 70, 140),
+            blank_color: Color::from_rgb(20, 20, 20),
+            cursor_color: Color::from_rgb(200, 200, 200),
+            scroll_bar_color: Color::from_rgb(110, 110, 110),
+            scroll_bar2_color: Color::from_rgb(45, 45, 45),
+            ..Self::light()
+        }
+    }
+
+    ///White on black with blue buttons, borders are thicker.
+    pub fn high_contrast() -> Self {
+        Self {
+            bg_color: Color::from_rgb(0, 0, 0),
+            border_color: Color::from_rgb(255, 255, 255),
+            font_color: Color::from_rgb(255, 255, 255),
+            surface_color: Color::from_rgb(0, 0, 0),
+            button_color: Color::from_rgb(0, 0, 180),
+            button2_color: Color::from_rgb(0, 90, 255),
+            blank_color: Color::from_rgb(0, 0, 0),
+            cursor_color: Color::from_rgb(255, 255, 0),
+            scroll_bar_color: Color::from_rgb(255, 255, 255),
+            scroll_bar2_color: Color::from_rgb(0, 0, 0),
+            border_width: 2.0,
+            ..Self::light()
+        }
+    }
+
+    ///Sets the default font, the font family must be available in system.
+    pub fn with_font(mut self, family: impl Into<String>, size: Option<f32>) -> Self {
+        self.font_family.replace(family.into());
+        self.font_size = size;
+        self
+    }
+}
+
+///InitialFont
+#[deprecated(note = "use `Theme::with_font` and `Page::set_theme` instead")]
+pub struct InitialFont(Theme);
+
+#[allow(deprecated)]
+impl InitialFont {
+    pub fn new(s: &str, color: Option<Color>) -> Option<Self> {
+        get_font(s)?;
+        let mut theme = Theme::light().with_font(s, None);
+        if let Some(color) = color {
+            theme.set_font_color(color);
+        }
+        Some(Self(theme))
+    }
+
+    ///Sets the theme of pages created afterwards.
+    pub fn initialize(self) {
+        let _ = INITIAL_THEME.set(self.0);
+        info!("initial font");
+    }
+}
@@ -92,5 +92,7 @@ mod script;
 mod style;
 mod utils;
 
+pub use content::{Bounds, Layout};
 pub use page::*;
 pub use screen::*;
+pub use style::{StyleOrigin, lint_style};
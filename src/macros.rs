@@ -1,7 +1,9 @@
 macro_rules! attribute_get {
     ($n:ident, $t:ty, $a:tt) => {
         pub(crate) fn $n(&self) -> Option<&$t> {
-            if let Some(Attribute::$a(o)) = self.attribute.get(&AttrName::$a) {
+            let a = AttrName::$a;
+            if let Some(Attribute::$a(o)) = self.animated.get(&a).or_else(|| self.attribute.get(&a))
+            {
                 Some(o)
             } else {
                 None
@@ -30,10 +32,35 @@ macro_rules! resize {
     () => {
         pub(crate) fn resize(&mut self, c: &mut LayoutCoord, cx: &mut PageContext) {
             if let Some(e) = cx.get(self.element) {
-                self.rect.get_attr(&e, c)
+                self.rect.get_attr(&e, c, None);
+                self.restyle(e, cx.theme());
             }
         }
     };
+    //`content` is the side which fits the content for "auto" width or height, it's evaluated after restyle.
+    ($self:ident, $e:ident, $c:ident, $cx:ident => $content:expr) => {
+        pub(crate) fn resize(&mut $self, $c: &mut LayoutCoord, $cx: &mut PageContext) {
+            if let Some($e) = $cx.get($self.element) {
+                $self.restyle($e, $cx.theme());
+                let o = $content;
+                $self.rect.get_attr($e, $c, o.as_ref());
+            }
+        }
+    };
+}
+
+macro_rules! restyle {
+    ($t:ident => $look:expr) => {
+        pub(crate) fn restyle(&mut self, e: &Element, $t: &Theme) {
+            self.painter = $look.with_attr(e).build();
+        }
+    };
+    ($t:ident => $look:expr, $draw_text:ident) => {
+        pub(crate) fn restyle(&mut self, e: &Element, $t: &Theme) {
+            self.painter = $look.with_attr(e).build();
+            *self.$draw_text.apply_font_mut() = ApplyFont::from_attr(e, $t);
+        }
+    };
 }
 
 macro_rules! right_bottom {
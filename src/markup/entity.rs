@@ -1,7 +1,8 @@
 use super::format::*;
 use super::*;
+use crate::style::parse_selector;
 use slotmap::{SlotMap, new_key_type};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 ///Represents element's attributes.
 #[derive(Default)]
@@ -18,21 +19,30 @@ impl std::fmt::Debug for AttributeHolder {
 
 impl AttributeHolder {
     fn add(&mut self, k: &str, s: &mut String) -> Option<Error> {
-        AttrName::try_from(k)
-            .and_then(|k| {
-                Attribute::from(&k, s).map(|a| {
-                    self.insert(k, a);
-                })
-            })
-            .err()
+        match AttrName::try_from(k) {
+            Ok(a) => match Attribute::from(&a, s) {
+                Ok(o) => {
+                    self.insert(a, o);
+                    None
+                }
+                Err(e) => {
+                    let e: Error = (ErrorKind::Markup, format!("{k}={s:?} {}", e.message())).into();
+                    Some(e.with_code(ErrorCode::InvalidValue))
+                }
+            },
+            Err(e) => Some(e.with_code(ErrorCode::InvalidAttribute)),
+        }
     }
 
-    pub(super) fn from(o: HashMap<String, String>, error: &mut ErrorHolder) -> Self {
+    pub(super) fn from(
+        o: HashMap<String, (String, (usize, usize))>,
+        error: &mut ErrorHolder,
+    ) -> Self {
         let mut r = Self::default();
-        for (k, mut s) in o {
+        for (k, (mut s, p)) in o {
             if let Some(e) = r.add(&k, &mut s) {
                 trace!("{e}");
-                error.push(e);
+                error.push(e.with_span(p, k.chars().count()));
             }
         }
         r
@@ -40,7 +50,7 @@ impl AttributeHolder {
 }
 
 ///Represents element.
-#[derive(Getters, MutGetters)]
+#[derive(CopyGetters, Getters, MutGetters)]
 pub struct Element {
     #[getset(get = "pub")]
     mark_type: Mark,
@@ -52,6 +62,12 @@ pub struct Element {
     subset: Vec<ElementKey>,
     #[getset(get = "pub")]
     upper: Option<ElementKey>,
+    #[getset(get_copy = "pub", get_mut = "pub(crate)")]
+    state: ElementState,
+    ///The values shown by running transitions and animations, they take precedence over attribute.
+    #[getset(get = "pub", get_mut = "pub(crate)")]
+    animated: AttributeHolder,
+    text_position: Option<(usize, usize)>,
 }
 
 impl std::fmt::Debug for Element {
@@ -91,9 +107,21 @@ impl Element {
             attribute,
             subset: Default::default(),
             upper: None,
+            state: Default::default(),
+            animated: Default::default(),
+            text_position: None,
         }
     }
 
+    ///Returns zero-based row and column where text starts in source.
+    pub fn text_position(&self) -> Option<(usize, usize)> {
+        self.text_position
+    }
+
+    pub(crate) fn set_text_position(&mut self, p: Option<(usize, usize)>) {
+        self.text_position = p;
+    }
+
     ///Returns a string slice of this element's type.
     pub fn as_str(&self) -> &str {
         self.mark_type.as_str()
@@ -118,21 +146,50 @@ impl Element {
         self.attribute.insert(a.name(), a);
     }
 
+    ///Removes an attribute.
+    pub fn attribute_remove(&mut self, a: &AttrName) -> Option<Attribute> {
+        self.attribute.remove(a)
+    }
+
+    attribute_get!(align, FlowAlign, ALIGN);
+    attribute_get!(animation, Animation, ANIMATION);
+    attribute_get!(areas, Areas, AREAS);
+    attribute_get!(background, Rgba, BACKGROUND);
+    attribute_get!(border_color, Rgba, BORDER_COLOR);
+    attribute_get!(border_width, Sides, BORDER_WIDTH);
     attribute_get!(class, String, CLASS);
+    attribute_get!(color, Rgba, COLOR);
     attribute_get!(column, Points, COLUMN);
+    attribute_get!(column_span, usize, COLUMN_SPAN);
     attribute_get!(disabled, bool, DISABLED);
-    attribute_get!(height, Distance, HEIGHT);
+    attribute_get!(flow, Flow, FLOW);
+    attribute_get!(font_family, String, FONT_FAMILY);
+    attribute_get!(font_size, f32, FONT_SIZE);
+    attribute_get!(font_weight, u16, FONT_WEIGHT);
+    attribute_get!(gap, f32, GAP);
+    attribute_get!(height, Length, HEIGHT);
     attribute_get!(hidden, bool, HIDDEN);
     attribute_get!(id, String, ID);
+    attribute_get!(justify, Justify, JUSTIFY);
+    attribute_get!(margin, Insets, MARGIN);
     attribute_get!(multiple, bool, MULTIPLE);
+    attribute_get!(opacity, f32, OPACITY);
     attribute_get!(ordinal, Ordinal, ORDINAL);
-    attribute_get!(position, Coord, POSITION);
+    attribute_get!(overlay, bool, OVERLAY);
+    attribute_get!(padding, Insets, PADDING);
+    attribute_get!(place, String, PLACE);
+    attribute_get!(position, Position, POSITION);
+    attribute_get!(radius, f32, RADIUS);
+    attribute_get!(required, bool, REQUIRED);
     attribute_get!(row, Points, ROW);
+    attribute_get!(row_span, usize, ROW_SPAN);
     attribute_get!(selected, bool, SELECTED);
     attribute_get!(script_type, ScriptType, TYPE);
+    attribute_get!(transition, Transition, TRANSITION);
     attribute_get!(value, String, VALUE);
     attribute_get_or_insert!(value_or_insert, String, VALUE, String::new());
-    attribute_get!(width, Distance, WIDTH);
+    attribute_get!(width, Length, WIDTH);
+    attribute_get!(z, f32, Z);
 
     ///Inserts an element into subset.
     pub fn subset_insert(&mut self, n: usize, a: ElementKey) {
@@ -235,6 +292,57 @@ impl ElementHolder {
         }
     }
 
+    ///Creates an element which has no upper and is not a root, `move_to` puts it into the tree.
+    pub fn create(&mut self, mut e: Element) -> ElementKey {
+        e.upper.take();
+        self.data.insert(e)
+    }
+
+    ///Moves an element to the subset of another element, returns false if the upper is the element or its descendant.
+    pub fn move_to(
+        &mut self,
+        key: ElementKey,
+        upper_key: ElementKey,
+        index: Option<usize>,
+    ) -> bool {
+        if !self.data.contains_key(key) || !self.data.contains_key(upper_key) {
+            return false;
+        }
+        if self.is_ancestor(key, upper_key) {
+            return false;
+        }
+        if let Some(upper) = self
+            .get(key)
+            .and_then(|e| e.upper)
+            .and_then(|k| self.data.get_mut(k))
+        {
+            upper.subset.retain(|k| k != &key);
+        }
+        if let Some(n) = self.root.iter().position(|k| k == &key) {
+            self.root.remove(n);
+        }
+        if let Some(e) = self.data.get_mut(key) {
+            e.upper.replace(upper_key);
+        }
+        if let Some(upper) = self.data.get_mut(upper_key) {
+            let n = index.unwrap_or(upper.subset.len()).min(upper.subset.len());
+            upper.subset.insert(n, key);
+        }
+        true
+    }
+
+    ///Returns true if `a` is `b` or an ancestor of `b`.
+    pub fn is_ancestor(&self, a: ElementKey, b: ElementKey) -> bool {
+        let mut k = Some(b);
+        while let Some(o) = k {
+            if o == a {
+                return true;
+            }
+            k = self.get(o).and_then(|e| e.upper);
+        }
+        false
+    }
+
     ///Sets an upper for an element.
     pub fn set_upper(&mut self, key: ElementKey, upper_key: ElementKey) -> bool {
         if !self.data.contains_key(key) && !self.data.contains_key(upper_key) {
@@ -275,6 +383,18 @@ impl ElementHolder {
             .unwrap_or_else(|| Vec::new())
     }
 
+    ///Returns the keys of elements matching a selector, the selector is written in the syntax of style sheet.
+    pub fn query(&self, s: &str) -> Result<Vec<ElementKey>> {
+        let v = parse_selector(s)?.find(self);
+        let mut set = HashSet::new();
+        Ok(v.into_iter().filter(|k| set.insert(*k)).collect())
+    }
+
+    ///Returns the key of the first element matching a selector.
+    pub fn query_one(&self, s: &str) -> Result<Option<ElementKey>> {
+        self.query(s).map(|v| v.first().copied())
+    }
+
     pub(crate) fn subset_with_mark(&self, key: ElementKey, mark_type: Mark) -> Vec<ElementKey> {
         let i = self.get_subset(key).into_iter();
         let mut r = Vec::new();
@@ -296,6 +416,19 @@ impl ElementHolder {
         }
     }
 
+    ///Serializes it to a string slice.
+    pub fn serialize(&self, o: MarkNumber) -> String {
+        match o {
+            MarkNumber::Double => write(self),
+            MarkNumber::Single => write_s(self),
+        }
+    }
+
+    ///Returns root keys.
+    pub fn roots(&self) -> &[ElementKey] {
+        &self.root
+    }
+
     pub(crate) fn first_root(&self) -> Option<ElementKey> {
         self.root.first().copied()
     }
@@ -1,4 +1,5 @@
 mod s;
+mod w;
 mod x;
 
 use self::x::*;
@@ -7,9 +8,51 @@ use crate::utils::ascii::*;
 use slotmap::{DefaultKey, SecondaryMap, SlotMap};
 use std::collections::HashMap;
 
+#[derive(Debug, Default)]
+struct TempElement {
+    mark: (String, (usize, usize)),
+    text: String,
+    text_p: Option<(usize, usize)>,
+    attribute: HashMap<String, (String, (usize, usize))>,
+}
+
+impl TempElement {
+    fn new(s: String, p: (usize, usize)) -> Self {
+        Self {
+            mark: (s, p),
+            ..Default::default()
+        }
+    }
+
+    fn push_text(&mut self, s: &str, p: (usize, usize)) {
+        if self.text_p.is_none() {
+            self.text_p.replace(p);
+        }
+        self.text.push_str(s);
+    }
+
+    fn to_element(self, error: &mut ErrorHolder) -> Option<Element> {
+        let (s, p) = self.mark;
+        match Mark::try_from(&s) {
+            Ok(m) => {
+                let mut e =
+                    Element::new(m, self.text, AttributeHolder::from(self.attribute, error));
+                e.set_text_position(self.text_p);
+                Some(e)
+            }
+            Err(e) => {
+                trace!("{e}");
+                let n = s.chars().count();
+                error.push(e.with_code(ErrorCode::InvalidMark).with_span(p, n));
+                None
+            }
+        }
+    }
+}
+
 #[derive(Default)]
 struct TempResult {
-    data: SlotMap<DefaultKey, (String, String, HashMap<String, String>)>,
+    data: SlotMap<DefaultKey, TempElement>,
     root: Vec<DefaultKey>,
     subset: SecondaryMap<DefaultKey, Vec<DefaultKey>>,
 }
@@ -33,17 +76,13 @@ impl TempResult {
         self.root.len() > 0
     }
 
-    fn add_root(&mut self, e: (String, String, HashMap<String, String>)) -> DefaultKey {
+    fn add_root(&mut self, e: TempElement) -> DefaultKey {
         let key = self.data.insert(e);
         self.root.push(key);
         key
     }
 
-    fn add(
-        &mut self,
-        upper_key: DefaultKey,
-        e: (String, String, HashMap<String, String>),
-    ) -> DefaultKey {
+    fn add(&mut self, upper_key: DefaultKey, e: TempElement) -> DefaultKey {
         let key = self.data.insert(e);
         if let Some(upper) = self.subset.get_mut(upper_key) {
             upper.push(key);
@@ -75,19 +114,10 @@ impl TempResult {
     fn to_element(mut self, eh: &mut ElementHolder, error: &mut ErrorHolder) {
         let root = self.root.clone();
         for k in root {
-            if let Some(o) = self.data.remove(k) {
-                match Mark::try_from(&o.0) {
-                    Ok(m) => {
-                        let e = Element::new(m, o.1, AttributeHolder::from(o.2, error));
-                        let rk = eh.add_root(e);
-                        if let Some(j) = self.subset.remove(k) {
-                            self.to_element0(j, eh, rk, error);
-                        }
-                    }
-                    Err(e) => {
-                        trace!("{e}");
-                        error.push(e);
-                    }
+            if let Some(e) = self.data.remove(k).and_then(|o| o.to_element(error)) {
+                let rk = eh.add_root(e);
+                if let Some(j) = self.subset.remove(k) {
+                    self.to_element0(j, eh, rk, error);
                 }
             }
         }
@@ -101,19 +131,10 @@ impl TempResult {
         error: &mut ErrorHolder,
     ) {
         for k in v {
-            if let Some(o) = self.data.remove(k) {
-                match Mark::try_from(&o.0) {
-                    Ok(m) => {
-                        let e = Element::new(m, o.1, AttributeHolder::from(o.2, error));
-                        if let Some(ek) = eh.add(upper_key, e) {
-                            if let Some(j) = self.subset.remove(k) {
-                                self.to_element0(j, eh, ek, error);
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        trace!("{e}");
-                        error.push(e);
+            if let Some(e) = self.data.remove(k).and_then(|o| o.to_element(error)) {
+                if let Some(ek) = eh.add(upper_key, e) {
+                    if let Some(j) = self.subset.remove(k) {
+                        self.to_element0(j, eh, ek, error);
                     }
                 }
             }
@@ -164,13 +185,13 @@ impl Builder {
 }
 
 impl XParser for Builder {
-    fn start_tag(&mut self, s: String) {
+    fn start_tag(&mut self, s: String, p: (usize, usize)) {
         if s.is_empty() {
             return;
         }
         self.temporary.push(s.clone());
         let step = self.step();
-        let e = (s, String::new(), Default::default());
+        let e = TempElement::new(s, p);
         if step == 0 {
             self.last_one.replace(self.rst.add_root(e));
             self.last_step = step;
@@ -186,40 +207,45 @@ impl XParser for Builder {
         self.last_step = step;
     }
 
-    fn end_tag(&mut self, s: String) {
-        if let Some(_) = self.get_step(s) {}
+    fn end_tag(&mut self, s: String, p: (usize, usize)) {
+        if self.get_step(s.clone()).is_none() {
+            let n = s.chars().count();
+            let e: Error = (ErrorKind::Markup, format!("unmatched tag: {s}")).into();
+            self.error
+                .push(e.with_code(ErrorCode::UnmatchedTag).with_span(p, n));
+        }
     }
 
     fn end_slash(&mut self) {
         self.temporary.pop();
     }
 
-    fn tag_text(&mut self, s: String) {
+    fn tag_text(&mut self, s: String, p: (usize, usize)) {
         if s.is_empty() {
             return;
         }
         let step = self.step();
         if step == self.last_step {
-            if let Some(p) = self.last_one {
-                if let Some(o) = self.rst.data.get_mut(p) {
-                    o.1.push_str(&s);
+            if let Some(k) = self.last_one {
+                if let Some(o) = self.rst.data.get_mut(k) {
+                    o.push_text(&s, p);
                     return;
                 }
             }
         }
         if let Some(k) = self.rst.get_key(step) {
             if let Some(o) = self.rst.data.get_mut(k) {
-                o.1.push_str(&s);
+                o.push_text(&s, p);
             }
         }
     }
 
-    fn attribute(&mut self, k: String, s: String) {
-        if let Some(p) = self.last_one {
-            if let Some(o) = self.rst.data.get_mut(p) {
-                if o.2.contains_key(&k) {
+    fn attribute(&mut self, k: String, s: String, p: (usize, usize)) {
+        if let Some(key) = self.last_one {
+            if let Some(o) = self.rst.data.get_mut(key) {
+                if o.attribute.contains_key(&k) {
                 } else {
-                    o.2.insert(k, s);
+                    o.attribute.insert(k, (s, p));
                 }
             }
         }
@@ -248,29 +274,22 @@ impl s::Output for Builder {
         self.f = false;
     }
 
-    fn tag(&mut self, s: String) {
+    fn tag(&mut self, s: String, p: (usize, usize)) {
         if self.f {
             self.f = false;
         } else {
             self.temporary.pop();
         }
 
-        self.start_tag(s);
+        self.start_tag(s, p);
     }
 
-    fn attribute(&mut self, k: String, v: String) {
-        if let Some(p) = self.last_one {
-            if let Some(o) = self.rst.data.get_mut(p) {
-                if o.2.contains_key(&k) {
-                } else {
-                    o.2.insert(k, v);
-                }
-            }
-        }
+    fn attribute(&mut self, k: String, v: String, p: (usize, usize)) {
+        XParser::attribute(self, k, v, p);
     }
 
-    fn text(&mut self, s: String) {
-        self.tag_text(s);
+    fn text(&mut self, s: String, p: (usize, usize)) {
+        self.tag_text(s, p);
     }
 
     fn error(&mut self, e: Error) {
@@ -288,6 +307,34 @@ pub(super) fn accept_s(s: &str) -> (Option<ElementHolder>, ErrorHolder) {
     s::Parser::new(Builder::default()).parse_str(s).to_element()
 }
 
+pub(super) fn write(eh: &ElementHolder) -> String {
+    w::Writer::new(eh).double()
+}
+
+pub(super) fn write_s(eh: &ElementHolder) -> String {
+    w::Writer::new(eh).single()
+}
+
+fn unescape(s: &str) -> Option<char> {
+    match s {
+        "&amp;" => Some(AMPERSAND),
+        "&apos;" => Some(APOSTROPHE),
+        "&gt;" => Some(GT),
+        "&lt;" => Some(LT),
+        "&nbsp;" => Some(SPACE),
+        "&quot;" => Some(QUOTATION),
+        //numeric references such as `&#32;` and `&#x20;`.
+        _ => {
+            let n = s.strip_prefix("&#")?.strip_suffix(SEMICOLON)?;
+            match n.strip_prefix('x') {
+                Some(h) => u32::from_str_radix(h, 16).ok(),
+                None => n.parse().ok(),
+            }
+            .and_then(char::from_u32)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -297,6 +344,11 @@ mod tests {
     const C: &str = "<a b=0 c d= '1' e=' f=\"2\"/>";
     const D: &str = "<a b=0 c d= '1' e='' f=\"2\">a&amp;0&lt;0&gt;0&quot;0&nbsp;0&apos;  0";
 
+    const E: &str = "<aht><head><title>a&lt;b && c</title></head><body>\
+        <button class=\"a &quot;b&quot;\" position=1,2>ok</button>\
+        <area column=[100,20%],3><pt>x</pt><area><img></img></area></area><inp/>\
+        </body><style>body{}</style><script></script></aht>";
+
     #[test]
     fn build() {
         println!("{:?}", accept(A));
@@ -304,4 +356,163 @@ mod tests {
         println!("{:?}", accept(C));
         println!("{:?}", accept_s(D));
     }
+
+    #[test]
+    fn write_0() {
+        let eh = accept(E).0.unwrap();
+        let a = write(&eh);
+        assert_eq!(a, write(&accept(&a).0.unwrap()));
+        assert!(a.contains("a&lt;b &amp;&amp; c"));
+
+        let b = write_s(&eh);
+        assert_eq!(b, write_s(&accept_s(&b).0.unwrap()));
+        assert_eq!(a, write(&accept_s(&b).0.unwrap()));
+    }
+
+    #[test]
+    fn write_space() {
+        let mut eh = accept(E).0.unwrap();
+        let k = eh.query_one("pt").unwrap().unwrap();
+        *eh.get_mut(k).unwrap().text_mut() = "  x &\ty \n".to_string();
+        let text = |eh: ElementHolder| {
+            eh.text(eh.query_one("pt").unwrap().unwrap())
+                .map(String::from)
+        };
+
+        let a = write(&eh);
+        assert!(a.contains("<pt>&#32;&#32;x &amp;\ty&#32;&#10;</pt>"));
+        assert_eq!(text(accept(&a).0.unwrap()).unwrap(), "  x &\ty \n");
+        let b = write_s(&eh);
+        assert_eq!(text(accept_s(&b).0.unwrap()).unwrap(), "  x &\ty \n");
+
+        let eh = accept("<pt> \n x y \n </pt>").0.unwrap();
+        assert_eq!(eh.text(eh.roots()[0]), Some("x y"));
+    }
+
+    #[test]
+    fn write_attribute() {
+        let s = "<area background=\"#ff000080\" color=\"rgba(0, 128, 255, 0.5)\" border-color=red \
+            width=\"calc(50vw + 2em)\" height=\"clamp(100, 20%, 300)\" margin=\"10%, min(1em, 8)\" \
+            column=\"240 1fr minmax(100, 1fr) repeat(2, auto)\" row=\"[240, 100%-240],3\" \
+            transition=\"300, ease-out\" animation=\"pulse, 1000, ease-in-out, alternate\" \
+            position=\"(50%-10, max(1, 2), 3)\" border-width=\"1, 2\"></area>";
+        let attrs = |eh: &ElementHolder| {
+            let e = eh.get(eh.roots()[0]).unwrap();
+            let mut v: Vec<_> = e
+                .attribute()
+                .iter()
+                .map(|(n, a)| (n.to_string(), a.to_string()))
+                .collect();
+            v.sort();
+            v
+        };
+        let (eh, err) = accept(s);
+        assert!(err.is_empty(), "{err}");
+        let eh = eh.unwrap();
+        let v = attrs(&eh);
+        assert_eq!(v.len(), 12);
+
+        let a = write(&eh);
+        let (o, err) = accept(&a);
+        assert!(err.is_empty(), "{err}{a}");
+        assert_eq!(attrs(&o.unwrap()), v);
+
+        let b = write_s(&eh);
+        let (o, err) = accept_s(&b);
+        assert!(err.is_empty(), "{err}{b}");
+        assert_eq!(attrs(&o.unwrap()), v);
+    }
+
+    #[test]
+    fn query() {
+        let eh = accept(E).0.unwrap();
+        assert_eq!(eh.query("area").unwrap().len(), 2);
+        let k = eh.query_one("area ?1 pt").unwrap().unwrap();
+        assert_eq!(eh.text(k), Some("x"));
+        assert_eq!(eh.query("area ?2 img").unwrap().len(), 1);
+        assert!(eh.query("body + pt").unwrap().is_empty());
+        assert!(eh.query("").is_err());
+        assert!(eh.query("foo").is_err());
+    }
+
+    #[test]
+    fn selector() {
+        use crate::style::{StyleContext, parse_selector};
+        let mut eh = accept(E).0.unwrap();
+        let len = |s| eh.query(s).unwrap().len();
+        assert_eq!(len("body > area"), 1);
+        assert_eq!(len("area > area"), 1);
+        assert_eq!(len("pt, img"), 2);
+        assert_eq!(len(".class=a"), 0);
+        assert_eq!(len(".class^=a"), 1);
+        assert_eq!(len(".class~=a"), 1);
+        assert_eq!(len(".class~=b"), 0);
+        assert_eq!(len(".class*=b"), 1);
+        assert_eq!(len("body > :nth-child(2n+1)"), 2);
+        assert_eq!(len("body > :not(area, button)"), 1);
+        let one = |s| {
+            eh.query_one(s)
+                .unwrap()
+                .map(|k| eh.get(k).unwrap().as_str().to_string())
+        };
+        assert_eq!(one("body > :first").as_deref(), Some("button"));
+        assert_eq!(one("body > :last").as_deref(), Some("inp"));
+        assert_eq!(one("body > :nth-child(2)").as_deref(), Some("area"));
+
+        let s = "body > :nth-child(odd), .a:not(pt)";
+        let o = parse_selector(s).unwrap();
+        assert_eq!(o.to_string(), "body > :nth-child(2n+1), .a:not(pt)");
+        assert!(parse_selector(":not(area pt)").is_err());
+        let o = parse_selector("pt .class*=b, .class~=b").unwrap();
+        assert_eq!(o.to_string(), "pt .class*=b, .class~=b");
+
+        let (mut sc, err) = StyleContext::new("pt, img {width:5;}");
+        assert!(err.is_empty());
+        sc.set_style(&mut eh);
+        let img = eh.query_one("img").unwrap().unwrap();
+        assert_eq!(sc.origin(img, &AttrName::WIDTH).unwrap().selector(), "img");
+        let pt = eh.query_one("pt").unwrap().unwrap();
+        assert_eq!(sc.origin(pt, &AttrName::WIDTH).unwrap().selector(), "pt");
+    }
+
+    #[test]
+    fn error_0() {
+        let s = "<aht>\n  <foo a=1></foo>\n  <pt b=1>x</pt>\n</aht>";
+        let err = accept(s).1;
+        let e = err
+            .iter()
+            .find(|e| e.code() == Some(ErrorCode::InvalidMark));
+        let e = e.unwrap();
+        assert_eq!(e.position(), Some((1, 3)));
+        assert_eq!(e.length(), 3);
+        assert!(e.render(s).contains("2:4"));
+        assert!(e.render(s).contains(" |    ^^^"));
+
+        let e = err
+            .iter()
+            .find(|e| e.code() == Some(ErrorCode::InvalidAttribute));
+        assert_eq!(e.unwrap().position(), Some((2, 6)));
+        assert!(
+            err.to_json()
+                .contains("\"code\":\"E004\",\"line\":3,\"column\":7")
+        );
+    }
+
+    #[test]
+    fn error_style() {
+        let s = "<aht><head></head><body></body>\n\
+            <style>pt{foo:1;} bar{}</style><script></script></aht>";
+        let err = crate::page::Page::parse(s).1;
+        let find = |c| err.iter().find(|e| e.code() == Some(c)).unwrap();
+
+        //positions in the style sheet are moved to the page source.
+        let e = find(ErrorCode::InvalidAttribute);
+        assert_eq!(e.position(), Some((1, 10)));
+        assert_eq!(e.length(), 3);
+        assert!(e.render(s).contains("2:11"));
+
+        let e = find(ErrorCode::InvalidMark);
+        assert_eq!(e.position(), Some((1, 18)));
+        assert_eq!(e.length(), 3);
+    }
 }
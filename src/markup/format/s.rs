@@ -7,11 +7,11 @@ pub(super) trait Output {
 
     fn upper(&mut self, n: usize);
 
-    fn tag(&mut self, s: String);
+    fn tag(&mut self, s: String, p: (usize, usize));
 
-    fn attribute(&mut self, k: String, v: String);
+    fn attribute(&mut self, k: String, v: String, p: (usize, usize));
 
-    fn text(&mut self, s: String);
+    fn text(&mut self, s: String, p: (usize, usize));
 
     fn error(&mut self, e: Error);
 }
@@ -26,6 +26,8 @@ where
     counter: CharCounter,
     temporary: (String, String),
     temporary_escape: String,
+    p: (usize, usize),
+    text_p: Option<(usize, usize)>,
     output: T,
 }
 
@@ -41,6 +43,8 @@ where
             counter: Default::default(),
             temporary: (String::new(), String::new()),
             temporary_escape: String::new(),
+            p: (0, 0),
+            text_p: None,
             output,
         }
     }
@@ -97,14 +101,15 @@ where
     fn output_tag(&mut self) {
         if !self.temporary.0.is_empty() {
             let s = self.temporary.0.drain(..).as_str().to_lowercase();
-            self.output.tag(s);
+            self.output.tag(s, self.p);
         }
     }
 
     fn output_text(&mut self) {
         let s = self.temporary.0.drain(..).as_str().to_string();
+        let p = self.text_p.take();
         if !s.is_empty() {
-            self.output.text(s);
+            self.output.text(s, p.unwrap_or(self.p));
         }
     }
 
@@ -112,15 +117,33 @@ where
         let k = self.temporary.0.drain(..).as_str().to_lowercase();
         let v = self.temporary.1.drain(..).as_str().to_string();
         if !k.is_empty() {
-            self.output.attribute(k, v);
+            self.output.attribute(k, v, self.p);
         }
     }
 
-    fn output_error(&mut self, s: &str) {
-        let e = self.counter.to_error(ErrorKind::Markup, s);
+    fn output_error(&mut self, code: ErrorCode) {
+        let e = self
+            .counter
+            .to_error(
+                ErrorKind::Markup,
+                format!("{}: {:?}", code.describe(), self.c),
+            )
+            .with_code(code);
         self.output.error(e);
     }
 
+    fn mark_p(&mut self) {
+        if self.temporary.0.is_empty() {
+            self.p = self.counter.position();
+        }
+    }
+
+    fn mark_text_p(&mut self) {
+        if self.text_p.is_none() {
+            self.text_p.replace(self.counter.position());
+        }
+    }
+
     fn ignore(&mut self) {
         match self.c {
             SPACE | LF | CR => {}
@@ -145,26 +168,8 @@ where
         let c = self.c;
         self.temporary_escape.push(c);
         if c == SEMICOLON {
-            match self.temporary_escape.drain(..).as_str() {
-                "&amp;" => {
-                    self.temporary.1.push(AMPERSAND);
-                }
-                "&apos;" => {
-                    self.temporary.1.push(APOSTROPHE);
-                }
-                "&gt;" => {
-                    self.temporary.1.push(GT);
-                }
-                "&lt;" => {
-                    self.temporary.1.push(LT);
-                }
-                "&nbsp;" => {
-                    self.temporary.1.push(SPACE);
-                }
-                "&quot;" => {
-                    self.temporary.1.push(QUOTATION);
-                }
-                _ => {}
+            if let Some(c) = unescape(self.temporary_escape.drain(..).as_str()) {
+                self.temporary.1.push(c);
             }
             self.current_function = self.next_function;
         }
@@ -177,7 +182,7 @@ where
                 self.current_function = Self::tag_1;
             }
             _ => {
-                self.output_error("illegal char");
+                self.output_error(ErrorCode::IllegalChar);
                 self.current_function = Self::tag_0_1;
             }
         }
@@ -210,7 +215,7 @@ where
                 self.current_function = Self::tag_4;
             }
             LF | CR => {
-                self.output_error("illegal char");
+                self.output_error(ErrorCode::IllegalChar);
             }
             _ => {
                 self.current_function = Self::tag_2;
@@ -222,7 +227,7 @@ where
     fn tag_1_1(&mut self) {
         match self.c {
             LF | CR => {
-                self.output_error("illegal char");
+                self.output_error(ErrorCode::IllegalChar);
                 self.current_function = Self::ignore;
                 self.next_function = Self::tag_2;
             }
@@ -237,6 +242,7 @@ where
         let c = self.c;
         match c {
             'A'..='Z' | 'a'..='z' => {
+                self.mark_p();
                 self.temporary.0.push(c);
             }
             GT => {
@@ -254,7 +260,7 @@ where
                 self.current_function = Self::tag_6;
             }
             _ => {
-                self.output_error("invalid mark");
+                self.output_error(ErrorCode::InvalidMark);
                 self.current_function = Self::tag_2_1;
             }
         }
@@ -348,7 +354,7 @@ where
                 self.next_function = Self::text_0;
             }
             EQUAL => {
-                self.output_error("no attribute name");
+                self.output_error(ErrorCode::NoAttributeName);
                 self.current_function = Self::attribute_0_1;
             }
             _ => {
@@ -389,6 +395,7 @@ where
                 self.attribute_3();
             }
             _ => {
+                self.mark_p();
                 self.temporary.0.push(c);
             }
         }
@@ -497,13 +504,14 @@ where
             GT => {
                 self.output_text();
                 self.current_function = Self::text_0_1;
-                self.output_error("illegal char");
+                self.output_error(ErrorCode::IllegalChar);
             }
             SPACE | LF | CR => {
                 self.current_function = Self::text_1;
                 self.text_1();
             }
             AMPERSAND => {
+                self.mark_text_p();
                 self.current_function = Self::escaping;
                 self.next_function = Self::text_2;
                 self.escaping();
@@ -512,6 +520,7 @@ where
                 self.output_text();
             }
             _ => {
+                self.mark_text_p();
                 self.temporary.0.push(c);
             }
         }
@@ -0,0 +1,126 @@
+use super::*;
+
+const INDENT: &str = "    ";
+
+pub(super) struct Writer<'a> {
+    eh: &'a ElementHolder,
+    buf: String,
+    last: Option<usize>,
+}
+
+impl<'a> Writer<'a> {
+    pub(super) fn new(eh: &'a ElementHolder) -> Self {
+        Self {
+            eh,
+            buf: String::new(),
+            last: None,
+        }
+    }
+
+    pub(super) fn double(mut self) -> String {
+        for &k in self.eh.roots() {
+            self.double0(k, 0);
+        }
+        self.buf
+    }
+
+    pub(super) fn single(mut self) -> String {
+        for &k in self.eh.roots() {
+            self.single0(k, 0);
+        }
+        self.buf
+    }
+
+    fn indent(&mut self, n: usize) {
+        for _ in 0..n {
+            self.buf.push_str(INDENT);
+        }
+    }
+
+    fn attributes(&mut self, e: &Element) {
+        let mut v: Vec<&Attribute> = e.attribute().values().collect();
+        v.sort_by_cached_key(|a| a.name().as_str().to_string());
+        for a in v {
+            self.buf.push(SPACE);
+            self.buf.push_str(a.name().as_str());
+            self.buf.push(EQUAL);
+            self.buf.push(QUOTATION);
+            escape(&a.to_string(), &mut self.buf);
+            self.buf.push(QUOTATION);
+        }
+    }
+
+    fn double0(&mut self, k: ElementKey, n: usize) {
+        let e = option_return!(self.eh.get(k));
+        self.indent(n);
+        self.buf.push(LT);
+        self.buf.push_str(e.as_str());
+        self.attributes(e);
+        self.buf.push(GT);
+        escape_text(e.text(), &mut self.buf);
+        if !e.subset().is_empty() {
+            self.buf.push(LF);
+            for &k in e.subset() {
+                self.double0(k, n + 1);
+            }
+            self.indent(n);
+        }
+        self.buf.push(LT);
+        self.buf.push(SLASH);
+        self.buf.push_str(e.as_str());
+        self.buf.push(GT);
+        self.buf.push(LF);
+    }
+
+    fn single0(&mut self, k: ElementKey, n: usize) {
+        let e = option_return!(self.eh.get(k));
+        self.indent(n);
+        self.buf.push(LT);
+        match self.last {
+            Some(m) if m < n => {
+                self.buf.push(SPACE);
+            }
+            Some(m) if m > n => {
+                self.buf.push(CIRCUMFLEX_ACCENT);
+                self.buf.push_str(&(m - n).to_string());
+                self.buf.push(SPACE);
+            }
+            _ => {}
+        }
+        self.buf.push_str(e.as_str());
+        self.attributes(e);
+        self.buf.push(GT);
+        escape_text(e.text(), &mut self.buf);
+        self.buf.push(LF);
+        self.last.replace(n);
+        for &k in e.subset() {
+            self.single0(k, n + 1);
+        }
+    }
+}
+
+//the whitespace before and after text is trimmed in parsing, so it's written as references.
+fn escape_text(s: &str, buf: &mut String) {
+    let t = s.trim();
+    let start = s.len() - s.trim_start().len();
+    for c in s[..start].chars() {
+        buf.push_str(&format!("&#{};", c as u32));
+    }
+    escape(t, buf);
+    for c in s[start + t.len()..].chars() {
+        buf.push_str(&format!("&#{};", c as u32));
+    }
+}
+
+fn escape(s: &str, buf: &mut String) {
+    for c in s.chars() {
+        match c {
+            AMPERSAND => buf.push_str("&amp;"),
+            APOSTROPHE => buf.push_str("&apos;"),
+            GT => buf.push_str("&gt;"),
+            LT => buf.push_str("&lt;"),
+            QUOTATION => buf.push_str("&quot;"),
+            _ => buf.push(c),
+        }
+    }
+}
@@ -2,15 +2,15 @@ use super::*;
 use std::mem::take;
 
 pub(super) trait XParser {
-    fn start_tag(&mut self, s: String);
+    fn start_tag(&mut self, s: String, p: (usize, usize));
 
-    fn end_tag(&mut self, s: String);
+    fn end_tag(&mut self, s: String, p: (usize, usize));
 
     fn end_slash(&mut self);
 
-    fn tag_text(&mut self, s: String);
+    fn tag_text(&mut self, s: String, p: (usize, usize));
 
-    fn attribute(&mut self, k: String, v: String);
+    fn attribute(&mut self, k: String, v: String, p: (usize, usize));
 
     fn error(&mut self, e: Error);
 }
@@ -23,6 +23,11 @@ pub(super) struct Context<'a> {
     temporary: String,
     temporary_attr: (String, String),
     temporary_escape: String,
+    p: (usize, usize),
+    attr_p: (usize, usize),
+    text_p: Option<(usize, usize)>,
+    //the length of text up to the last char kept, the whitespace after it is trimmed.
+    text_end: usize,
     parser: Box<&'a mut dyn XParser>,
 }
 
@@ -36,6 +41,10 @@ impl<'a> Context<'a> {
             temporary: String::new(),
             temporary_attr: (String::new(), String::new()),
             temporary_escape: String::new(),
+            p: (0, 0),
+            attr_p: (0, 0),
+            text_p: None,
+            text_end: 0,
             parser: Box::new(parser),
         }
     }
@@ -64,14 +73,14 @@ impl<'a> Context<'a> {
         let k = self.attribute_k();
         let v = self.attribute_v();
         if k.len() > 0 {
-            self.parser.attribute(k, v);
+            self.parser.attribute(k, v, self.attr_p);
         }
     }
 
     fn attribute_push(&mut self) {
         let k = self.attribute_k();
         if k.len() > 0 {
-            self.parser.attribute(k, String::new());
+            self.parser.attribute(k, String::new(), self.attr_p);
         }
         let v = self.attribute_v();
         if v.len() > 0 {
@@ -79,8 +88,19 @@ impl<'a> Context<'a> {
         }
     }
 
-    fn output_error(&mut self, s: &str) {
-        let e = self.counter.to_error(ErrorKind::Markup, s);
+    fn start_tag(&mut self) {
+        let t = self.temporary.drain(..).collect();
+        self.parser.start_tag(t, self.p);
+    }
+
+    fn output_error(&mut self, code: ErrorCode) {
+        let e = self
+            .counter
+            .to_error(
+                ErrorKind::Markup,
+                format!("{}: {:?}", code.describe(), self.c),
+            )
+            .with_code(code);
         self.parser.error(e);
     }
 }
@@ -123,17 +143,64 @@ fn escaping(context: &mut Context) {
     }
 }
 
+fn escaping_text(context: &mut Context) {
+    let c = context.c;
+    if c == SEMICOLON {
+        context.temporary_escape.push(c);
+        let s = context.temporary_escape.drain(..).collect::<String>();
+        if let Some(c) = unescape(&s) {
+            context.temporary.push(c);
+        } else {
+            context.temporary.push_str(&s);
+        }
+        //the escaped whitespace is kept.
+        context.text_end = context.temporary.len();
+        context.current_function = context.next_function;
+    } else if context.temporary_escape.is_empty()
+        || (context.temporary_escape.len() < 8 && (c.is_ascii_alphanumeric() || c == NUMBER_SIGN))
+    {
+        context.temporary_escape.push(c);
+    } else {
+        //not an escape sequence, keeps it as it is.
+        let s = context.temporary_escape.drain(..).collect::<String>();
+        context.temporary.push_str(&s);
+        context.text_end = context.temporary.len();
+        context.current_function = context.next_function;
+        (context.next_function)(context);
+    }
+}
+
 fn tag_0(context: &mut Context) {
     let c = context.c;
     if c == LT {
         context.current_function = series_space;
         context.next_function = tag_1;
         if !context.temporary.is_empty() {
-            let s = context.temporary.drain(..).as_str().trim().to_string();
-            context.parser.tag_text(s);
+            let mut s = take(&mut context.temporary);
+            s.truncate(context.text_end);
+            if let Some(p) = context.text_p.take() {
+                context.parser.tag_text(s, p);
+            }
+        }
+        context.text_end = 0;
+    } else if c == AMPERSAND {
+        if context.text_p.is_none() {
+            context.text_p.replace(context.counter.position());
         }
+        context.current_function = escaping_text;
+        context.next_function = tag_0;
+        escaping_text(context);
     } else {
-        context.temporary.push(c);
+        //the whitespace before and after text is trimmed.
+        if !c.is_whitespace() {
+            if context.text_p.is_none() {
+                context.text_p.replace(context.counter.position());
+            }
+            context.temporary.push(c);
+            context.text_end = context.temporary.len();
+        } else if context.text_p.is_some() {
+            context.temporary.push(c);
+        }
     }
 }
 
@@ -145,6 +212,7 @@ fn tag_1(context: &mut Context) {
     } else if c == GT {
         context.current_function = tag_0;
     } else {
+        context.p = context.counter.position();
         context.current_function = tag_3;
         tag_3(context);
     }
@@ -155,10 +223,13 @@ fn tag_2(context: &mut Context) {
     if c == GT {
         if !context.temporary.is_empty() {
             let t = context.temporary.drain(..).collect();
-            context.parser.end_tag(t);
+            context.parser.end_tag(t, context.p);
         }
         context.current_function = tag_0;
     } else {
+        if context.temporary.is_empty() {
+            context.p = context.counter.position();
+        }
         context.temporary.push(c);
     }
 }
@@ -166,18 +237,15 @@ fn tag_2(context: &mut Context) {
 fn tag_3(context: &mut Context) {
     let c = context.c;
     if c == GT {
-        let t = context.temporary.drain(..).collect();
-        context.parser.start_tag(t);
+        context.start_tag();
         context.current_function = tag_0;
     } else if c == SLASH {
-        let t = context.temporary.drain(..).collect();
-        context.parser.start_tag(t);
+        context.start_tag();
         context.parser.end_slash();
         context.current_function = series_space;
         context.next_function = tag_4;
     } else if c == SPACE {
-        let t = context.temporary.drain(..).collect();
-        context.parser.start_tag(t);
+        context.start_tag();
         context.current_function = series_space;
         context.next_function = tag_5;
     } else {
@@ -190,7 +258,7 @@ fn tag_4(context: &mut Context) {
     if c == GT {
         context.current_function = tag_0;
     } else {
-        context.output_error("illegal char");
+        context.output_error(ErrorCode::IllegalChar);
     }
 }
 
@@ -219,6 +287,9 @@ fn attribute_0(context: &mut Context) {
     } else if c == EQUAL {
         attribute_1(context);
     } else {
+        if context.temporary_attr.0.is_empty() {
+            context.attr_p = context.counter.position();
+        }
         context.temporary_attr.0.push(c);
     }
 }
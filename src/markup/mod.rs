@@ -85,6 +85,35 @@ impl Mark {
             Self::VIDEO => VIDEO,
         }
     }
+
+    ///Returns true if the attribute takes effect on this kind of element.
+    pub fn accepts(&self, a: &AttrName) -> bool {
+        match a {
+            AttrName::CLASS | AttrName::ID | AttrName::LANG => true,
+            AttrName::ALIGN
+            | AttrName::AREAS
+            | AttrName::COLUMN
+            | AttrName::FLOW
+            | AttrName::GAP
+            | AttrName::JUSTIFY
+            | AttrName::ROW => matches!(self, Self::AREA | Self::BODY),
+            AttrName::ACTION | AttrName::ENCTYPE | AttrName::METHOD => matches!(self, Self::FORM),
+            AttrName::HREF => matches!(self, Self::BUTTON),
+            AttrName::MULTIPLE => matches!(self, Self::SELECT),
+            AttrName::NAME => matches!(self, Self::BUTTON | Self::FORM | Self::INP | Self::SELECT),
+            AttrName::READONLY | AttrName::REQUIRED => matches!(self, Self::INP | Self::SELECT),
+            AttrName::SELECTED => matches!(self, Self::OPTION),
+            AttrName::SRC => matches!(self, Self::AUDIO | Self::IFRAME | Self::IMG | Self::VIDEO),
+            AttrName::TYPE => matches!(self, Self::SCRIPT),
+            AttrName::VALUE => {
+                matches!(self, Self::BUTTON | Self::INP | Self::OPTION | Self::SELECT)
+            }
+            _ => !matches!(
+                self,
+                Self::AHT | Self::HEAD | Self::SCRIPT | Self::STYLE | Self::TITLE
+            ),
+        }
+    }
 }
 
 impl FromStr for Mark {
@@ -133,29 +162,52 @@ impl TryFrom<&String> for Mark {
 }
 
 const ACTION: &str = "action";
+const ALIGN: &str = "align";
+const ANIMATION: &str = "animation";
+const AREAS: &str = "areas";
+const BACKGROUND: &str = "background";
+const BORDER_COLOR: &str = "border-color";
+const BORDER_WIDTH: &str = "border-width";
 const CLASS: &str = "class";
+const COLOR: &str = "color";
 const COLUMN: &str = "column";
+const COLUMN_SPAN: &str = "column-span";
 const DISABLED: &str = "disabled";
 const ENCTYPE: &str = "enctype";
+const FLOW: &str = "flow";
+const FONT_FAMILY: &str = "font-family";
+const FONT_SIZE: &str = "font-size";
+const FONT_WEIGHT: &str = "font-weight";
+const GAP: &str = "gap";
 const HEIGHT: &str = "height";
 const HIDDEN: &str = "hidden";
 const HREF: &str = "href";
 const ID: &str = "id";
+const JUSTIFY: &str = "justify";
 const LANG: &str = "lang";
+const MARGIN: &str = "margin";
 const METHOD: &str = "method";
 const MULTIPLE: &str = "multiple";
 const NAME: &str = "name";
+const OPACITY: &str = "opacity";
 const ORDINAL: &str = "ordinal";
+const OVERLAY: &str = "overlay";
+const PADDING: &str = "padding";
+const PLACE: &str = "place";
 const POSITION: &str = "position";
+const RADIUS: &str = "radius";
 const READONLY: &str = "readonly";
 const REQUIRED: &str = "required";
 const ROW: &str = "row";
+const ROW_SPAN: &str = "row-span";
 const SELECTED: &str = "selected";
 const SRC: &str = "src";
 const TIP: &str = "tip";
+const TRANSITION: &str = "transition";
 const TYPE: &str = "type";
 const VALUE: &str = "value";
 const WIDTH: &str = "width";
+const Z: &str = "z";
 
 const ONABORT: &str = "onabort";
 const ONBLUR: &str = "onblur";
@@ -173,29 +225,52 @@ const ONSCROLL: &str = "onscroll";
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum AttrName {
     ACTION,
+    ALIGN,
+    ANIMATION,
+    AREAS,
+    BACKGROUND,
+    BORDER_COLOR,
+    BORDER_WIDTH,
     CLASS,
+    COLOR,
     COLUMN,
+    COLUMN_SPAN,
     DISABLED,
     ENCTYPE,
+    FLOW,
+    FONT_FAMILY,
+    FONT_SIZE,
+    FONT_WEIGHT,
+    GAP,
     HEIGHT,
     HIDDEN,
     HREF,
     ID,
+    JUSTIFY,
     LANG,
+    MARGIN,
     METHOD,
     MULTIPLE,
     NAME,
+    OPACITY,
     ORDINAL,
+    OVERLAY,
+    PADDING,
+    PLACE,
     POSITION,
+    RADIUS,
     READONLY,
     REQUIRED,
     ROW,
+    ROW_SPAN,
     SELECTED,
     SRC,
     TIP,
+    TRANSITION,
     TYPE,
     VALUE,
     WIDTH,
+    Z,
     ONABORT,
     ONBLUR,
     ONCANCEL,
@@ -214,29 +289,52 @@ impl AttrName {
     pub fn as_str(&self) -> &str {
         match self {
             Self::ACTION => ACTION,
+            Self::ALIGN => ALIGN,
+            Self::ANIMATION => ANIMATION,
+            Self::AREAS => AREAS,
+            Self::BACKGROUND => BACKGROUND,
+            Self::BORDER_COLOR => BORDER_COLOR,
+            Self::BORDER_WIDTH => BORDER_WIDTH,
             Self::CLASS => CLASS,
+            Self::COLOR => COLOR,
             Self::COLUMN => COLUMN,
+            Self::COLUMN_SPAN => COLUMN_SPAN,
             Self::DISABLED => DISABLED,
             Self::ENCTYPE => ENCTYPE,
+            Self::FLOW => FLOW,
+            Self::FONT_FAMILY => FONT_FAMILY,
+            Self::FONT_SIZE => FONT_SIZE,
+            Self::FONT_WEIGHT => FONT_WEIGHT,
+            Self::GAP => GAP,
             Self::HEIGHT => HEIGHT,
             Self::HIDDEN => HIDDEN,
             Self::HREF => HREF,
             Self::ID => ID,
+            Self::JUSTIFY => JUSTIFY,
             Self::LANG => LANG,
+            Self::MARGIN => MARGIN,
             Self::METHOD => METHOD,
             Self::MULTIPLE => MULTIPLE,
             Self::NAME => NAME,
+            Self::OPACITY => OPACITY,
             Self::ORDINAL => ORDINAL,
+            Self::OVERLAY => OVERLAY,
+            Self::PADDING => PADDING,
+            Self::PLACE => PLACE,
             Self::POSITION => POSITION,
+            Self::RADIUS => RADIUS,
             Self::READONLY => READONLY,
             Self::REQUIRED => REQUIRED,
             Self::ROW => ROW,
+            Self::ROW_SPAN => ROW_SPAN,
             Self::SELECTED => SELECTED,
             Self::SRC => SRC,
             Self::TIP => TIP,
+            Self::TRANSITION => TRANSITION,
             Self::TYPE => TYPE,
             Self::VALUE => VALUE,
             Self::WIDTH => WIDTH,
+            Self::Z => Z,
             Self::ONABORT => ONABORT,
             Self::ONBLUR => ONBLUR,
             Self::ONCANCEL => ONCANCEL,
@@ -250,6 +348,29 @@ impl AttrName {
             Self::ONSCROLL => ONSCROLL,
         }
     }
+
+    ///Returns true if the value can be interpolated by transition and animation.
+    pub fn is_animatable(&self) -> bool {
+        matches!(
+            self,
+            Self::BACKGROUND
+                | Self::BORDER_COLOR
+                | Self::COLOR
+                | Self::HEIGHT
+                | Self::OPACITY
+                | Self::POSITION
+                | Self::RADIUS
+                | Self::WIDTH
+        )
+    }
+
+    ///Returns true if it only changes how an element looks, not its place or size.
+    pub fn is_paint_only(&self) -> bool {
+        matches!(
+            self,
+            Self::BACKGROUND | Self::BORDER_COLOR | Self::COLOR | Self::OPACITY | Self::RADIUS
+        )
+    }
 }
 
 impl FromStr for AttrName {
@@ -258,29 +379,52 @@ impl FromStr for AttrName {
     fn from_str(s: &str) -> Result<Self> {
         match s {
             ACTION => Ok(Self::ACTION),
+            ALIGN => Ok(Self::ALIGN),
+            ANIMATION => Ok(Self::ANIMATION),
+            AREAS => Ok(Self::AREAS),
+            BACKGROUND => Ok(Self::BACKGROUND),
+            BORDER_COLOR => Ok(Self::BORDER_COLOR),
+            BORDER_WIDTH => Ok(Self::BORDER_WIDTH),
             CLASS => Ok(Self::CLASS),
+            COLOR => Ok(Self::COLOR),
             COLUMN => Ok(Self::COLUMN),
+            COLUMN_SPAN => Ok(Self::COLUMN_SPAN),
             DISABLED => Ok(Self::DISABLED),
             ENCTYPE => Ok(Self::ENCTYPE),
+            FLOW => Ok(Self::FLOW),
+            FONT_FAMILY => Ok(Self::FONT_FAMILY),
+            FONT_SIZE => Ok(Self::FONT_SIZE),
+            FONT_WEIGHT => Ok(Self::FONT_WEIGHT),
+            GAP => Ok(Self::GAP),
             HEIGHT => Ok(Self::HEIGHT),
             HIDDEN => Ok(Self::HIDDEN),
             HREF => Ok(Self::HREF),
             ID => Ok(Self::ID),
+            JUSTIFY => Ok(Self::JUSTIFY),
             LANG => Ok(Self::LANG),
+            MARGIN => Ok(Self::MARGIN),
             METHOD => Ok(Self::METHOD),
             MULTIPLE => Ok(Self::MULTIPLE),
             NAME => Ok(Self::NAME),
+            OPACITY => Ok(Self::OPACITY),
             ORDINAL => Ok(Self::ORDINAL),
+            OVERLAY => Ok(Self::OVERLAY),
+            PADDING => Ok(Self::PADDING),
+            PLACE => Ok(Self::PLACE),
             POSITION => Ok(Self::POSITION),
+            RADIUS => Ok(Self::RADIUS),
             READONLY => Ok(Self::READONLY),
             REQUIRED => Ok(Self::REQUIRED),
             ROW => Ok(Self::ROW),
+            ROW_SPAN => Ok(Self::ROW_SPAN),
             SELECTED => Ok(Self::SELECTED),
             SRC => Ok(Self::SRC),
             TIP => Ok(Self::TIP),
+            TRANSITION => Ok(Self::TRANSITION),
             TYPE => Ok(Self::TYPE),
             VALUE => Ok(Self::VALUE),
             WIDTH => Ok(Self::WIDTH),
+            Z => Ok(Self::Z),
             ONABORT => Ok(Self::ONABORT),
             ONBLUR => Ok(Self::ONBLUR),
             ONCANCEL => Ok(Self::ONCANCEL),
@@ -317,29 +461,52 @@ impl From<&Attribute> for AttrName {
     fn from(a: &Attribute) -> Self {
         match a {
             Attribute::ACTION(_) => Self::ACTION,
+            Attribute::ALIGN(_) => Self::ALIGN,
+            Attribute::ANIMATION(_) => Self::ANIMATION,
+            Attribute::AREAS(_) => Self::AREAS,
+            Attribute::BACKGROUND(_) => Self::BACKGROUND,
+            Attribute::BORDER_COLOR(_) => Self::BORDER_COLOR,
+            Attribute::BORDER_WIDTH(_) => Self::BORDER_WIDTH,
             Attribute::CLASS(_) => Self::CLASS,
+            Attribute::COLOR(_) => Self::COLOR,
             Attribute::COLUMN(_) => Self::COLUMN,
+            Attribute::COLUMN_SPAN(_) => Self::COLUMN_SPAN,
             Attribute::DISABLED(_) => Self::DISABLED,
             Attribute::ENCTYPE(_) => Self::ENCTYPE,
+            Attribute::FLOW(_) => Self::FLOW,
+            Attribute::FONT_FAMILY(_) => Self::FONT_FAMILY,
+            Attribute::FONT_SIZE(_) => Self::FONT_SIZE,
+            Attribute::FONT_WEIGHT(_) => Self::FONT_WEIGHT,
+            Attribute::GAP(_) => Self::GAP,
             Attribute::HEIGHT(_) => Self::HEIGHT,
             Attribute::HIDDEN(_) => Self::HIDDEN,
             Attribute::HREF(_) => Self::HREF,
             Attribute::ID(_) => Self::ID,
+            Attribute::JUSTIFY(_) => Self::JUSTIFY,
             Attribute::LANG(_) => Self::LANG,
+            Attribute::MARGIN(_) => Self::MARGIN,
             Attribute::METHOD(_) => Self::METHOD,
             Attribute::MULTIPLE(_) => Self::MULTIPLE,
             Attribute::NAME(_) => Self::NAME,
+            Attribute::OPACITY(_) => Self::OPACITY,
             Attribute::ORDINAL(_) => Self::ORDINAL,
+            Attribute::OVERLAY(_) => Self::OVERLAY,
+            Attribute::PADDING(_) => Self::PADDING,
+            Attribute::PLACE(_) => Self::PLACE,
             Attribute::POSITION(_) => Self::POSITION,
+            Attribute::RADIUS(_) => Self::RADIUS,
             Attribute::READONLY(_) => Self::READONLY,
             Attribute::REQUIRED(_) => Self::REQUIRED,
             Attribute::ROW(_) => Self::ROW,
+            Attribute::ROW_SPAN(_) => Self::ROW_SPAN,
             Attribute::SELECTED(_) => Self::SELECTED,
             Attribute::SRC(_) => Self::SRC,
             Attribute::TIP(_) => Self::TIP,
+            Attribute::TRANSITION(_) => Self::TRANSITION,
             Attribute::TYPE(_) => Self::TYPE,
             Attribute::VALUE(_) => Self::VALUE,
             Attribute::WIDTH(_) => Self::WIDTH,
+            Attribute::Z(_) => Self::Z,
             Attribute::ONABORT(_) => Self::ONABORT,
             Attribute::ONBLUR(_) => Self::ONBLUR,
             Attribute::ONCANCEL(_) => Self::ONCANCEL,
@@ -359,29 +526,52 @@ impl From<&Attribute> for AttrName {
 #[derive(Clone, Debug)]
 pub enum Attribute {
     ACTION(String),
+    ALIGN(FlowAlign),
+    ANIMATION(Animation),
+    AREAS(Areas),
+    BACKGROUND(Rgba),
+    BORDER_COLOR(Rgba),
+    BORDER_WIDTH(Sides),
     CLASS(String),
+    COLOR(Rgba),
     COLUMN(Points),
+    COLUMN_SPAN(usize),
     DISABLED(bool),
     ENCTYPE(String),
-    HEIGHT(Distance),
+    FLOW(Flow),
+    FONT_FAMILY(String),
+    FONT_SIZE(f32),
+    FONT_WEIGHT(u16),
+    GAP(f32),
+    HEIGHT(Length),
     HIDDEN(bool),
     HREF(String),
     ID(String),
+    JUSTIFY(Justify),
     LANG(String),
+    MARGIN(Insets),
     METHOD(String),
     MULTIPLE(bool),
     NAME(String),
+    OPACITY(f32),
     ORDINAL(Ordinal),
-    POSITION(Coord),
+    OVERLAY(bool),
+    PADDING(Insets),
+    PLACE(String),
+    POSITION(Position),
+    RADIUS(f32),
     READONLY(bool),
     REQUIRED(bool),
     ROW(Points),
+    ROW_SPAN(usize),
     SELECTED(bool),
     SRC(String),
     TIP(String),
+    TRANSITION(Transition),
     TYPE(ScriptType),
     VALUE(String),
-    WIDTH(Distance),
+    WIDTH(Length),
+    Z(f32),
     ONABORT(String),
     ONBLUR(String),
     ONCANCEL(String),
@@ -401,29 +591,52 @@ impl Attribute {
         let t = s.as_str();
         match a {
             AttrName::ACTION => Ok(Self::ACTION(take(s))),
+            AttrName::ALIGN => FlowAlign::try_from(t).map(|o| Self::ALIGN(o)),
+            AttrName::ANIMATION => Animation::try_from(t).map(|o| Self::ANIMATION(o)),
+            AttrName::AREAS => Areas::try_from(t).map(|o| Self::AREAS(o)),
+            AttrName::BACKGROUND => Rgba::try_from(t).map(|o| Self::BACKGROUND(o)),
+            AttrName::BORDER_COLOR => Rgba::try_from(t).map(|o| Self::BORDER_COLOR(o)),
+            AttrName::BORDER_WIDTH => Sides::try_from(t).map(|o| Self::BORDER_WIDTH(o)),
             AttrName::CLASS => Ok(Self::CLASS(take(s))),
+            AttrName::COLOR => Rgba::try_from(t).map(|o| Self::COLOR(o)),
             AttrName::COLUMN => Points::try_from(t).map(|o| Self::COLUMN(o)),
+            AttrName::COLUMN_SPAN => to_span(t).map(|o| Self::COLUMN_SPAN(o)),
             AttrName::DISABLED => to_bool(t).map(|o| Self::DISABLED(o)),
             AttrName::ENCTYPE => Ok(Self::ENCTYPE(take(s))),
-            AttrName::HEIGHT => Distance::try_from(t).map(|o| Self::HEIGHT(o)),
+            AttrName::FLOW => Flow::try_from(t).map(|o| Self::FLOW(o)),
+            AttrName::FONT_FAMILY => Ok(Self::FONT_FAMILY(take(s))),
+            AttrName::FONT_SIZE => to_f32(t).map(|o| Self::FONT_SIZE(o)),
+            AttrName::FONT_WEIGHT => to_font_weight(t).map(|o| Self::FONT_WEIGHT(o)),
+            AttrName::GAP => to_f32(t).map(|o| Self::GAP(o)),
+            AttrName::HEIGHT => Length::try_from(t).map(|o| Self::HEIGHT(o)),
             AttrName::HIDDEN => to_bool(t).map(|o| Self::HIDDEN(o)),
             AttrName::HREF => Ok(Self::HREF(take(s))),
             AttrName::ID => Ok(Self::ID(take(s))),
+            AttrName::JUSTIFY => Justify::try_from(t).map(|o| Self::JUSTIFY(o)),
             AttrName::LANG => Ok(Self::LANG(take(s))),
+            AttrName::MARGIN => Insets::try_from(t).map(|o| Self::MARGIN(o)),
             AttrName::METHOD => Ok(Self::METHOD(take(s))),
             AttrName::MULTIPLE => to_bool(t).map(|o| Self::MULTIPLE(o)),
             AttrName::NAME => Ok(Self::NAME(take(s))),
+            AttrName::OPACITY => to_opacity(t).map(|o| Self::OPACITY(o)),
             AttrName::ORDINAL => Ordinal::try_from(t).map(|o| Self::ORDINAL(o)),
-            AttrName::POSITION => Coord::try_from(t).map(|c| Self::POSITION(c)),
+            AttrName::OVERLAY => to_bool(t).map(|o| Self::OVERLAY(o)),
+            AttrName::PADDING => Insets::try_from(t).map(|o| Self::PADDING(o)),
+            AttrName::PLACE => Ok(Self::PLACE(take(s))),
+            AttrName::POSITION => Position::try_from(t).map(|o| Self::POSITION(o)),
+            AttrName::RADIUS => to_f32(t).map(|o| Self::RADIUS(o)),
             AttrName::READONLY => to_bool(t).map(|o| Self::READONLY(o)),
             AttrName::REQUIRED => to_bool(t).map(|o| Self::REQUIRED(o)),
             AttrName::ROW => Points::try_from(t).map(|o| Self::ROW(o)),
+            AttrName::ROW_SPAN => to_span(t).map(|o| Self::ROW_SPAN(o)),
             AttrName::SELECTED => to_bool(t).map(|o| Self::SELECTED(o)),
             AttrName::SRC => Ok(Self::SRC(take(s))),
             AttrName::TIP => Ok(Self::TIP(take(s))),
+            AttrName::TRANSITION => Transition::try_from(t).map(|o| Self::TRANSITION(o)),
             AttrName::TYPE => ScriptType::try_from(t).map(|t| Self::TYPE(t)),
             AttrName::VALUE => Ok(Self::VALUE(take(s))),
-            AttrName::WIDTH => Distance::try_from(t).map(|o| Self::WIDTH(o)),
+            AttrName::WIDTH => Length::try_from(t).map(|o| Self::WIDTH(o)),
+            AttrName::Z => to_f32(t).map(|o| Self::Z(o)),
             AttrName::ONABORT => Ok(Self::ONABORT(take(s))),
             AttrName::ONBLUR => Ok(Self::ONBLUR(take(s))),
             AttrName::ONCANCEL => Ok(Self::ONCANCEL(take(s))),
@@ -448,12 +661,29 @@ impl Attribute {
         self.into()
     }
 
+    ///Returns the value at `t` between 0 and 1 from this to `o`.
+    ///Returns None if they are different attributes or can't be interpolated.
+    pub fn interpolate(&self, o: &Self, t: f32) -> Option<Self> {
+        match (self, o) {
+            (Self::BACKGROUND(a), Self::BACKGROUND(b)) => Some(Self::BACKGROUND(a.mix(b, t))),
+            (Self::BORDER_COLOR(a), Self::BORDER_COLOR(b)) => Some(Self::BORDER_COLOR(a.mix(b, t))),
+            (Self::COLOR(a), Self::COLOR(b)) => Some(Self::COLOR(a.mix(b, t))),
+            (Self::HEIGHT(a), Self::HEIGHT(b)) => a.mix(b, t).map(|o| Self::HEIGHT(o)),
+            (Self::OPACITY(a), Self::OPACITY(b)) => Some(Self::OPACITY(mix(*a, *b, t))),
+            (Self::POSITION(a), Self::POSITION(b)) => a.mix(b, t).map(|o| Self::POSITION(o)),
+            (Self::RADIUS(a), Self::RADIUS(b)) => Some(Self::RADIUS(mix(*a, *b, t))),
+            (Self::WIDTH(a), Self::WIDTH(b)) => a.mix(b, t).map(|o| Self::WIDTH(o)),
+            _ => None,
+        }
+    }
+
     ///Returns true if matches a pattern within the given string.
     pub fn matches(&self, o: &AttrPattern) -> bool {
         let s = self.to_string();
         match o {
             AttrPattern::Eq(o) => &s == o,
             AttrPattern::Contain(o) => s.contains(o),
+            AttrPattern::Word(o) => s.split_whitespace().any(|t| t == o),
             AttrPattern::StartsWith(o) => s.starts_with(o),
             AttrPattern::EndsWith(o) => s.ends_with(o),
         }
@@ -464,29 +694,52 @@ impl std::fmt::Display for Attribute {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let s = match self {
             Attribute::ACTION(o) => o,
+            Attribute::ALIGN(o) => &o.to_string(),
+            Attribute::ANIMATION(o) => &o.to_string(),
+            Attribute::AREAS(o) => &o.to_string(),
+            Attribute::BACKGROUND(o) => &o.to_string(),
+            Attribute::BORDER_COLOR(o) => &o.to_string(),
+            Attribute::BORDER_WIDTH(o) => &o.to_string(),
             Attribute::CLASS(o) => o,
+            Attribute::COLOR(o) => &o.to_string(),
             Attribute::COLUMN(o) => &o.to_string(),
+            Attribute::COLUMN_SPAN(o) => &o.to_string(),
             Attribute::DISABLED(o) => &o.to_string(),
             Attribute::ENCTYPE(o) => o,
+            Attribute::FLOW(o) => &o.to_string(),
+            Attribute::FONT_FAMILY(o) => o,
+            Attribute::FONT_SIZE(o) => &o.to_string(),
+            Attribute::FONT_WEIGHT(o) => &o.to_string(),
+            Attribute::GAP(o) => &o.to_string(),
             Attribute::HEIGHT(o) => &o.to_string(),
             Attribute::HIDDEN(o) => &o.to_string(),
             Attribute::HREF(o) => o,
             Attribute::ID(o) => o,
+            Attribute::JUSTIFY(o) => &o.to_string(),
             Attribute::LANG(o) => o,
+            Attribute::MARGIN(o) => &o.to_string(),
             Attribute::METHOD(o) => o,
             Attribute::MULTIPLE(o) => &o.to_string(),
             Attribute::NAME(o) => o,
+            Attribute::OPACITY(o) => &o.to_string(),
             Attribute::ORDINAL(o) => &o.to_string(),
+            Attribute::OVERLAY(o) => &o.to_string(),
+            Attribute::PADDING(o) => &o.to_string(),
+            Attribute::PLACE(o) => o,
             Attribute::POSITION(o) => &o.to_string(),
+            Attribute::RADIUS(o) => &o.to_string(),
             Attribute::READONLY(o) => &o.to_string(),
             Attribute::REQUIRED(o) => &o.to_string(),
             Attribute::ROW(o) => &o.to_string(),
+            Attribute::ROW_SPAN(o) => &o.to_string(),
             Attribute::SELECTED(o) => &o.to_string(),
             Attribute::SRC(o) => o,
             Attribute::TIP(o) => o,
+            Attribute::TRANSITION(o) => &o.to_string(),
             Attribute::TYPE(o) => &o.to_string(),
             Attribute::VALUE(o) => o,
             Attribute::WIDTH(o) => &o.to_string(),
+            Attribute::Z(o) => &o.to_string(),
             Attribute::ONABORT(o) => o,
             Attribute::ONBLUR(o) => o,
             Attribute::ONCANCEL(o) => o,
@@ -506,8 +759,14 @@ impl std::fmt::Display for Attribute {
 ///Represents pattern for searching in attribute.
 #[derive(Debug)]
 pub enum AttrPattern {
+    ///`.name=value`
     Eq(String),
+    ///`.name*=value`
     Contain(String),
+    ///`.name~=value`, contains the value as a word separated by whitespace.
+    Word(String),
+    ///`.name^=value`
     StartsWith(String),
+    ///`.name$=value`
     EndsWith(String),
 }
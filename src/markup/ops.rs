@@ -290,6 +290,24 @@ impl<'a> Requirement for AttrMatch<'a> {
     }
 }
 
+///Represents whether element is in the state.
+pub struct StateMatch<'a> {
+    s: &'a State,
+}
+
+impl<'a> StateMatch<'a> {
+    ///Creates a new instance.
+    pub fn new(s: &'a State) -> Self {
+        Self { s }
+    }
+}
+
+impl<'a> Requirement for StateMatch<'a> {
+    fn satisfy(&self, e: &Element) -> bool {
+        self.s.is_match(e)
+    }
+}
+
 ///Represents match any element.
 pub struct AnyMatch;
 
@@ -145,6 +145,10 @@ impl FromStr for Coord {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
+        let s = s
+            .trim()
+            .trim_start_matches(LEFT_PARENTHESIS)
+            .trim_end_matches(RIGHT_PARENTHESIS);
         let mut v = Vec::new();
         for o in s.split(COMMA) {
             let o = o.trim();
@@ -197,15 +201,63 @@ impl From<&Coord2D> for Coord {
     }
 }
 
-///Represents distance.
+///The sizes which relative distances refer to, `vw` and `vh` are percentages of the viewport and `em` is the font size.
+#[derive(Clone, Copy, Debug, Default, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct Basis {
+    width: f32,
+    height: f32,
+    em: f32,
+}
+
+impl Basis {
+    pub fn new(width: f32, height: f32, em: f32) -> Self {
+        Self { width, height, em }
+    }
+
+    ///Returns the basis of element, `em` is its font size if it's set.
+    pub(crate) fn of(&self, e: &Element) -> Self {
+        let em = e.font_size().copied().unwrap_or(self.em);
+        Self { em, ..*self }
+    }
+}
+
+const VW: &str = "vw";
+const VH: &str = "vh";
+const EM: &str = "em";
+const CALC: &str = "calc";
+const MIN: &str = "min";
+const MAX: &str = "max";
+const CLAMP: &str = "clamp";
+
+///Represents distance, written as pixels, `%` of the upper, `vw`, `vh`, `em`,
+///or an expression such as `100%-240`, `min(50%, 400)`, `max(...)` and `clamp(min, value, max)`.
 #[derive(Clone, Debug)]
 pub enum Distance {
     Pixel(f32),
     Percentage(f32),
+    ViewWidth(f32),
+    ViewHeight(f32),
+    Em(f32),
+    Sum(Box<Distance>, Box<Distance>),
+    Difference(Box<Distance>, Box<Distance>),
+    Min(Vec<Distance>),
+    Max(Vec<Distance>),
+    Clamp(Box<[Distance; 3]>),
+}
+
+impl Default for Distance {
+    fn default() -> Self {
+        Self::Pixel(0.0)
+    }
 }
 
 impl std::fmt::Display for Distance {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = |v: &[Distance]| {
+            let v: Vec<String> = v.iter().map(|o| o.to_string()).collect();
+            v.join(&COMMA.to_string())
+        };
         match self {
             Distance::Pixel(i) => {
                 write!(f, "{}", i)
@@ -213,6 +265,23 @@ impl std::fmt::Display for Distance {
             Distance::Percentage(i) => {
                 write!(f, "{}{}", i, PER_CENT)
             }
+            Distance::ViewWidth(i) => write!(f, "{i}{VW}"),
+            Distance::ViewHeight(i) => write!(f, "{i}{VH}"),
+            Distance::Em(i) => write!(f, "{i}{EM}"),
+            Distance::Sum(a, b) | Distance::Difference(a, b) => {
+                let op = if matches!(self, Distance::Sum(..)) {
+                    '+'
+                } else {
+                    '-'
+                };
+                match **b {
+                    Distance::Sum(..) | Distance::Difference(..) => write!(f, "{a}{op}({b})"),
+                    _ => write!(f, "{a}{op}{b}"),
+                }
+            }
+            Distance::Min(v) => write!(f, "{MIN}({})", join(v)),
+            Distance::Max(v) => write!(f, "{MAX}({})", join(v)),
+            Distance::Clamp(v) => write!(f, "{CLAMP}({})", join(&v[..])),
         }
     }
 }
@@ -221,34 +290,150 @@ impl Distance {
     pub fn pixel(&self) -> Option<f32> {
         match self {
             Self::Pixel(i) => Some(*i),
-            Self::Percentage(_) => None,
+            _ => None,
         }
     }
 
-    pub fn get(&self, n: f32) -> f32 {
+    ///Returns pixels, `n` is the size of the upper which a percentage refers to.
+    pub fn get(&self, n: f32, b: &Basis) -> f32 {
         match self {
             Self::Pixel(i) => *i,
             Self::Percentage(i) => n * (*i) / 100.0,
+            Self::ViewWidth(i) => b.width * (*i) / 100.0,
+            Self::ViewHeight(i) => b.height * (*i) / 100.0,
+            Self::Em(i) => b.em * (*i),
+            Self::Sum(a, c) => a.get(n, b) + c.get(n, b),
+            Self::Difference(a, c) => a.get(n, b) - c.get(n, b),
+            Self::Min(v) => v.iter().map(|o| o.get(n, b)).fold(f32::INFINITY, f32::min),
+            Self::Max(v) => v
+                .iter()
+                .map(|o| o.get(n, b))
+                .fold(f32::NEG_INFINITY, f32::max),
+            Self::Clamp(v) => v[1].get(n, b).min(v[2].get(n, b)).max(v[0].get(n, b)),
         }
     }
 
     pub fn is_empty(&self) -> bool {
         match self {
-            Self::Pixel(i) => *i == 0.0,
-            Self::Percentage(i) => *i == 0.0,
+            Self::Pixel(i)
+            | Self::Percentage(i)
+            | Self::ViewWidth(i)
+            | Self::ViewHeight(i)
+            | Self::Em(i) => *i == 0.0,
+            _ => false,
+        }
+    }
+
+    ///Returns the distance at `t` between 0 and 1 from this to `o`, None if they are in different units.
+    pub(crate) fn mix(&self, o: &Self, t: f32) -> Option<Self> {
+        match (self, o) {
+            (Self::Pixel(a), Self::Pixel(b)) => Some(Self::Pixel(mix(*a, *b, t))),
+            (Self::Percentage(a), Self::Percentage(b)) => Some(Self::Percentage(mix(*a, *b, t))),
+            (Self::ViewWidth(a), Self::ViewWidth(b)) => Some(Self::ViewWidth(mix(*a, *b, t))),
+            (Self::ViewHeight(a), Self::ViewHeight(b)) => Some(Self::ViewHeight(mix(*a, *b, t))),
+            (Self::Em(a), Self::Em(b)) => Some(Self::Em(mix(*a, *b, t))),
+            _ => None,
+        }
+    }
+
+    fn term(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if let Some((name, args)) = s
+            .strip_suffix(RIGHT_PARENTHESIS)
+            .and_then(|o| o.split_once(LEFT_PARENTHESIS))
+        {
+            let v = split_outside(args, COMMA)
+                .into_iter()
+                .map(|o| Self::from_str(o))
+                .collect::<Result<Vec<_>>>()?;
+            return match (name.trim().to_lowercase().as_str(), v.len()) {
+                ("" | CALC, 1) => Ok(v.into_iter().next().unwrap_or_default()),
+                (MIN, 1..) => Ok(Self::Min(v)),
+                (MAX, 1..) => Ok(Self::Max(v)),
+                (CLAMP, 3) => Ok(Self::Clamp(Box::new(v.try_into().unwrap_or_default()))),
+                _ => Err((ErrorKind::StrErr, format!("invalid distance: {s}")).into()),
+            };
+        }
+        if let Some(s) = s.strip_suffix(PER_CENT) {
+            to_f32(s.trim()).map(|i| Self::Percentage(i))
+        } else if let Some(s) = s.strip_suffix(VW) {
+            to_f32(s.trim()).map(|i| Self::ViewWidth(i))
+        } else if let Some(s) = s.strip_suffix(VH) {
+            to_f32(s.trim()).map(|i| Self::ViewHeight(i))
+        } else if let Some(s) = s.strip_suffix(EM) {
+            to_f32(s.trim()).map(|i| Self::Em(i))
+        } else {
+            to_f32(s).map(|i| Self::Pixel(i))
+        }
+    }
+
+    fn join(a: Self, op: char, b: Self) -> Self {
+        if op == '-' {
+            Self::Difference(Box::new(a), Box::new(b))
+        } else {
+            Self::Sum(Box::new(a), Box::new(b))
+        }
+    }
+}
+
+//splits `s` by `sep` outside parentheses.
+pub(crate) fn split_outside(s: &str, sep: char) -> Vec<&str> {
+    let mut v = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+    for (i, c) in s.char_indices() {
+        match c {
+            LEFT_PARENTHESIS => depth += 1,
+            RIGHT_PARENTHESIS => depth -= 1,
+            c if c == sep && depth == 0 => {
+                v.push(&s[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
         }
     }
+    v.push(&s[start..]);
+    v
 }
 
 impl FromStr for Distance {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
-        if let Some((s, _)) = s.split_once(PER_CENT) {
-            to_f32(s).map(|i| Self::Percentage(i))
-        } else {
-            to_f32(s).map(|i| Self::Pixel(i))
+        let s = s.trim();
+        //the operators outside parentheses, a sign after another operator or an exponent is a part of number.
+        let mut terms = Vec::new();
+        let (mut depth, mut start, mut op) = (0, 0, '+');
+        let mut last = [' ', ' '];
+        for (i, c) in s.char_indices() {
+            match c {
+                LEFT_PARENTHESIS => depth += 1,
+                RIGHT_PARENTHESIS if depth == 0 => {
+                    return Err((ErrorKind::StrErr, format!("invalid distance: {s}")).into());
+                }
+                RIGHT_PARENTHESIS => depth -= 1,
+                '+' | '-'
+                    if depth == 0
+                        && !matches!(last[1], ' ' | '+' | '-' | LEFT_PARENTHESIS | COMMA)
+                        && !(last[1] == 'e' && last[0].is_ascii_digit()) =>
+                {
+                    terms.push((op, &s[start..i]));
+                    (start, op) = (i + 1, c);
+                }
+                _ => {}
+            }
+            if !c.is_whitespace() {
+                last = [last[1], c];
+            }
+        }
+        let mut o = Self::term(&s[start..])?;
+        if let Some((_, a)) = terms.first() {
+            let mut a = Self::term(a)?;
+            for &(op, b) in &terms[1..] {
+                a = Self::join(a, op, Self::term(b)?);
+            }
+            o = Self::join(a, op, o);
         }
+        Ok(o)
     }
 }
 
@@ -268,6 +453,153 @@ impl TryFrom<&String> for Distance {
     }
 }
 
+///Represents the width or height of element, a distance or `auto` which fits the content.
+#[derive(Clone, Debug)]
+pub enum Length {
+    Auto,
+    Fixed(Distance),
+}
+
+impl std::fmt::Display for Length {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Length::Auto => f.write_str(AUTO),
+            Length::Fixed(o) => write!(f, "{o}"),
+        }
+    }
+}
+
+impl Length {
+    pub fn is_auto(&self) -> bool {
+        matches!(self, Self::Auto)
+    }
+
+    pub fn fixed(&self) -> Option<&Distance> {
+        match self {
+            Self::Auto => None,
+            Self::Fixed(o) => Some(o),
+        }
+    }
+
+    ///Returns the length at `t` between 0 and 1 from this to `o`, None if either is `auto` or they are in different units.
+    pub(crate) fn mix(&self, o: &Self, t: f32) -> Option<Self> {
+        let a = self.fixed()?.mix(o.fixed()?, t)?;
+        Some(Self::Fixed(a))
+    }
+}
+
+impl FromStr for Length {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.trim() == AUTO {
+            Ok(Self::Auto)
+        } else {
+            Distance::try_from(s).map(|o| Self::Fixed(o))
+        }
+    }
+}
+
+impl TryFrom<&str> for Length {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self> {
+        Self::from_str(s)
+    }
+}
+
+///Represents the position of element in its upper, written as `x, y` or `x, y, z`, where `x` and `y` are distances.
+#[derive(Clone, Debug, Default)]
+pub struct Position {
+    x: Distance,
+    y: Distance,
+    z: f32,
+}
+
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({},{},{})", self.x, self.y, self.z)
+    }
+}
+
+impl Position {
+    ///Returns the coordinate, percentages refer to the side of the upper.
+    pub fn get(&self, side: &RectSide, b: &Basis) -> Coord {
+        Coord::new(
+            self.x.get(side.width(), b),
+            self.y.get(side.height(), b),
+            self.z,
+        )
+    }
+
+    ///Returns the position at `t` between 0 and 1 from this to `o`, None if they are in different units.
+    pub(crate) fn mix(&self, o: &Self, t: f32) -> Option<Self> {
+        Some(Self {
+            x: self.x.mix(&o.x, t)?,
+            y: self.y.mix(&o.y, t)?,
+            z: mix(self.z, o.z, t),
+        })
+    }
+}
+
+impl FromStr for Position {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut s = s.trim();
+        //the parentheses around all of it, such as `(1, 2)`.
+        if let Some(o) = s
+            .strip_prefix(LEFT_PARENTHESIS)
+            .and_then(|o| o.strip_suffix(RIGHT_PARENTHESIS))
+        {
+            let mut depth = 0;
+            let closed = o.chars().all(|c| {
+                match c {
+                    LEFT_PARENTHESIS => depth += 1,
+                    RIGHT_PARENTHESIS => depth -= 1,
+                    _ => {}
+                }
+                depth >= 0
+            });
+            if closed {
+                s = o;
+            }
+        }
+        let v: Vec<&str> = split_outside(s, COMMA)
+            .into_iter()
+            .map(|o| o.trim())
+            .filter(|o| o.len() > 0)
+            .collect();
+        let mut o = Self::default();
+        if let Some(a) = v.first() {
+            o.x = Distance::try_from(*a)?;
+        }
+        if let Some(a) = v.get(1) {
+            o.y = Distance::try_from(*a)?;
+        }
+        if let Some(a) = v.get(2) {
+            o.z = to_f32(a)?;
+        }
+        Ok(o)
+    }
+}
+
+impl TryFrom<&str> for Position {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self> {
+        Self::from_str(s)
+    }
+}
+
+impl TryFrom<&String> for Position {
+    type Error = Error;
+
+    fn try_from(s: &String) -> Result<Self> {
+        Self::from_str(s.as_str())
+    }
+}
+
 ///Represents ordinal.
 #[derive(Clone, Debug)]
 pub enum Ordinal {
@@ -342,18 +674,210 @@ impl TryFrom<&String> for Ordinal {
     }
 }
 
-///Represents points.
+///Returns how many tracks an element covers, one at least.
+pub(crate) fn to_span(s: &str) -> Result<usize> {
+    match to_usize(s.trim())? {
+        0 => Err((ErrorKind::StrErr, format!("invalid span: {s}")).into()),
+        n => Ok(n),
+    }
+}
+
+const DOT: &str = ".";
+
+///Represents named areas of grid, rows are separated by comma and cells by space, `.` is an empty cell.
+///Such as `head head, side main`, each name covers a rectangle of cells.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Areas(Vec<Vec<String>>);
+
+impl Areas {
+    ///Returns the first column and row of the area, and how many columns and rows it covers.
+    ///The cells of a name which isn't a rectangle are bounded by the smallest one covering all of them.
+    pub fn find(&self, name: &str) -> Option<(usize, usize, usize, usize)> {
+        let mut r: Option<(usize, usize, usize, usize)> = None;
+        for (y, row) in self.0.iter().enumerate() {
+            for (x, o) in row.iter().enumerate() {
+                if o == name {
+                    r = Some(match r {
+                        Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+                        None => (x, y, x, y),
+                    });
+                }
+            }
+        }
+        r.map(|(x0, y0, x1, y1)| (x0, y0, x1 - x0 + 1, y1 - y0 + 1))
+    }
+
+    fn is_rect(&self, name: &str) -> bool {
+        let (x_n, y_n, w, h) = option_return!(self.find(name), false);
+        let n = self.0.iter().flatten().filter(|o| *o == name).count();
+        n == w * h
+            && self.0[y_n..y_n + h]
+                .iter()
+                .all(|o| o[x_n..x_n + w].iter().all(|o| o == name))
+    }
+}
+
+impl std::fmt::Display for Areas {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let v: Vec<String> = self.0.iter().map(|o| o.join(" ")).collect();
+        f.write_str(&v.join(&COMMA.to_string()))
+    }
+}
+
+impl FromStr for Areas {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let data: Vec<Vec<String>> = s
+            .split(COMMA)
+            .map(|o| o.split_whitespace().map(|o| o.to_string()).collect())
+            .collect();
+        let n = data[0].len();
+        let o = Self(data);
+        if n == 0 || o.0.iter().any(|v| v.len() != n) {
+            return Err((ErrorKind::StrErr, format!("invalid areas: {s}")).into());
+        }
+        for name in o.0.iter().flatten() {
+            if name != DOT && !o.is_rect(name) {
+                return Err((
+                    ErrorKind::StrErr,
+                    format!("area is not a rectangle: {name}"),
+                )
+                    .into());
+            }
+        }
+        Ok(o)
+    }
+}
+
+impl TryFrom<&str> for Areas {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self> {
+        Self::from_str(s)
+    }
+}
+
+const FR: &str = "fr";
+const AUTO: &str = "auto";
+const MINMAX: &str = "minmax";
+const REPEAT: &str = "repeat";
+
+///Represents the size of a column or a row, written as a distance, `1fr` as a share of the free space,
+///`auto` as the largest element in it, or `minmax(min, max)`.
+#[derive(Clone, Debug)]
+pub enum Track {
+    Fixed(Distance),
+    Fraction(f32),
+    Auto,
+    MinMax(Distance, Box<Track>),
+}
+
+impl std::fmt::Display for Track {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Track::Fixed(o) => write!(f, "{o}"),
+            Track::Fraction(i) => write!(f, "{i}{FR}"),
+            Track::Auto => f.write_str(AUTO),
+            Track::MinMax(a, b) => write!(f, "{MINMAX}({a}{COMMA}{b})"),
+        }
+    }
+}
+
+impl Track {
+    //the share of free space.
+    fn fraction(&self) -> Option<f32> {
+        match self {
+            Self::Fraction(i) => Some(*i),
+            Self::MinMax(_, o) => o.fraction(),
+            _ => None,
+        }
+    }
+
+    fn is_auto(&self) -> bool {
+        match self {
+            Self::Auto => true,
+            Self::MinMax(_, o) => o.is_auto(),
+            _ => false,
+        }
+    }
+
+    //a list separated by space, `repeat(n, tracks)` repeats tracks n times.
+    fn list(s: &str) -> Result<Vec<Self>> {
+        let mut v = Vec::new();
+        for o in split_outside(s, ' ').into_iter().filter(|o| o.len() > 0) {
+            let r = o
+                .strip_prefix(REPEAT)
+                .and_then(|o| o.strip_prefix(LEFT_PARENTHESIS))
+                .and_then(|o| o.strip_suffix(RIGHT_PARENTHESIS))
+                .and_then(|o| o.split_once(COMMA));
+            if let Some((n, o)) = r {
+                let n = to_span(n)?;
+                let a = Self::list(o)?;
+                for _ in 0..n {
+                    v.extend(a.iter().cloned());
+                }
+            } else {
+                v.push(Self::from_str(o)?);
+            }
+        }
+        if v.is_empty() {
+            return Err((ErrorKind::StrErr, format!("invalid tracks: {s}")).into());
+        }
+        Ok(v)
+    }
+}
+
+impl FromStr for Track {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if s == AUTO {
+            return Ok(Self::Auto);
+        }
+        if let Some(o) = s.strip_suffix(FR) {
+            return match to_f32(o.trim())? {
+                i if i >= 0.0 => Ok(Self::Fraction(i)),
+                _ => Err((ErrorKind::StrErr, format!("invalid track: {s}")).into()),
+            };
+        }
+        let r = s
+            .strip_prefix(MINMAX)
+            .and_then(|o| o.trim_start().strip_prefix(LEFT_PARENTHESIS))
+            .and_then(|o| o.strip_suffix(RIGHT_PARENTHESIS));
+        if let Some(o) = r {
+            let v = split_outside(o, COMMA);
+            if let [a, b] = v[..] {
+                let b = Self::from_str(b)?;
+                if !matches!(b, Self::MinMax(..)) {
+                    return Ok(Self::MinMax(Distance::try_from(a)?, Box::new(b)));
+                }
+            }
+            return Err((ErrorKind::StrErr, format!("invalid track: {s}")).into());
+        }
+        Distance::try_from(s).map(|o| Self::Fixed(o))
+    }
+}
+
+///Represents points, written as `[100, 20%], 3` for the start of columns or rows and their number,
+///or as tracks such as `240 1fr`, `repeat(4, 1fr)` and `auto minmax(100, 2fr)`.
 #[derive(Clone, Debug, Default, CopyGetters, Setters)]
 pub struct Points {
     data: Vec<Distance>,
     #[getset(get_copy = "pub", set = "pub")]
     count: usize,
+    tracks: Vec<Track>,
 }
 
 deref!(Points, Vec<Distance>, data);
 
 impl std::fmt::Display for Points {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.tracks.len() > 0 {
+            let v: Vec<String> = self.tracks.iter().map(|o| o.to_string()).collect();
+            return f.write_str(&v.join(" "));
+        }
         let mut s = String::new();
         if self.data.len() > 0 {
             s.push(LEFT_SQUARE_BRACKET);
@@ -367,11 +891,11 @@ impl std::fmt::Display for Points {
 }
 
 impl Points {
-    fn effect(&self, sum: f32) -> Vec<f32> {
+    fn effect(&self, sum: f32, b: &Basis) -> Vec<f32> {
         let mut v = Vec::new();
         let mut o = 0.0;
         for i in &self.data {
-            o = i.get(sum);
+            o = i.get(sum, b);
             v.push(o);
         }
         //one point at least.
@@ -395,11 +919,79 @@ impl Points {
         v
     }
 
-    pub(crate) fn coord(&self, sum: f32, zero: f32) -> Vec<f32> {
-        let mut v = self.effect(sum);
-        v.iter_mut().for_each(|i| *i += zero);
+    ///Returns true if the size of a track depends on the elements in it.
+    pub(crate) fn has_auto(&self) -> bool {
+        self.tracks.iter().any(|o| o.is_auto())
+    }
+
+    ///Returns the size of each track, `auto` is the size of the largest element in each one.
+    fn track_sizes(&self, sum: f32, b: &Basis, auto: &[f32]) -> Vec<f32> {
+        let auto_of = |i: usize| auto.get(i).copied().unwrap_or_default();
+        let mut v: Vec<f32> = self
+            .tracks
+            .iter()
+            .enumerate()
+            .map(|(i, o)| match o {
+                Track::Fixed(o) => o.get(sum, b),
+                Track::Fraction(_) => 0.0,
+                Track::Auto => auto_of(i),
+                Track::MinMax(o, _) => o.get(sum, b),
+            })
+            .collect();
+        let total: f32 = self.tracks.iter().filter_map(|o| o.fraction()).sum();
+        if total > 0.0 {
+            //the minimum of a flexible track is a part of its share.
+            let fixed: f32 = v
+                .iter()
+                .zip(&self.tracks)
+                .filter(|o| o.1.fraction().is_none())
+                .map(|o| *o.0)
+                .sum();
+            let free = (sum - fixed).max(0.0);
+            for (i, o) in self.tracks.iter().enumerate() {
+                if let Some(n) = o.fraction() {
+                    v[i] = v[i].max(free * n / total);
+                }
+            }
+            return v;
+        }
+        //the space left is shared by tracks which can grow to their maximum.
+        let free = sum - v.iter().sum::<f32>();
+        let n = self
+            .tracks
+            .iter()
+            .filter(|o| matches!(o, Track::MinMax(..)))
+            .count();
+        if free > 0.0 && n > 0 {
+            for (i, o) in self.tracks.iter().enumerate() {
+                let max = match o {
+                    Track::MinMax(_, o) => match &**o {
+                        Track::Fixed(o) => o.get(sum, b),
+                        _ => auto_of(i),
+                    },
+                    _ => continue,
+                };
+                v[i] += (free / n as f32).min(max - v[i]).max(0.0);
+            }
+        }
         v
     }
+
+    ///Returns the start of each column or row and the end of the last one, `auto` is the size of the largest element in each track.
+    pub(crate) fn coord(&self, sum: f32, zero: f32, b: &Basis, auto: &[f32]) -> (Vec<f32>, f32) {
+        if self.tracks.is_empty() {
+            let mut v = self.effect(sum, b);
+            v.iter_mut().for_each(|i| *i += zero);
+            return (v, zero + sum);
+        }
+        let mut v = Vec::with_capacity(self.tracks.len());
+        let mut o = zero;
+        for i in self.track_sizes(sum, b, auto) {
+            v.push(o);
+            o += i;
+        }
+        (v, o)
+    }
 }
 
 impl FromStr for Points {
@@ -410,6 +1002,19 @@ impl FromStr for Points {
             return Ok(Self::default());
         }
 
+        //tracks have neither brackets nor a number of points.
+        let first = split_outside(s, COMMA)
+            .into_iter()
+            .map(|o| o.trim())
+            .find(|o| o.len() > 0)
+            .unwrap_or_default();
+        if !s.contains(LEFT_SQUARE_BRACKET) && to_usize(first).is_err() {
+            return Track::list(s.trim()).map(|tracks| Self {
+                tracks,
+                ..Default::default()
+            });
+        }
+
         if let Some(t) = s.split_once(LEFT_SQUARE_BRACKET) {
             s = t.1;
         }
@@ -420,7 +1025,7 @@ impl FromStr for Points {
         }
 
         let mut data = Vec::new();
-        for o in data_str.split(COMMA) {
+        for o in split_outside(data_str, COMMA) {
             let o = o.trim();
             if o.len() > 0 {
                 data.push(Distance::try_from(o)?);
@@ -436,9 +1041,16 @@ impl FromStr for Points {
         }
 
         if count_str.is_empty() {
-            Ok(Self { data, count: 0 })
+            Ok(Self {
+                data,
+                ..Default::default()
+            })
         } else {
-            to_usize(count_str).map(|count| Self { data, count })
+            to_usize(count_str).map(|count| Self {
+                data,
+                count,
+                ..Default::default()
+            })
         }
     }
 }
@@ -459,34 +1071,117 @@ impl TryFrom<&String> for Points {
     }
 }
 
-const JS: &str = "text/javascript";
-
-///Represents script type.
-#[derive(Clone, Debug)]
-pub enum ScriptType {
-    JS,
-}
-
-impl std::fmt::Display for ScriptType {
+//named colors, in argb.
+const COLOR_NAMES: [(&str, [u8; 4]); 19] = [
+    ("black", [255, 0, 0, 0]),
+    ("blue", [255, 0, 0, 255]),
+    ("cyan", [255, 0, 255, 255]),
+    ("gray", [255, 128, 128, 128]),
+    ("green", [255, 0, 128, 0]),
+    ("grey", [255, 128, 128, 128]),
+    ("lime", [255, 0, 255, 0]),
+    ("magenta", [255, 255, 0, 255]),
+    ("maroon", [255, 128, 0, 0]),
+    ("navy", [255, 0, 0, 128]),
+    ("olive", [255, 128, 128, 0]),
+    ("orange", [255, 255, 165, 0]),
+    ("purple", [255, 128, 0, 128]),
+    ("red", [255, 255, 0, 0]),
+    ("silver", [255, 192, 192, 192]),
+    ("teal", [255, 0, 128, 128]),
+    ("transparent", [0, 0, 0, 0]),
+    ("white", [255, 255, 255, 255]),
+    ("yellow", [255, 255, 255, 0]),
+];
+
+///Represents color, written as `#rgb`, `#rrggbb`, `#rrggbbaa`, `rgb(r,g,b)`, `rgba(r,g,b,a)` or a name.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(transparent)]
+pub struct Rgba(Color);
+
+deref!(Rgba, Color, 0);
+
+impl std::fmt::Display for Rgba {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::JS => f.write_str(JS),
+        let c = self.0;
+        write!(f, "{}{:02x}{:02x}{:02x}", NUMBER_SIGN, c.r(), c.g(), c.b())?;
+        if c.a() != 255 {
+            write!(f, "{:02x}", c.a())?;
         }
+        Ok(())
     }
 }
 
-impl FromStr for ScriptType {
+impl Rgba {
+    pub fn new(c: Color) -> Self {
+        Self(c)
+    }
+
+    pub fn color(&self) -> Color {
+        self.0
+    }
+
+    ///Returns the color at `t` between 0 and 1 from this to `o`, each channel is mixed.
+    pub(crate) fn mix(&self, o: &Self, t: f32) -> Self {
+        let f = |a: u8, b: u8| mix(a as f32, b as f32, t).round().clamp(0.0, 255.0) as u8;
+        let (a, b) = (self.0, o.0);
+        Self(Color::from_argb(
+            f(a.a(), b.a()),
+            f(a.r(), b.r()),
+            f(a.g(), b.g()),
+            f(a.b(), b.b()),
+        ))
+    }
+}
+
+impl FromStr for Rgba {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
-        match s {
-            JS => Ok(Self::JS),
-            _ => Err((ErrorKind::Script, s).into()),
+        let s = s.trim().to_ascii_lowercase();
+        let err = || -> Error { (ErrorKind::StrErr, format!("invalid color: {s}")).into() };
+        if let Some(h) = s.strip_prefix(NUMBER_SIGN) {
+            let v: Vec<u8> = h
+                .chars()
+                .map(|c| c.to_digit(16).map(|o| o as u8))
+                .collect::<Option<_>>()
+                .ok_or_else(err)?;
+            let c = match v[..] {
+                [r, g, b] => Color::from_rgb(r * 17, g * 17, b * 17),
+                [r0, r1, g0, g1, b0, b1] => {
+                    Color::from_rgb(r0 << 4 | r1, g0 << 4 | g1, b0 << 4 | b1)
+                }
+                [r0, r1, g0, g1, b0, b1, a0, a1] => {
+                    Color::from_argb(a0 << 4 | a1, r0 << 4 | r1, g0 << 4 | g1, b0 << 4 | b1)
+                }
+                _ => return Err(err()),
+            };
+            return Ok(Self(c));
         }
+        if let Some(o) = s.strip_prefix("rgba").or_else(|| s.strip_prefix("rgb")) {
+            let o = o
+                .trim()
+                .strip_prefix(LEFT_PARENTHESIS)
+                .and_then(|o| o.strip_suffix(RIGHT_PARENTHESIS))
+                .ok_or_else(err)?;
+            let v: Vec<&str> = o.split(COMMA).map(|o| o.trim()).collect();
+            let n = |o: &str| o.parse::<u8>().map_err(|_| err());
+            let a = match v.len() {
+                3 => 255,
+                4 => (to_f32(v[3])?.clamp(0.0, 1.0) * 255.0).round() as u8,
+                _ => return Err(err()),
+            };
+            return Ok(Self(Color::from_argb(a, n(v[0])?, n(v[1])?, n(v[2])?)));
+        }
+        COLOR_NAMES
+            .iter()
+            .find(|o| o.0 == s)
+            .map(|(_, [a, r, g, b])| Self(Color::from_argb(*a, *r, *g, *b)))
+            .ok_or_else(err)
     }
 }
 
-impl TryFrom<&str> for ScriptType {
+impl TryFrom<&str> for Rgba {
     type Error = Error;
 
     fn try_from(s: &str) -> Result<Self> {
@@ -494,7 +1189,7 @@ impl TryFrom<&str> for ScriptType {
     }
 }
 
-impl TryFrom<&String> for ScriptType {
+impl TryFrom<&String> for Rgba {
     type Error = Error;
 
     fn try_from(s: &String) -> Result<Self> {
@@ -502,38 +1197,788 @@ impl TryFrom<&String> for ScriptType {
     }
 }
 
-#[derive(Clone, Debug, Default, CopyGetters, Setters)]
-#[getset(get_copy = "pub(crate)", set = "pub(crate)")]
-pub(crate) struct RectSide {
-    width: f32,
-    height: f32,
+///Represents four sides, written as `n` for all, `x,y` or `left,top,right,bottom`.
+#[derive(Clone, Debug, Default, CopyGetters, PartialEq)]
+#[getset(get_copy = "pub")]
+pub struct Sides {
+    left: f32,
+    top: f32,
+    right: f32,
+    bottom: f32,
 }
 
-impl std::fmt::Display for RectSide {
+impl std::fmt::Display for Sides {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({},{})", self.width, self.height)
+        let l = self.left;
+        if [self.top, self.right, self.bottom].iter().all(|o| *o == l) {
+            write!(f, "{}", l)
+        } else {
+            let v = [l, self.top, self.right, self.bottom].map(|o| o.to_string());
+            f.write_str(&v.join(&COMMA.to_string()))
+        }
     }
 }
 
-impl RectSide {
-    pub(crate) fn new(width: f32, height: f32) -> Self {
-        Self { width, height }
+impl Sides {
+    pub fn new(left: f32, top: f32, right: f32, bottom: f32) -> Self {
+        Self {
+            left,
+            top,
+            right,
+            bottom,
+        }
     }
 
-    pub(crate) fn away_from(a: &Coord2D, b: &Coord2D) -> Self {
-        Self::new(a.x() - b.x(), a.y() - b.y())
+    pub fn is_empty(&self) -> bool {
+        self.left == 0.0 && self.top == 0.0 && self.right == 0.0 && self.bottom == 0.0
     }
+}
 
-    pub(crate) fn is_empty(&self) -> bool {
-        self.width == 0.0 || self.height == 0.0
-    }
+impl FromStr for Sides {
+    type Err = Error;
 
-    pub(crate) fn get_attr(&mut self, e: &Element, r: &Self) {
-        if let Some(a) = e.width() {
-            self.width = a.get(r.width);
+    fn from_str(s: &str) -> Result<Self> {
+        let mut v = Vec::new();
+        for o in s.split(COMMA) {
+            let o = o.trim();
+            if o.len() > 0 {
+                v.push(to_f32(o)?)
+            }
         }
-        if let Some(a) = e.height() {
-            self.height = a.get(r.height);
+        match v[..] {
+            [n] => Ok(Self::new(n, n, n, n)),
+            [x, y] => Ok(Self::new(x, y, x, y)),
+            [l, t, r, b] => Ok(Self::new(l, t, r, b)),
+            _ => Err((ErrorKind::StrErr, format!("invalid sides: {s}")).into()),
+        }
+    }
+}
+
+impl TryFrom<&str> for Sides {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self> {
+        Self::from_str(s)
+    }
+}
+
+impl TryFrom<&String> for Sides {
+    type Error = Error;
+
+    fn try_from(s: &String) -> Result<Self> {
+        Self::from_str(s.as_str())
+    }
+}
+
+impl Add for &Sides {
+    type Output = Sides;
+
+    fn add(self, o: Self) -> Self::Output {
+        Sides::new(
+            self.left + o.left,
+            self.top + o.top,
+            self.right + o.right,
+            self.bottom + o.bottom,
+        )
+    }
+}
+
+///Represents four sides in distances, written as `n` for all, `x,y` or `left,top,right,bottom`,
+///percentages of left and right refer to a width, those of top and bottom refer to a height.
+#[derive(Clone, Debug, Default)]
+pub struct Insets {
+    left: Distance,
+    top: Distance,
+    right: Distance,
+    bottom: Distance,
+}
+
+impl std::fmt::Display for Insets {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let v = [&self.left, &self.top, &self.right, &self.bottom].map(|o| o.to_string());
+        if v.iter().all(|o| *o == v[0]) {
+            f.write_str(&v[0])
+        } else {
+            f.write_str(&v.join(&COMMA.to_string()))
+        }
+    }
+}
+
+impl Insets {
+    ///Returns the sides in pixels, `side` is the one which percentages refer to.
+    pub fn get(&self, side: &RectSide, b: &Basis) -> Sides {
+        let (w, h) = (side.width(), side.height());
+        Sides::new(
+            self.left.get(w, b),
+            self.top.get(h, b),
+            self.right.get(w, b),
+            self.bottom.get(h, b),
+        )
+    }
+}
+
+impl FromStr for Insets {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut v = Vec::new();
+        for o in split_outside(s, COMMA) {
+            let o = o.trim();
+            if o.len() > 0 {
+                v.push(Distance::try_from(o)?)
+            }
+        }
+        let (left, top, right, bottom) = match &v[..] {
+            [n] => (n.clone(), n.clone(), n.clone(), n.clone()),
+            [x, y] => (x.clone(), y.clone(), x.clone(), y.clone()),
+            [l, t, r, b] => (l.clone(), t.clone(), r.clone(), b.clone()),
+            _ => return Err((ErrorKind::StrErr, format!("invalid insets: {s}")).into()),
+        };
+        Ok(Self {
+            left,
+            top,
+            right,
+            bottom,
+        })
+    }
+}
+
+impl TryFrom<&str> for Insets {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self> {
+        Self::from_str(s)
+    }
+}
+
+///Converts font weight from a number between 1 and 1000, "normal" or "bold".
+pub(crate) fn to_font_weight(s: &str) -> Result<u16> {
+    match s.trim() {
+        "normal" => Ok(400),
+        "bold" => Ok(700),
+        o => to_usize(o).and_then(|n| {
+            if between(n, 1, 1000) {
+                Ok(n as u16)
+            } else {
+                Err((ErrorKind::StrErr, format!("invalid font weight: {s}")).into())
+            }
+        }),
+    }
+}
+
+pub(crate) fn to_opacity(s: &str) -> Result<f32> {
+    let n = to_f32(s.trim())?;
+    if (0.0..=1.0).contains(&n) {
+        Ok(n)
+    } else {
+        Err((ErrorKind::StrErr, format!("invalid opacity: {s}")).into())
+    }
+}
+
+const JS: &str = "text/javascript";
+
+///Represents script type.
+#[derive(Clone, Debug)]
+pub enum ScriptType {
+    JS,
+}
+
+impl std::fmt::Display for ScriptType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::JS => f.write_str(JS),
+        }
+    }
+}
+
+impl FromStr for ScriptType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            JS => Ok(Self::JS),
+            _ => Err((ErrorKind::Script, s).into()),
+        }
+    }
+}
+
+impl TryFrom<&str> for ScriptType {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self> {
+        Self::from_str(s)
+    }
+}
+
+impl TryFrom<&String> for ScriptType {
+    type Error = Error;
+
+    fn try_from(s: &String) -> Result<Self> {
+        Self::from_str(s.as_str())
+    }
+}
+
+///Returns the number at `t` between 0 and 1 from `a` to `b`.
+pub(crate) fn mix(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+const LINEAR: &str = "linear";
+const EASE: &str = "ease";
+const EASE_IN: &str = "ease-in";
+const EASE_OUT: &str = "ease-out";
+const EASE_IN_OUT: &str = "ease-in-out";
+
+///Represents an easing curve of transition and animation.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Easing {
+    #[default]
+    Linear,
+    Ease,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Linear => LINEAR,
+            Self::Ease => EASE,
+            Self::EaseIn => EASE_IN,
+            Self::EaseOut => EASE_OUT,
+            Self::EaseInOut => EASE_IN_OUT,
+        }
+    }
+
+    ///Maps the progress `t` between 0 and 1 on the curve.
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => t,
+            Self::Ease => t * t * (3.0 - 2.0 * t),
+            Self::EaseIn => t * t * t,
+            Self::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Self::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (2.0 - 2.0 * t).powi(3) / 2.0
+                }
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Easing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Easing {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim() {
+            LINEAR => Ok(Self::Linear),
+            EASE => Ok(Self::Ease),
+            EASE_IN => Ok(Self::EaseIn),
+            EASE_OUT => Ok(Self::EaseOut),
+            EASE_IN_OUT => Ok(Self::EaseInOut),
+            _ => Err((ErrorKind::StrErr, format!("invalid easing: {s}")).into()),
+        }
+    }
+}
+
+///Represents a transition, written as the duration in milliseconds and an easing such as `300, ease-out`.
+///Position, size, colors, radius and opacity are interpolated when they change.
+#[derive(Clone, Debug, PartialEq, CopyGetters)]
+pub struct Transition {
+    #[getset(get_copy = "pub")]
+    duration: u64,
+    #[getset(get_copy = "pub")]
+    easing: Easing,
+}
+
+impl std::fmt::Display for Transition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{}", self.duration, self.easing)
+    }
+}
+
+impl Transition {
+    pub fn new(duration: u64, easing: Easing) -> Self {
+        Self { duration, easing }
+    }
+}
+
+impl FromStr for Transition {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut i = s.split(COMMA);
+        let duration = to_usize(i.next().unwrap_or_default().trim())? as u64;
+        let easing = match i.next() {
+            Some(o) => Easing::from_str(o)?,
+            None => Default::default(),
+        };
+        if i.next().is_some() {
+            return Err((ErrorKind::StrErr, format!("invalid transition: {s}")).into());
+        }
+        Ok(Self::new(duration, easing))
+    }
+}
+
+impl TryFrom<&str> for Transition {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self> {
+        Self::from_str(s)
+    }
+}
+
+impl TryFrom<&String> for Transition {
+    type Error = Error;
+
+    fn try_from(s: &String) -> Result<Self> {
+        Self::from_str(s.as_str())
+    }
+}
+
+const ONCE: &str = "once";
+const LOOP: &str = "loop";
+const ALTERNATE: &str = "alternate";
+
+///Represents how an animation repeats.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Repeat {
+    #[default]
+    Once,
+    Loop,
+    ///Runs forwards and backwards in turn.
+    Alternate,
+}
+
+impl Repeat {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Once => ONCE,
+            Self::Loop => LOOP,
+            Self::Alternate => ALTERNATE,
+        }
+    }
+
+    ///Maps the elapsed number of cycles to the progress of a cycle, None if it's finished.
+    pub(crate) fn progress(&self, n: f32) -> Option<f32> {
+        match self {
+            Self::Once if n >= 1.0 => None,
+            Self::Once => Some(n),
+            Self::Loop => Some(n.fract()),
+            Self::Alternate if n as u64 % 2 == 1 => Some(1.0 - n.fract()),
+            Self::Alternate => Some(n.fract()),
+        }
+    }
+}
+
+impl std::fmt::Display for Repeat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Repeat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim() {
+            ONCE => Ok(Self::Once),
+            LOOP => Ok(Self::Loop),
+            ALTERNATE => Ok(Self::Alternate),
+            _ => Err((ErrorKind::StrErr, format!("invalid repeat: {s}")).into()),
+        }
+    }
+}
+
+///Represents an animation of keyframes declared in style sheet,
+///written as `name, duration` and optionally an easing and `once`, `loop` or `alternate`.
+#[derive(Clone, Debug, PartialEq, CopyGetters, Getters)]
+pub struct Animation {
+    #[getset(get = "pub")]
+    name: String,
+    #[getset(get_copy = "pub")]
+    duration: u64,
+    #[getset(get_copy = "pub")]
+    easing: Easing,
+    #[getset(get_copy = "pub")]
+    repeat: Repeat,
+}
+
+impl std::fmt::Display for Animation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{},{},{},{}",
+            self.name, self.duration, self.easing, self.repeat
+        )
+    }
+}
+
+impl Animation {
+    pub fn new(name: impl Into<String>, duration: u64, easing: Easing, repeat: Repeat) -> Self {
+        Self {
+            name: name.into(),
+            duration,
+            easing,
+            repeat,
+        }
+    }
+}
+
+impl FromStr for Animation {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut i = s.split(COMMA).map(|o| o.trim());
+        let name = i.next().unwrap_or_default().to_lowercase();
+        let duration = to_usize(i.next().unwrap_or_default())? as u64;
+        if name.is_empty() || duration == 0 {
+            return Err((ErrorKind::StrErr, format!("invalid animation: {s}")).into());
+        }
+        let mut o = Self::new(name, duration, Default::default(), Default::default());
+        for t in i {
+            match (Easing::from_str(t), Repeat::from_str(t)) {
+                (Ok(e), _) => o.easing = e,
+                (_, Ok(r)) => o.repeat = r,
+                _ => return Err((ErrorKind::StrErr, format!("invalid animation: {s}")).into()),
+            }
+        }
+        Ok(o)
+    }
+}
+
+impl TryFrom<&str> for Animation {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self> {
+        Self::from_str(s)
+    }
+}
+
+impl TryFrom<&String> for Animation {
+    type Error = Error;
+
+    fn try_from(s: &String) -> Result<Self> {
+        Self::from_str(s.as_str())
+    }
+}
+
+const WRAP: &str = "wrap";
+
+///Represents a flow layout, the subset is laid out in a row or a column instead of the grid.
+///Written as `row` or `column`, and `wrap` starts a new line when the space runs out, such as `row, wrap`.
+#[derive(Clone, Debug, Default, PartialEq, CopyGetters)]
+pub struct Flow {
+    ///True if the main axis is vertical.
+    #[getset(get_copy = "pub")]
+    column: bool,
+    #[getset(get_copy = "pub")]
+    wrap: bool,
+}
+
+impl Flow {
+    pub fn new(column: bool, wrap: bool) -> Self {
+        Self { column, wrap }
+    }
+}
+
+impl std::fmt::Display for Flow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(if self.column { COLUMN } else { ROW })?;
+        if self.wrap {
+            write!(f, "{COMMA}{WRAP}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Flow {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut i = s.split(COMMA).map(|o| o.trim());
+        let column = match i.next() {
+            Some(ROW) => false,
+            Some(COLUMN) => true,
+            _ => return Err((ErrorKind::StrErr, format!("invalid flow: {s}")).into()),
+        };
+        let wrap = match (i.next(), i.next()) {
+            (None, _) => false,
+            (Some(WRAP), None) => true,
+            _ => return Err((ErrorKind::StrErr, format!("invalid flow: {s}")).into()),
+        };
+        Ok(Self::new(column, wrap))
+    }
+}
+
+impl TryFrom<&str> for Flow {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self> {
+        Self::from_str(s)
+    }
+}
+
+const START: &str = "start";
+const CENTER: &str = "center";
+const END: &str = "end";
+const SPACE_BETWEEN: &str = "space-between";
+const SPACE_AROUND: &str = "space-around";
+
+///Represents how a line of flow layout is distributed on the main axis.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Justify {
+    #[default]
+    Start,
+    Center,
+    End,
+    SpaceBetween,
+    SpaceAround,
+}
+
+impl Justify {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Start => START,
+            Self::Center => CENTER,
+            Self::End => END,
+            Self::SpaceBetween => SPACE_BETWEEN,
+            Self::SpaceAround => SPACE_AROUND,
+        }
+    }
+
+    ///Returns the offset of the first one and the space between each two, `free` is the space left in the line.
+    pub(crate) fn distribute(&self, free: f32, n: usize, gap: f32) -> (f32, f32) {
+        let free = free.max(0.0);
+        match self {
+            Self::Start => (0.0, gap),
+            Self::Center => (free / 2.0, gap),
+            Self::End => (free, gap),
+            Self::SpaceBetween if n > 1 => (0.0, gap + free / (n - 1) as f32),
+            Self::SpaceBetween => (0.0, gap),
+            Self::SpaceAround => {
+                let n = n.max(1) as f32;
+                (free / n / 2.0, gap + free / n)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Justify {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Justify {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim() {
+            START => Ok(Self::Start),
+            CENTER => Ok(Self::Center),
+            END => Ok(Self::End),
+            SPACE_BETWEEN => Ok(Self::SpaceBetween),
+            SPACE_AROUND => Ok(Self::SpaceAround),
+            _ => Err((ErrorKind::StrErr, format!("invalid justify: {s}")).into()),
+        }
+    }
+}
+
+impl TryFrom<&str> for Justify {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self> {
+        Self::from_str(s)
+    }
+}
+
+///Represents how an element is aligned in a line of flow layout on the cross axis.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum FlowAlign {
+    #[default]
+    Start,
+    Center,
+    End,
+}
+
+impl FlowAlign {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Start => START,
+            Self::Center => CENTER,
+            Self::End => END,
+        }
+    }
+
+    ///Returns the offset of an element whose size is `n` in a line whose size is `line`.
+    pub(crate) fn offset(&self, line: f32, n: f32) -> f32 {
+        match self {
+            Self::Start => 0.0,
+            Self::Center => (line - n) / 2.0,
+            Self::End => line - n,
+        }
+    }
+}
+
+impl std::fmt::Display for FlowAlign {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for FlowAlign {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim() {
+            START => Ok(Self::Start),
+            CENTER => Ok(Self::Center),
+            END => Ok(Self::End),
+            _ => Err((ErrorKind::StrErr, format!("invalid align: {s}")).into()),
+        }
+    }
+}
+
+impl TryFrom<&str> for FlowAlign {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self> {
+        Self::from_str(s)
+    }
+}
+
+const HOVER: &str = "hover";
+const FOCUS: &str = "focus";
+const PRESSED: &str = "pressed";
+const EMPTY: &str = "empty";
+
+///Represents a state of element, which is written as `:hover` in selector.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum State {
+    Hover,
+    Focus,
+    Pressed,
+    Disabled,
+    Selected,
+    Empty,
+}
+
+impl State {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Hover => HOVER,
+            Self::Focus => FOCUS,
+            Self::Pressed => PRESSED,
+            Self::Disabled => DISABLED,
+            Self::Selected => SELECTED,
+            Self::Empty => EMPTY,
+        }
+    }
+
+    ///Returns true if it's changed by actions rather than attributes and subset.
+    pub fn is_interactive(&self) -> bool {
+        matches!(self, Self::Hover | Self::Focus | Self::Pressed)
+    }
+
+    pub(crate) fn is_match(&self, e: &Element) -> bool {
+        match self {
+            Self::Hover => e.state().hover(),
+            Self::Focus => e.state().focus(),
+            Self::Pressed => e.state().pressed(),
+            Self::Disabled => e.disabled() == Some(&true),
+            Self::Selected => e.selected() == Some(&true),
+            Self::Empty => e.subset().is_empty() && e.text().trim().is_empty(),
+        }
+    }
+}
+
+impl std::fmt::Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for State {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            HOVER => Ok(Self::Hover),
+            FOCUS => Ok(Self::Focus),
+            PRESSED => Ok(Self::Pressed),
+            DISABLED => Ok(Self::Disabled),
+            SELECTED => Ok(Self::Selected),
+            EMPTY => Ok(Self::Empty),
+            _ => Err((ErrorKind::Style, format!("invalid state: {s}")).into()),
+        }
+    }
+}
+
+///Represents the interaction states of element, which are tracked from actions.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, CopyGetters, Setters)]
+pub struct ElementState {
+    #[getset(get_copy = "pub", set = "pub(crate)")]
+    hover: bool,
+    #[getset(get_copy = "pub", set = "pub(crate)")]
+    focus: bool,
+    #[getset(get_copy = "pub", set = "pub(crate)")]
+    pressed: bool,
+}
+
+impl ElementState {
+    pub(crate) fn set(&mut self, s: State, b: bool) {
+        match s {
+            State::Hover => self.hover = b,
+            State::Focus => self.focus = b,
+            State::Pressed => self.pressed = b,
+            _ => {}
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, CopyGetters, Setters)]
+#[getset(get_copy = "pub(crate)", set = "pub(crate)")]
+pub(crate) struct RectSide {
+    width: f32,
+    height: f32,
+}
+
+impl std::fmt::Display for RectSide {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({},{})", self.width, self.height)
+    }
+}
+
+impl RectSide {
+    pub(crate) fn new(width: f32, height: f32) -> Self {
+        Self { width, height }
+    }
+
+    pub(crate) fn away_from(a: &Coord2D, b: &Coord2D) -> Self {
+        Self::new(a.x() - b.x(), a.y() - b.y())
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.width == 0.0 || self.height == 0.0
+    }
+
+    pub(crate) fn get_attr(&mut self, e: &Element, r: &Self, b: &Basis) {
+        if let Some(a) = e.width().and_then(|o| o.fixed()) {
+            self.width = a.get(r.width, b);
+        }
+        if let Some(a) = e.height().and_then(|o| o.fixed()) {
+            self.height = a.get(r.height, b);
         }
     }
 }
@@ -577,6 +2022,46 @@ mod tests {
         let s = "20%";
         let d = Distance::try_from(s).unwrap();
         assert_eq!(s, d.to_string());
+
+        let b = Basis::new(800.0, 600.0, 16.0);
+        let get = |s: &str| Distance::try_from(s).unwrap().get(1000.0, &b);
+        assert_eq!(get("100%-240"), 760.0);
+        assert_eq!(get("calc(50vw + 2em - 8)"), 424.0);
+        assert_eq!(get("min(50%, 400)"), 400.0);
+        assert_eq!(get("max(10vh, 1e2)"), 100.0);
+        assert_eq!(get("clamp(100, 20%, 150)"), 150.0);
+        assert_eq!(get("-10-(5+5)"), -20.0);
+        let s = "100%-min(240,10em)";
+        assert_eq!(s, Distance::try_from(s).unwrap().to_string());
+        assert!(Distance::try_from("clamp(1, 2)").is_err());
+        assert!(Distance::try_from("1)+(2").is_err());
+
+        let p = Position::try_from("(50%-10, max(1, 2), 3)").unwrap();
+        let c = p.get(&RectSide::new(100.0, 100.0), &b);
+        assert_eq!((c.x(), c.y(), c.z()), (40.0, 2.0, 3.0));
+        let p = Points::try_from("[240, 100%-240],3").unwrap();
+        assert_eq!(p.effect(1000.0, &b), vec![240.0, 760.0, 880.0]);
+    }
+
+    #[test]
+    fn length() {
+        let a = Length::try_from(" auto ").unwrap();
+        assert!(a.is_auto() && a.fixed().is_none());
+        let o = Length::try_from("50%").unwrap();
+        assert_eq!(o.to_string(), "50%");
+        assert!(a.mix(&o, 0.5).is_none());
+        let b = Length::try_from("100%").unwrap();
+        assert_eq!(o.mix(&b, 0.5).unwrap().to_string(), "75%");
+        assert!(Length::try_from("autos").is_err());
+    }
+
+    #[test]
+    fn coord() {
+        let c = Coord::try_from("1, 2").unwrap();
+        let s = c.to_string();
+        assert_eq!(s, "(1,2,0)");
+        let c = Coord::try_from(&s).unwrap();
+        assert_eq!((c.x(), c.y(), c.z()), (1.0, 2.0, 0.0));
     }
 
     #[test]
@@ -594,29 +2079,171 @@ mod tests {
         assert_eq!(s, o.to_string());
     }
 
+    #[test]
+    fn areas() {
+        let s = "head head,side main,side .";
+        let o = Areas::try_from(s).unwrap();
+        assert_eq!(o.to_string(), s);
+        assert_eq!(o.find("head"), Some((0, 0, 2, 1)));
+        assert_eq!(o.find("side"), Some((0, 1, 1, 2)));
+        assert_eq!(o.find("foot"), None);
+        assert!(Areas::try_from("a b, b a").is_err());
+        let o = Areas(vec![
+            vec!["a".into(), "b".into()],
+            vec!["b".into(), ".".into()],
+        ]);
+        assert_eq!(o.find("b"), Some((0, 0, 2, 2)));
+        assert!(Areas::try_from("a b, b .").is_err());
+        assert!(Areas::try_from("a b, c").is_err());
+        assert!(to_span("0").is_err());
+    }
+
+    #[test]
+    fn rgba() {
+        let c = Rgba::try_from("#f80").unwrap();
+        assert_eq!(c.to_string(), "#ff8800");
+        let c = Rgba::try_from("rgba(255, 136, 0, 0.5)").unwrap();
+        assert_eq!(c.to_string(), "#ff880080");
+        assert_eq!(Rgba::try_from(&c.to_string()).unwrap(), c);
+        assert_eq!(*Rgba::try_from("Red").unwrap(), Color::from_rgb(255, 0, 0));
+        assert!(Rgba::try_from("#ff88").is_err());
+        assert!(Rgba::try_from("rgb(1,2)").is_err());
+        assert!(Rgba::try_from("foo").is_err());
+    }
+
+    #[test]
+    fn sides() {
+        let o = Sides::try_from("1, 2").unwrap();
+        assert_eq!(o, Sides::new(1.0, 2.0, 1.0, 2.0));
+        assert_eq!(o.to_string(), "1,2,1,2");
+        assert_eq!(Sides::try_from("3").unwrap().to_string(), "3");
+        assert!(Sides::try_from("1,2,3").is_err());
+        assert_eq!(to_font_weight("bold").unwrap(), 700);
+        assert!(to_font_weight("0").is_err());
+    }
+
+    #[test]
+    fn insets() {
+        let o = Sides::new(1.0, 2.0, 1.0, 2.0);
+        assert_eq!(
+            &o + &Sides::new(1.0, 1.0, 1.0, 1.0),
+            Sides::new(2.0, 3.0, 2.0, 3.0)
+        );
+
+        let o = Insets::try_from("10%, min(1em, 8)").unwrap();
+        assert_eq!(o.to_string(), "10%,min(1em,8),10%,min(1em,8)");
+        let v = o.get(&RectSide::new(200.0, 100.0), &Basis::new(0.0, 0.0, 4.0));
+        assert_eq!(v, Sides::new(20.0, 4.0, 20.0, 4.0));
+        assert_eq!(Insets::try_from("2vw").unwrap().to_string(), "2vw");
+        assert!(Insets::try_from("1,2,3").is_err());
+    }
+
+    #[test]
+    fn animation() {
+        let o = Transition::try_from("300, ease-out").unwrap();
+        assert_eq!(o, Transition::new(300, Easing::EaseOut));
+        assert_eq!(o.to_string(), "300,ease-out");
+        assert!(Transition::try_from("fast").is_err());
+
+        let o = Animation::try_from("Pulse, 1000, alternate").unwrap();
+        assert_eq!(o.name(), "pulse");
+        assert_eq!(
+            (o.easing(), o.repeat()),
+            (Easing::Linear, Repeat::Alternate)
+        );
+        assert_eq!(Animation::try_from(&o.to_string()).unwrap(), o);
+        assert!(Animation::try_from("pulse, 0").is_err());
+        assert!(Animation::try_from("pulse, 100, bounce").is_err());
+
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+        assert_eq!(Easing::Ease.apply(2.0), 1.0);
+        assert_eq!(Repeat::Once.progress(1.0), None);
+        assert_eq!(Repeat::Loop.progress(2.25), Some(0.25));
+        assert_eq!(Repeat::Alternate.progress(1.25), Some(0.75));
+
+        let a = Rgba::try_from("#000000").unwrap();
+        let b = Rgba::try_from("#ff000080").unwrap();
+        assert_eq!(a.mix(&b, 0.5).to_string(), "#800000c0");
+        let d = Distance::Pixel(10.0).mix(&Distance::Pixel(20.0), 0.5);
+        assert_eq!(d.map(|o| o.to_string()), Some("15".to_string()));
+        assert!(
+            Distance::Pixel(10.0)
+                .mix(&Distance::Percentage(20.0), 0.5)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn flow() {
+        assert_eq!(
+            Flow::try_from("column, wrap").unwrap(),
+            Flow::new(true, true)
+        );
+        assert_eq!(Flow::try_from("row").unwrap().to_string(), "row");
+        assert!(Flow::try_from("row, nowrap").is_err());
+        assert!(Flow::try_from("grid").is_err());
+        assert_eq!(
+            Justify::try_from("space-between").unwrap(),
+            Justify::SpaceBetween
+        );
+        assert!(FlowAlign::try_from("stretch").is_err());
+
+        assert_eq!(Justify::End.distribute(30.0, 3, 5.0), (30.0, 5.0));
+        assert_eq!(Justify::SpaceBetween.distribute(30.0, 3, 5.0), (0.0, 20.0));
+        assert_eq!(Justify::SpaceAround.distribute(30.0, 3, 0.0), (5.0, 10.0));
+        assert_eq!(Justify::Center.distribute(-10.0, 3, 0.0), (0.0, 0.0));
+        assert_eq!(FlowAlign::Center.offset(30.0, 10.0), 10.0);
+    }
+
+    #[test]
+    fn tracks() {
+        let b = Basis::default();
+        let p = Points::try_from("240 1fr 2fr").unwrap();
+        assert_eq!(p.to_string(), "240 1fr 2fr");
+        assert_eq!(
+            p.coord(840.0, 10.0, &b, &[]),
+            (vec![10.0, 250.0, 450.0], 850.0)
+        );
+
+        let p = Points::try_from("repeat(2, 100 1fr)").unwrap();
+        assert_eq!(p.to_string(), "100 1fr 100 1fr");
+        let p = Points::try_from("auto minmax(200, 1fr) minmax(50, 80)").unwrap();
+        assert!(p.has_auto());
+        let (v, end) = p.coord(400.0, 0.0, &b, &[120.0]);
+        assert_eq!((v, end), (vec![0.0, 120.0, 350.0], 400.0));
+        let p = Points::try_from("auto minmax(50, 80)").unwrap();
+        assert_eq!(p.coord(400.0, 0.0, &b, &[120.0]).1, 200.0);
+
+        assert_eq!(Points::try_from("3,").unwrap().count(), 3);
+        assert!(Points::try_from("repeat(0, 1fr)").is_err());
+        assert!(Points::try_from("minmax(1, minmax(1, 2))").is_err());
+        assert!(Points::try_from("-1fr").is_err());
+    }
+
     #[test]
     fn points() {
         let p = Points {
             data: vec![Distance::Pixel(100.0), Distance::Percentage(20.0)],
             count: 3,
+            ..Default::default()
         };
-        let v = p.effect(1000.0);
+        let v = p.effect(1000.0, &Basis::default());
         assert_eq!((v[0], v[1], v[2]), (100.0, 200.0, 600.0));
 
         let s = "[100,20%],3";
         let p = Points::try_from(s).unwrap();
-        let v = p.effect(1000.0);
+        let v = p.effect(1000.0, &Basis::default());
         assert_eq!((v[0], v[1], v[2]), (100.0, 200.0, 600.0));
         assert_eq!(s, p.to_string());
 
         let s = " [ 100 ,, ,] , 3 ,";
         let p = Points::try_from(s).unwrap();
-        let v = p.effect(1000.0);
+        let v = p.effect(1000.0, &Basis::default());
         assert_eq!((v[0], v[1], v[2]), (100.0, 400.0, 700.0));
 
         let s = "2";
         let p = Points::try_from(s).unwrap();
-        let v = p.effect(1000.0);
+        let v = p.effect(1000.0, &Basis::default());
         assert_eq!((v[0], v[1]), (0.0, 500.0));
         assert_eq!(s, p.to_string());
     }
@@ -1,6 +1,7 @@
 mod head;
 
 pub(crate) use self::head::*;
+use crate::error::*;
 use crate::markup::*;
 use crate::page::*;
 use crate::script::*;
@@ -12,17 +13,70 @@ use std::sync::{Arc, RwLock};
 #[derive(Debug)]
 pub(crate) struct Style {
     style: Option<StyleContext>,
+    error: ErrorHolder,
 }
 
 impl Style {
     pub(crate) fn new(cx: &mut PageContext) -> Self {
         let mut style = None;
-        if let Some(s) = cx.text(cx.style_key()) {
-            let mut o = StyleContext::new(s);
+        let mut error = ErrorHolder::default();
+        if let Some(e) = cx.style_element() {
+            let p = e.text_position().unwrap_or_default();
+            let (mut o, mut err) = StyleContext::new(e.text());
             o.set_style(cx);
+            err.append(&mut o.take_error());
+            err.offset(p);
+            error = err;
             style.replace(o);
         }
-        Self { style }
+        Self { style, error }
+    }
+
+    ///Evaluates meta rules for a new size of window, returns true if the style changed.
+    pub(crate) fn respond(&mut self, v: Viewport, cx: &mut PageContext) -> bool {
+        self.style.as_mut().is_some_and(|o| o.respond(v, cx))
+    }
+
+    ///Applies the rules depending on states again after the states of elements changed,
+    ///returns the elements and the attributes changed on them.
+    pub(crate) fn restyle(&mut self, cx: &mut PageContext) -> Vec<(ElementKey, Vec<AttrName>)> {
+        match self.style.as_mut().filter(|o| o.is_interactive()) {
+            Some(o) => o.restyle(cx),
+            None => Vec::new(),
+        }
+    }
+
+    ///Replaces the style sheet, returns its errors. Inline attributes and those set by script are kept.
+    pub(crate) fn reload(&mut self, s: &str, cx: &mut PageContext) -> ErrorHolder {
+        let k = cx.style_key();
+        if let Some(e) = cx.get_mut(k) {
+            *e.text_mut() = s.to_string();
+        }
+        match self.style.as_mut() {
+            Some(o) => o.reload(s, cx),
+            None => {
+                let (mut o, mut err) = StyleContext::new(s);
+                o.set_style(cx);
+                err.append(&mut o.take_error());
+                self.style.replace(o);
+                err
+            }
+        }
+    }
+
+    ///Returns the rule which supplied an attribute of an element.
+    pub(crate) fn origin(&self, k: ElementKey, n: &AttrName) -> Option<StyleOrigin> {
+        self.style.as_ref()?.origin(k, n)
+    }
+
+    ///Returns the block declared as `@keyframes name`.
+    pub(crate) fn keyframes(&self, name: &str) -> Option<&Keyframes> {
+        self.style.as_ref()?.keyframes(name)
+    }
+
+    ///Takes the errors of style sheet, positions are in page source.
+    pub(crate) fn take_error(&mut self) -> ErrorHolder {
+        std::mem::take(&mut self.error)
     }
 }
 
@@ -58,4 +112,19 @@ impl Script {
             rt.rebuild();
         }
     }
+
+    ///Runs the handler of an event, the runtime is created if there is no script.
+    pub(crate) fn dispatch(&mut self, s: String, e: Event, cx: Arc<RwLock<PageContext>>) {
+        if self.rt.is_none() {
+            let t = cx
+                .read()
+                .ok()
+                .and_then(|o| o.script_element().and_then(|e| e.script_type().cloned()))
+                .unwrap_or(ScriptType::JS);
+            self.rt.replace(ScriptRuntime::new(t, cx));
+        }
+        if let Some(rt) = &mut self.rt {
+            rt.dispatch(s, e);
+        }
+    }
 }
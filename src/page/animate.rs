@@ -0,0 +1,361 @@
+use super::*;
+use std::collections::HashMap;
+
+//a running transition of an attribute.
+#[derive(Debug)]
+struct Tween {
+    from: Attribute,
+    to: Attribute,
+    easing: Easing,
+    time_meter: Chronograph,
+}
+
+//a running keyframe animation of an element.
+#[derive(Debug)]
+struct Playing {
+    animation: Animation,
+    //the attributes written by the animation.
+    names: Vec<AttrName>,
+    finished: bool,
+    time_meter: Chronograph,
+}
+
+impl Playing {
+    fn new(animation: Animation) -> Self {
+        let time_meter = Chronograph::new(animation.duration());
+        Self {
+            animation,
+            names: Default::default(),
+            finished: false,
+            time_meter,
+        }
+    }
+}
+
+///Runs transitions and keyframe animations, the values shown are written into the animated attributes of elements.
+#[derive(Debug, Default)]
+pub(crate) struct Animator {
+    //the last values of animatable attributes, on elements with transition.
+    seen: HashMap<ElementKey, HashMap<AttrName, Attribute>>,
+    tweens: HashMap<(ElementKey, AttrName), Tween>,
+    playing: HashMap<ElementKey, Playing>,
+    //elements with transition and with animation, found again only after the page changed.
+    transiting: Vec<ElementKey>,
+    animating: Vec<ElementKey>,
+    revision: Option<usize>,
+}
+
+//the attributes shown differently on each element.
+type Changed = HashMap<ElementKey, Vec<AttrName>>;
+
+impl Animator {
+    ///Advances transitions and animations by the elapsed time, returns true if any value shown changed.
+    pub(crate) fn animate(&mut self, cx: &mut PageContext, style: &Style) -> bool {
+        if self.revision != Some(cx.revision()) {
+            self.scan(cx);
+        }
+        let mut changed = HashMap::new();
+        self.transit(cx, &mut changed);
+        self.play(cx, style, &mut changed);
+        for (k, v) in &changed {
+            cx.push_restyled(*k, v);
+        }
+        self.revision = Some(cx.revision());
+        !changed.is_empty()
+    }
+
+    ///Finds the animated elements again at the next frame, for changes of attributes not recorded by page.
+    pub(crate) fn refresh(&mut self) {
+        self.revision.take();
+    }
+
+    fn scan(&mut self, cx: &PageContext) {
+        self.transiting.clear();
+        FindDescendant::new(AttrExists::new(&AttrName::TRANSITION), 0)
+            .all_with_first_root(cx, &mut self.transiting);
+        self.animating.clear();
+        FindDescendant::new(AttrExists::new(&AttrName::ANIMATION), 0)
+            .all_with_first_root(cx, &mut self.animating);
+    }
+
+    fn touch(changed: &mut Changed, k: ElementKey, n: &AttrName) {
+        let v = changed.entry(k).or_default();
+        if !v.contains(n) {
+            v.push(n.clone());
+        }
+    }
+
+    //starts a transition when an attribute changed, then moves all of them forward.
+    fn transit(&mut self, cx: &mut PageContext, changed: &mut Changed) {
+        let ks = &self.transiting;
+        self.seen.retain(|k, _| ks.contains(k));
+        let mut stopped: Vec<_> = self
+            .tweens
+            .keys()
+            .filter(|o| !ks.contains(&o.0))
+            .cloned()
+            .collect();
+        for &k in ks {
+            let e = if let Some(e) = cx.get(k) {
+                e
+            } else {
+                continue;
+            };
+            let t = if let Some(o) = e.transition() {
+                o.clone()
+            } else {
+                continue;
+            };
+            let now: HashMap<_, _> = e
+                .attribute()
+                .iter()
+                .filter(|o| o.0.is_animatable())
+                .map(|(n, a)| (n.clone(), a.clone()))
+                .collect();
+            //the values are recorded at the first time, nothing moves.
+            let old = if let Some(o) = self.seen.insert(k, now.clone()) {
+                o
+            } else {
+                continue;
+            };
+            for (n, a) in &now {
+                let o = match old.get(n) {
+                    Some(o) if o.to_string() != a.to_string() => o,
+                    _ => continue,
+                };
+                //starts from the value shown, so an interrupted transition turns smoothly.
+                let from = e.animated().get(n).unwrap_or(o).clone();
+                if t.duration() == 0 || from.interpolate(a, 0.0).is_none() {
+                    stopped.push((k, n.clone()));
+                    continue;
+                }
+                let o = Tween {
+                    from,
+                    to: a.clone(),
+                    easing: t.easing(),
+                    time_meter: Chronograph::new(t.duration()),
+                };
+                self.tweens.insert((k, n.clone()), o);
+            }
+            stopped.extend(
+                old.into_keys()
+                    .filter(|n| !now.contains_key(n))
+                    .map(|n| (k, n)),
+            );
+        }
+        for o in stopped {
+            if self.tweens.remove(&o).is_some() {
+                if let Some(e) = cx.get_mut(o.0) {
+                    e.animated_mut().remove(&o.1);
+                    Self::touch(changed, o.0, &o.1);
+                }
+            }
+        }
+
+        let mut finished = Vec::new();
+        for ((k, n), o) in &self.tweens {
+            let e = if let Some(e) = cx.get_mut(*k) {
+                e
+            } else {
+                continue;
+            };
+            let t = o.time_meter.ratio();
+            if t >= 1.0 {
+                e.animated_mut().remove(n);
+                finished.push((*k, n.clone()));
+            } else if let Some(a) = o.from.interpolate(&o.to, o.easing.apply(t)) {
+                e.animated_mut().insert(n.clone(), a);
+            }
+            Self::touch(changed, *k, n);
+        }
+        for o in finished {
+            self.tweens.remove(&o);
+        }
+    }
+
+    //samples the keyframes of each animation, `once` ones return to the attributes when finished.
+    fn play(&mut self, cx: &mut PageContext, style: &Style, changed: &mut Changed) {
+        let ks = &self.animating;
+        let stopped: Vec<_> = self
+            .playing
+            .keys()
+            .filter(|k| !ks.contains(k))
+            .copied()
+            .collect();
+        for k in stopped {
+            if let Some(o) = self.playing.remove(&k) {
+                Self::clear(cx, k, o.names, changed);
+            }
+        }
+        for &k in ks {
+            let a = if let Some(o) = cx.get(k).and_then(|e| e.animation()) {
+                o.clone()
+            } else {
+                continue;
+            };
+            if self.playing.get(&k).is_none_or(|o| o.animation != a) {
+                if let Some(o) = self.playing.remove(&k) {
+                    Self::clear(cx, k, o.names, changed);
+                }
+                self.playing.insert(k, Playing::new(a.clone()));
+            }
+            let o = if let Some(o) = self.playing.get_mut(&k).filter(|o| !o.finished) {
+                o
+            } else {
+                continue;
+            };
+            let kf = if let Some(o) = style.keyframes(a.name()) {
+                o
+            } else {
+                continue;
+            };
+            let e = if let Some(e) = cx.get_mut(k) {
+                e
+            } else {
+                continue;
+            };
+            match a.repeat().progress(o.time_meter.ratio()) {
+                Some(t) => {
+                    for v in kf.sample(a.easing().apply(t)) {
+                        let n = v.name();
+                        if !o.names.contains(&n) {
+                            o.names.push(n.clone());
+                        }
+                        Self::touch(changed, k, &n);
+                        e.animated_mut().insert(n, v);
+                    }
+                }
+                None => {
+                    for n in o.names.drain(..) {
+                        Self::touch(changed, k, &n);
+                        e.animated_mut().remove(&n);
+                    }
+                    o.finished = true;
+                }
+            }
+        }
+    }
+
+    fn clear(cx: &mut PageContext, k: ElementKey, names: Vec<AttrName>, changed: &mut Changed) {
+        if let Some(e) = cx.get_mut(k) {
+            for n in names {
+                e.animated_mut().remove(&n);
+                Self::touch(changed, k, &n);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(body: &str, style: &str) -> (Page, Style) {
+        let s = format!(
+            "<aht><head></head><body>{body}</body><style>{style}</style><script></script></aht>"
+        );
+        let (p, err) = Page::parse(&s);
+        assert!(err.is_empty(), "{err}");
+        let p = p.unwrap();
+        let style = Style::new(&mut p.write().unwrap());
+        (p, style)
+    }
+
+    fn key(cx: &PageContext, id: &str) -> ElementKey {
+        cx.query(&format!(".id={id}")).unwrap()[0]
+    }
+
+    fn step(a: &mut Animator, n: u64) {
+        a.tweens.values_mut().for_each(|o| o.time_meter.pass(n));
+        a.playing.values_mut().for_each(|o| o.time_meter.pass(n));
+    }
+
+    fn shown(cx: &PageContext, k: ElementKey, n: &AttrName) -> Option<f32> {
+        let a = cx.get(k)?.animated().get(n)?;
+        a.to_string().parse().ok()
+    }
+
+    fn near(o: Option<f32>, n: f32) -> bool {
+        o.is_some_and(|o| (o - n).abs() < 0.5)
+    }
+
+    fn attr(n: &AttrName, s: &str) -> Attribute {
+        Attribute::from(n, &mut s.to_string()).unwrap()
+    }
+
+    #[test]
+    fn transit() {
+        let (p, style) = context(
+            "<pt id=a width=10 height=20 opacity=0 transition=1000>a</pt>",
+            "",
+        );
+        let mut cx = p.write().unwrap();
+        let k = key(&cx, "a");
+        let mut a = Animator::default();
+        assert!(!a.animate(&mut cx, &style));
+
+        assert!(cx.set_attribute(k, attr(&AttrName::WIDTH, "50")));
+        cx.take_changes();
+        assert!(a.animate(&mut cx, &style));
+        step(&mut a, 500);
+        assert!(a.animate(&mut cx, &style));
+        assert!(near(shown(&cx, k, &AttrName::WIDTH), 30.0));
+        let upper = cx.upper_or_self(k);
+        assert!(cx.take_changes().contains(&(upper, None)));
+
+        step(&mut a, 600);
+        assert!(a.animate(&mut cx, &style));
+        assert_eq!(shown(&cx, k, &AttrName::WIDTH), None);
+        cx.take_changes();
+        assert!(!a.animate(&mut cx, &style));
+
+        //opacity only restyles the element.
+        assert!(cx.set_attribute(k, attr(&AttrName::OPACITY, "1")));
+        cx.take_changes();
+        cx.take_restyles();
+        assert!(a.animate(&mut cx, &style));
+        step(&mut a, 250);
+        assert!(a.animate(&mut cx, &style));
+        assert!(shown(&cx, k, &AttrName::OPACITY).is_some_and(|o| (o - 0.25).abs() < 0.05));
+        assert!(cx.take_changes().is_empty());
+        assert_eq!(cx.take_restyles(), [k]);
+    }
+
+    #[test]
+    fn play() {
+        let (p, style) = context(
+            "<pt id=a width=10 height=20 animation=\"grow, 1000, once\">a</pt>\
+            <pt id=b width=10 height=20 animation=\"grow, 1000, loop\">b</pt>\
+            <pt id=c width=10 height=20 animation=\"grow, 1000, alternate\">c</pt>",
+            "@keyframes grow { from{width:10;} to{width:30;} }",
+        );
+        let mut cx = p.write().unwrap();
+        let ks: Vec<_> = ["a", "b", "c"].iter().map(|o| key(&cx, o)).collect();
+        let width = |cx: &PageContext, i: usize| shown(cx, ks[i], &AttrName::WIDTH);
+        let mut a = Animator::default();
+        assert!(a.animate(&mut cx, &style));
+        assert!((0..3).all(|i| near(width(&cx, i), 10.0)));
+
+        step(&mut a, 250);
+        a.animate(&mut cx, &style);
+        assert!((0..3).all(|i| near(width(&cx, i), 15.0)));
+
+        //the second iteration, alternate goes back.
+        step(&mut a, 1000);
+        a.animate(&mut cx, &style);
+        assert_eq!(width(&cx, 0), None);
+        assert!(near(width(&cx, 1), 15.0));
+        assert!(near(width(&cx, 2), 25.0));
+
+        //the end of keyframes.
+        step(&mut a, 749);
+        a.animate(&mut cx, &style);
+        assert!(near(width(&cx, 2), 10.0));
+
+        assert!(cx.remove_attribute(ks[1], &AttrName::ANIMATION).is_some());
+        a.animate(&mut cx, &style);
+        assert_eq!(width(&cx, 1), None);
+        assert!(a.playing.get(&ks[0]).is_some_and(|o| o.finished));
+        assert!(!a.playing.contains_key(&ks[1]));
+    }
+}
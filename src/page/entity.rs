@@ -1,4 +1,5 @@
 use super::*;
+use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 
 ///Represents page.
@@ -9,6 +10,7 @@ pub struct Page {
     body: Body,
     style: Style,
     script: Script,
+    animator: Animator,
     callback: Vec<DrawUnitKey>,
     #[getset(get_copy = "pub", set = "pub")]
     scale_factor: f32,
@@ -46,6 +48,7 @@ impl Page {
             body,
             style,
             script,
+            animator: Default::default(),
             callback: Default::default(),
             scale_factor: 1.0,
         };
@@ -65,9 +68,16 @@ impl Page {
         let (e, mut err) = ElementHolder::parse(buf, o);
         let p = e.and_then(|e| {
             e.try_into()
-                .map_err(|_| err.push((ErrorKind::Markup, "no page").into()))
+                .map_err(|_| {
+                    let e: Error = (ErrorKind::Markup, "no page").into();
+                    err.push(e.with_code(ErrorCode::NoPage));
+                })
                 .ok()
         });
+        let p = p.map(|mut p: Self| {
+            err.append(&mut p.style.take_error());
+            p
+        });
         (p, err)
     }
 
@@ -82,8 +92,13 @@ impl Page {
     }
 
     ///Reset width and height, each number is not equal to the size of window if the coordinate is not 0.0
+    ///Meta rules of style sheet are evaluated again with the size and scale factor.
     pub fn resize(&mut self, width: f32, height: f32) {
         if let Ok(mut context) = self.context.write() {
+            let v = Viewport::new(width, height, self.scale_factor);
+            if self.style.respond(v, &mut context) {
+                self.animator.refresh();
+            }
             self.body.resize(width, height, &mut context);
         }
     }
@@ -91,22 +106,176 @@ impl Page {
     ///Reset zero point on rectangular coordinates and size. `resize`
     pub fn reset(&mut self, x: f32, y: f32, width: f32, height: f32) {
         if let Ok(mut context) = self.context.write() {
+            let v = Viewport::new(width, height, self.scale_factor);
+            if self.style.respond(v, &mut context) {
+                self.animator.refresh();
+            }
             self.body.reset(x, y, width, height, &mut context);
         }
     }
 
+    ///Returns the keys of elements matching a selector, the selector is written in the syntax of style sheet.
+    pub fn query(&self, s: &str) -> Result<Vec<ElementKey>> {
+        match self.context.read() {
+            Ok(context) => context.query(s),
+            Err(_) => Ok(Vec::new()),
+        }
+    }
+
+    ///Returns the key of the first element matching a selector.
+    pub fn query_one(&self, s: &str) -> Result<Option<ElementKey>> {
+        self.query(s).map(|v| v.first().copied())
+    }
+
+    ///Replaces the style sheet and lays out the body again, returns the errors of the new style sheet.
+    ///Attributes supplied by the previous style sheet are removed, inline ones and those set by script are kept.
+    pub fn reload_style(&mut self, s: &str) -> ErrorHolder {
+        let mut context = result_return!(self.context.write(), Default::default());
+        let err = self.style.reload(s, &mut context);
+        let k = context.body_key();
+        context.push_change(k, None);
+        self.body.sync_changes(&mut context);
+        err
+    }
+
+    ///Replaces the theme and lays out the body again, the next frame is drawn with it.
+    pub fn set_theme(&mut self, theme: Theme) {
+        let mut context = result_return!(self.context.write());
+        context.theme = Arc::new(theme);
+        let k = context.body_key();
+        context.push_change(k, None);
+        self.body.sync_changes(&mut context);
+    }
+
+    ///Advances transitions and keyframe animations, returns true if any element changed.
+    ///Window calls it once per frame.
+    pub fn animate(&mut self) -> bool {
+        let mut context = result_return!(self.context.write(), false);
+        let r = self.animator.animate(&mut context, &self.style);
+        if r {
+            self.body.sync_changes(&mut context);
+        }
+        r
+    }
+
+    ///Returns where an element is laid out, None if it isn't drawn by itself, such as "form" and "option".
+    pub fn layout_of(&self, key: ElementKey) -> Option<Layout> {
+        let context = self.context.read().ok()?;
+        self.body.layout_of(key, &context)
+    }
+
+    ///Returns the layout of body and the elements drawn as text, one line for each, indented by depth.
+    pub fn dump_layout(&self) -> String {
+        let context = result_return!(self.context.read(), String::new());
+        self.body.dump_layout(&context)
+    }
+
+    ///Returns the style rule which supplied an attribute of an element, None if it is not from style sheet.
+    pub fn style_origin(&self, key: ElementKey, name: &AttrName) -> Option<StyleOrigin> {
+        self.style.origin(key, name)
+    }
+
+    ///Inserts an element into the subset of `upper` at `index`, or at the end if `index` is None.
+    pub fn insert_element(
+        &mut self,
+        upper: ElementKey,
+        index: Option<usize>,
+        e: Element,
+    ) -> Option<ElementKey> {
+        let mut context = self.context.write().ok()?;
+        let key = context.create(e);
+        if !context.insert_child(upper, key, index) {
+            context.remove(key);
+            return None;
+        }
+        self.body.sync_changes(&mut context);
+        Some(key)
+    }
+
+    ///Removes an element and its subset, the elements of page structure are kept.
+    pub fn remove_element(&mut self, key: ElementKey) -> bool {
+        let mut context = result_return!(self.context.write(), false);
+        let upper = option_return!(context.get(key).and_then(|e| *e.upper()), false);
+        let r = context.remove_child(upper, key);
+        self.body.sync_changes(&mut context);
+        r
+    }
+
+    ///Moves an element into the subset of `upper` at `index`, or at the end if `index` is None.
+    pub fn move_element(
+        &mut self,
+        key: ElementKey,
+        upper: ElementKey,
+        index: Option<usize>,
+    ) -> bool {
+        let mut context = result_return!(self.context.write(), false);
+        let r = context.insert_child(upper, key, index);
+        self.body.sync_changes(&mut context);
+        r
+    }
+
+    ///Replaces the text of an element.
+    pub fn set_text(&mut self, key: ElementKey, s: impl Into<String>) -> bool {
+        let mut context = result_return!(self.context.write(), false);
+        let r = context.set_text(key, s.into());
+        self.body.sync_changes(&mut context);
+        r
+    }
+
+    ///Inserts an attribute into an element.
+    pub fn set_attribute(&mut self, key: ElementKey, a: Attribute) -> bool {
+        let mut context = result_return!(self.context.write(), false);
+        let r = context.set_attribute(key, a);
+        self.body.sync_changes(&mut context);
+        r
+    }
+
+    ///Removes an attribute from an element.
+    pub fn remove_attribute(&mut self, key: ElementKey, a: &AttrName) -> Option<Attribute> {
+        let mut context = self.context.write().ok()?;
+        let r = context.remove_attribute(key, a);
+        self.body.sync_changes(&mut context);
+        r
+    }
+
     pub(crate) fn draw_body(&mut self, surface: skia_safe::Surface) {
         if let Ok(mut context) = self.context.write() {
-            self.body.draw(DrawCtx::new(surface), &mut context);
+            self.body.sync_changes(&mut context);
+            let dcx = DrawCtx::new(surface, context.theme.clone());
+            self.body.draw(dcx, &mut context);
         }
     }
 
     ///Receive a action.
     pub fn receive_action(&mut self, a: ActionKind) {
+        for (s, e) in self.consume_action(a) {
+            self.script.dispatch(s, e, self.context.clone());
+        }
+    }
+
+    //returns the handlers of the events raised by an action.
+    fn consume_action(&mut self, a: ActionKind) -> Vec<(String, Event)> {
+        let mut events = Vec::new();
+        let mut handlers = Vec::new();
+        let mut hits = Vec::new();
         if let Ok(mut context) = self.context.try_write() {
-            let o = ActionCtx::new(a, &mut self.callback);
+            self.body.sync_changes(&mut context);
+            let o = ActionCtx::new(a.clone(), &mut self.callback, &mut events, &mut hits);
             self.body.consume_action(o, &mut context);
+            if context.track_state(&a, &hits, &events) {
+                for (k, v) in self.style.restyle(&mut context) {
+                    context.push_restyled(k, &v);
+                }
+            }
+            self.body.sync_changes(&mut context);
+            for e in events {
+                let name = e.kind().attr_name();
+                if let Some(a) = context.get(e.target()).and_then(|o| o.attribute_get(&name)) {
+                    handlers.push((a.to_string(), e));
+                }
+            }
         }
+        handlers
     }
 }
 
@@ -122,6 +291,18 @@ pub struct PageContext {
     style_key: ElementKey,
     #[getset(get_copy = "pub")]
     script_key: ElementKey,
+    //the palette, default font and metrics of page, shared with the draw context.
+    theme: Arc<Theme>,
+    changes: Vec<(ElementKey, Option<ElementKey>)>,
+    //elements whose draw units only look different.
+    restyles: Vec<ElementKey>,
+    //counts the changes recorded, the animator finds animated elements again when it moves.
+    revision: usize,
+    //elements in the interaction states.
+    stated: HashMap<State, Vec<ElementKey>>,
+    //the element which takes the input, only one of them is focused at a time.
+    #[getset(get_copy = "pub(crate)")]
+    focused: Option<ElementKey>,
 }
 
 deref!(PageContext, ElementHolder, eh);
@@ -150,9 +331,20 @@ impl PageContext {
             body_key,
             style_key,
             script_key,
+            theme: Default::default(),
+            changes: Default::default(),
+            restyles: Default::default(),
+            revision: 0,
+            stated: Default::default(),
+            focused: None,
         }
     }
 
+    ///The palette, default font and metrics of page.
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
     pub fn head_element(&self) -> Option<&Element> {
         self.eh.get(self.head_key)
     }
@@ -168,6 +360,251 @@ impl PageContext {
     pub fn script_element(&self) -> Option<&Element> {
         self.eh.get(self.script_key)
     }
+
+    fn is_structure(&self, key: ElementKey) -> bool {
+        key == self.head_key
+            || key == self.body_key
+            || key == self.style_key
+            || key == self.script_key
+            || self.eh.first_root() == Some(key)
+    }
+
+    //the body is laid out by itself, others by their upper.
+    pub(super) fn upper_or_self(&self, key: ElementKey) -> ElementKey {
+        if key == self.body_key {
+            return key;
+        }
+        self.eh.get(key).and_then(|e| *e.upper()).unwrap_or(key)
+    }
+
+    //records that the subset of `key` changed, `renew` is an element whose draw unit must be created again.
+    pub(super) fn push_change(&mut self, key: ElementKey, renew: Option<ElementKey>) {
+        self.revision += 1;
+        if !self.changes.contains(&(key, renew)) {
+            self.changes.push((key, renew));
+        }
+    }
+
+    //records that an element only looks different, the body is always laid out again.
+    pub(super) fn push_restyle(&mut self, key: ElementKey) {
+        if key == self.body_key {
+            return self.push_change(key, None);
+        }
+        self.revision += 1;
+        if !self.restyles.contains(&key) {
+            self.restyles.push(key);
+        }
+    }
+
+    ///Records attributes changed on an element, it's laid out again unless all of them only change the look.
+    pub(crate) fn push_restyled(&mut self, key: ElementKey, names: &[AttrName]) {
+        if names.iter().all(|n| n.is_paint_only()) {
+            self.push_restyle(key);
+        } else {
+            let upper = self.upper_or_self(key);
+            self.push_change(upper, None);
+        }
+    }
+
+    ///Updates the interaction states of elements after an action, returns true if any of them changed.
+    pub(crate) fn track_state(
+        &mut self,
+        a: &ActionKind,
+        hits: &[ElementKey],
+        events: &[Event],
+    ) -> bool {
+        let mut r = match a {
+            ActionKind::Cursor(..) | ActionKind::CursorWithoutFocus(..) | ActionKind::Sweep(..) => {
+                self.set_state(State::Hover, hits)
+            }
+            ActionKind::CursorLeft => self.set_state(State::Hover, &[]),
+            ActionKind::Pressed(..) => {
+                self.set_state(State::Hover, hits) | self.set_state(State::Pressed, hits)
+            }
+            ActionKind::Released(_) => self.set_state(State::Pressed, &[]),
+            _ => false,
+        };
+        for e in events {
+            match e.kind() {
+                EventKind::Focus => r |= self.set_state_one(e.target(), State::Focus, true),
+                EventKind::Blur => r |= self.set_state_one(e.target(), State::Focus, false),
+                _ => {}
+            }
+        }
+        r
+    }
+
+    fn set_state(&mut self, s: State, ks: &[ElementKey]) -> bool {
+        let mut r = false;
+        for k in self.stated.remove(&s).unwrap_or_default() {
+            if !ks.contains(&k) {
+                r |= self.set_state_one(k, s, false);
+            }
+        }
+        for &k in ks {
+            r |= self.set_state_one(k, s, true);
+        }
+        self.stated.insert(s, ks.to_vec());
+        r
+    }
+
+    //the draw unit of a changed element looks the state, disabled elements are never pressed or focused.
+    fn set_state_one(&mut self, key: ElementKey, s: State, b: bool) -> bool {
+        let e = option_return!(self.eh.get_mut(key), false);
+        if b && matches!(s, State::Pressed | State::Focus) && e.disabled() == Some(&true) {
+            return false;
+        }
+        let mut o = e.state();
+        o.set(s, b);
+        if o == e.state() {
+            return false;
+        }
+        *e.state_mut() = o;
+        self.push_restyle(key);
+        true
+    }
+
+    //replaces the focused element, returns the one focused before.
+    pub(crate) fn set_focused(&mut self, key: Option<ElementKey>) -> Option<ElementKey> {
+        std::mem::replace(&mut self.focused, key)
+    }
+
+    pub(crate) fn take_changes(&mut self) -> Vec<(ElementKey, Option<ElementKey>)> {
+        std::mem::take(&mut self.changes)
+    }
+
+    pub(crate) fn revision(&self) -> usize {
+        self.revision
+    }
+
+    pub(crate) fn take_restyles(&mut self) -> Vec<ElementKey> {
+        std::mem::take(&mut self.restyles)
+    }
+
+    ///Creates an element which is not in the tree, `insert_child` puts it into, or it stays until the page is dropped.
+    pub(crate) fn create(&mut self, e: Element) -> ElementKey {
+        self.eh.create(e)
+    }
+
+    ///Puts an element into the subset of `upper` at `index`, or at the end if `index` is None.
+    pub(crate) fn insert_child(
+        &mut self,
+        upper: ElementKey,
+        key: ElementKey,
+        index: Option<usize>,
+    ) -> bool {
+        if self.is_structure(key) {
+            return false;
+        }
+        let old = self.eh.get(key).and_then(|e| *e.upper());
+        if !self.eh.move_to(key, upper, index) {
+            return false;
+        }
+        if let Some(old) = old {
+            self.push_change(old, self.renew_of(old));
+        }
+        self.push_change(upper, self.renew_of(upper));
+        true
+    }
+
+    ///Removes an element and its subset from the subset of `upper`.
+    pub(crate) fn remove_child(&mut self, upper: ElementKey, key: ElementKey) -> bool {
+        if self.is_structure(key) {
+            return false;
+        }
+        if self.eh.get(key).and_then(|e| *e.upper()) != Some(upper) {
+            return false;
+        }
+        self.eh.remove(key);
+        if self.focused.is_some_and(|k| self.eh.get(k).is_none()) {
+            self.focused = None;
+        }
+        self.push_change(upper, self.renew_of(upper));
+        true
+    }
+
+    //"body" and "area" lay their subset out again, other draw units read their subset when created,
+    //such as "select" with options, so they are created again.
+    fn renew_of(&self, upper: ElementKey) -> Option<ElementKey> {
+        let e = self.eh.get(upper)?;
+        (!matches!(e.mark_type(), Mark::AREA | Mark::BODY)).then_some(upper)
+    }
+
+    pub(crate) fn set_text(&mut self, key: ElementKey, s: String) -> bool {
+        let e = option_return!(self.eh.get_mut(key), false);
+        *e.text_mut() = s;
+        let upper = self.upper_or_self(key);
+        self.push_change(upper, None);
+        true
+    }
+
+    pub(crate) fn set_attribute(&mut self, key: ElementKey, a: Attribute) -> bool {
+        let e = option_return!(self.eh.get_mut(key), false);
+        e.attribute_insert(a);
+        let upper = self.upper_or_self(key);
+        self.push_change(upper, None);
+        true
+    }
+
+    pub(crate) fn remove_attribute(&mut self, key: ElementKey, a: &AttrName) -> Option<Attribute> {
+        let r = self.eh.get_mut(key)?.attribute_remove(a)?;
+        let upper = self.upper_or_self(key);
+        self.push_change(upper, None);
+        Some(r)
+    }
+}
+
+///Represents the kind of event which runs a handler of element.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum EventKind {
+    Blur,
+    Change,
+    Click,
+    Focus,
+    Invalid,
+}
+
+impl EventKind {
+    pub(crate) fn as_str(&self) -> &str {
+        match self {
+            Self::Blur => "blur",
+            Self::Change => "change",
+            Self::Click => "click",
+            Self::Focus => "focus",
+            Self::Invalid => "invalid",
+        }
+    }
+
+    pub(crate) fn attr_name(&self) -> AttrName {
+        match self {
+            Self::Blur => AttrName::ONBLUR,
+            Self::Change => AttrName::ONCHANGE,
+            Self::Click => AttrName::ONCLICK,
+            Self::Focus => AttrName::ONFOCUS,
+            Self::Invalid => AttrName::ONINVALID,
+        }
+    }
+}
+
+///Represents an event of element.
+#[derive(Clone, Debug, CopyGetters, Getters)]
+pub(crate) struct Event {
+    #[getset(get_copy = "pub(crate)")]
+    kind: EventKind,
+    #[getset(get_copy = "pub(crate)")]
+    target: ElementKey,
+    #[getset(get = "pub(crate)")]
+    coord: Option<Coord2D>,
+}
+
+impl Event {
+    pub(crate) fn new(kind: EventKind, target: ElementKey, coord: Option<Coord2D>) -> Self {
+        Self {
+            kind,
+            target,
+            coord,
+        }
+    }
 }
 
 ///Represents the kind of action.
@@ -189,6 +626,19 @@ pub enum ActionKind {
 }
 
 impl ActionKind {
+    ///Returns the coordinate of cursor, the new one for sweep.
+    pub(crate) fn coord(&self) -> Option<&Coord2D> {
+        match self {
+            Self::Click(c, _)
+            | Self::DoubleClick(c, _)
+            | Self::Pressed(c, _)
+            | Self::Cursor(c, _)
+            | Self::CursorWithoutFocus(c, _)
+            | Self::Sweep(c, _, _) => Some(c),
+            _ => None,
+        }
+    }
+
     pub(crate) fn set_var_cursor(&mut self, x: f32, y: f32) {
         match self {
             Self::Click(c, _)
@@ -209,3 +659,479 @@ impl ActionKind {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(s: &str) -> Page {
+        let (p, err) = Page::parse(s);
+        assert!(err.is_empty(), "{err}");
+        let mut p = p.unwrap();
+        p.resize(400.0, 300.0);
+        p
+    }
+
+    fn key(p: &Page, id: &str) -> ElementKey {
+        p.query_one(&format!(".id={id}")).unwrap().unwrap()
+    }
+
+    fn pt(width: &str) -> Element {
+        let mut e = Element::new(Mark::PT, "d".to_string(), Default::default());
+        for (a, s) in [(AttrName::WIDTH, width), (AttrName::HEIGHT, "20")] {
+            e.attribute_insert(Attribute::from(&a, &mut s.to_string()).unwrap());
+        }
+        e
+    }
+
+    #[test]
+    fn edit_in_area() {
+        let mut p = page(
+            "<aht><head></head><body><pt width=50 height=20>a</pt>\
+            <area width=150 height=80><pt width=40 height=20>c</pt></area>\
+            </body><style></style><script></script></aht>",
+        );
+        let subset = |p: &Page, k| p.context.read().unwrap().get_subset(k);
+        let body = p.context.read().unwrap().body_key();
+        let b = subset(&p, body)[1];
+        let c = subset(&p, b)[0];
+
+        let d = p.insert_element(b, Some(0), pt("30")).unwrap();
+        assert_eq!(subset(&p, b), [d, c]);
+
+        let w = Attribute::from(&AttrName::WIDTH, &mut "45".to_string()).unwrap();
+        assert!(p.set_attribute(c, w));
+        assert!(p.remove_attribute(c, &AttrName::WIDTH).is_some());
+        assert!(p.remove_attribute(c, &AttrName::WIDTH).is_none());
+
+        assert!(p.remove_element(d));
+        assert!(p.context.read().unwrap().get(d).is_none());
+        assert_eq!(subset(&p, b), [c]);
+
+        assert!(p.move_element(c, body, None));
+        assert!(subset(&p, b).is_empty());
+        assert_eq!(subset(&p, body).last(), Some(&c));
+        assert!(!p.remove_element(body));
+    }
+
+    fn bounds(p: &Page, id: &str) -> Bounds {
+        p.layout_of(key(p, id)).unwrap().rect()
+    }
+
+    fn flow_page(body: &str, subset: &str) -> Page {
+        page(&format!(
+            "<aht><head></head><body {body}>{subset}</body><style></style><script></script></aht>"
+        ))
+    }
+
+    #[test]
+    fn flow_wrap() {
+        let p = flow_page(
+            "flow=\"row, wrap\" gap=10",
+            "<pt id=a width=150 height=20>a</pt><pt id=b width=150 height=30>b</pt>\
+            <pt id=c width=150 height=20>c</pt><pt id=d width=380 height=20>d</pt>",
+        );
+        assert_eq!(bounds(&p, "a"), Bounds::new(0.0, 0.0, 150.0, 20.0));
+        assert_eq!(bounds(&p, "b"), Bounds::new(160.0, 0.0, 150.0, 30.0));
+        assert_eq!(bounds(&p, "c"), Bounds::new(0.0, 40.0, 150.0, 20.0));
+        assert_eq!(bounds(&p, "d"), Bounds::new(0.0, 70.0, 380.0, 20.0));
+
+        let p = flow_page(
+            "flow=column gap=5",
+            "<pt id=a width=150 height=20>a</pt><pt id=b width=100 height=30>b</pt>",
+        );
+        assert_eq!(bounds(&p, "b"), Bounds::new(0.0, 25.0, 100.0, 30.0));
+    }
+
+    #[test]
+    fn flow_justify_align() {
+        let items = "<pt id=a width=50 height=20>a</pt><pt id=b width=50 height=40>b</pt>\
+            <pt id=c width=50 height=20>c</pt>";
+        let p = flow_page("flow=row justify=space-between align=center", items);
+        assert_eq!(bounds(&p, "a"), Bounds::new(0.0, 10.0, 50.0, 20.0));
+        assert_eq!(bounds(&p, "b"), Bounds::new(175.0, 0.0, 50.0, 40.0));
+        assert_eq!(bounds(&p, "c"), Bounds::new(350.0, 10.0, 50.0, 20.0));
+
+        let p = flow_page("flow=row gap=10 justify=end align=end", items);
+        assert_eq!(bounds(&p, "a"), Bounds::new(230.0, 20.0, 50.0, 20.0));
+        assert_eq!(bounds(&p, "c"), Bounds::new(350.0, 20.0, 50.0, 20.0));
+
+        let p = flow_page("flow=column justify=center", items);
+        assert_eq!(bounds(&p, "a"), Bounds::new(0.0, 110.0, 50.0, 20.0));
+        assert_eq!(bounds(&p, "c"), Bounds::new(0.0, 170.0, 50.0, 20.0));
+    }
+
+    #[test]
+    fn flow_content() {
+        let mut p = flow_page(
+            "flow=row",
+            "<area id=f flow=row gap=5>\
+            <area id=g flow=column><pt id=a width=40 height=20>a</pt><pt id=b width=30 height=20>b</pt></area>\
+            <pt id=c width=40 height=30>c</pt></area>\
+            <pt id=d width=10 height=10>d</pt>",
+        );
+        assert_eq!(bounds(&p, "g"), Bounds::new(0.0, 0.0, 40.0, 40.0));
+        assert_eq!(bounds(&p, "b"), Bounds::new(0.0, 20.0, 30.0, 20.0));
+        assert_eq!(bounds(&p, "c"), Bounds::new(45.0, 0.0, 40.0, 30.0));
+        assert_eq!(bounds(&p, "f"), Bounds::new(0.0, 0.0, 85.0, 40.0));
+        assert_eq!(bounds(&p, "d"), Bounds::new(85.0, 0.0, 10.0, 10.0));
+
+        //the areas fit the changed subset.
+        let b = key(&p, "b");
+        let w = Attribute::from(&AttrName::WIDTH, &mut "60".to_string()).unwrap();
+        assert!(p.set_attribute(b, w));
+        assert_eq!(bounds(&p, "f"), Bounds::new(0.0, 0.0, 105.0, 40.0));
+        assert_eq!(bounds(&p, "c"), Bounds::new(65.0, 0.0, 40.0, 30.0));
+        assert_eq!(bounds(&p, "d"), Bounds::new(105.0, 0.0, 10.0, 10.0));
+    }
+
+    #[test]
+    fn auto_side() {
+        let path = std::env::temp_dir().join("auto_side.png");
+        image::RgbImage::new(30, 20).save(&path).unwrap();
+        let s = format!(
+            "<img id=i width=auto height=auto>{}</img><pt id=a width=10 height=10>a</pt>",
+            path.display()
+        );
+        let mut p = flow_page("column=\"auto 100\"", &s);
+        assert_eq!(bounds(&p, "i"), Bounds::new(0.0, 0.0, 30.0, 20.0));
+        assert_eq!(bounds(&p, "a"), Bounds::new(30.0, 0.0, 10.0, 10.0));
+
+        //the side is kept without reading the file again.
+        std::fs::remove_file(&path).unwrap();
+        p.resize(300.0, 200.0);
+        assert_eq!(bounds(&p, "i"), Bounds::new(0.0, 0.0, 30.0, 20.0));
+
+        let p = flow_page(
+            "flow=row",
+            "<button id=a>a</button><button id=b>aaaa</button>\
+            <button id=c width=auto height=40>a</button>",
+        );
+        let (a, b, c) = (bounds(&p, "a"), bounds(&p, "b"), bounds(&p, "c"));
+        assert!(a.width() > 0.0 && a.width() < b.width());
+        assert!(a.height() > 0.0 && a.height() == b.height());
+        assert_eq!((b.x(), c.x()), (a.width(), a.width() + b.width()));
+        assert_eq!((c.width(), c.height()), (a.width(), 40.0));
+    }
+
+    #[test]
+    fn insets() {
+        let p = flow_page(
+            "column=\"200 200\"",
+            "<area id=f width=200 height=100 border-width=2 padding=\"10,5\" column=\"50 50\">\
+            <pt id=a width=20 height=10>a</pt><pt id=b width=20 height=10 margin=3>b</pt></area>\
+            <area id=g width=100 height=50 margin=\"4,6\" padding=\"10%\" flow=row>\
+            <pt id=c width=20 height=10>c</pt></area>",
+        );
+        assert_eq!(bounds(&p, "f"), Bounds::new(0.0, 0.0, 200.0, 100.0));
+        assert_eq!(bounds(&p, "a"), Bounds::new(12.0, 7.0, 20.0, 10.0));
+        assert_eq!(bounds(&p, "b"), Bounds::new(65.0, 10.0, 20.0, 10.0));
+        assert_eq!(bounds(&p, "g"), Bounds::new(204.0, 6.0, 100.0, 50.0));
+        assert_eq!(bounds(&p, "c"), Bounds::new(214.0, 11.0, 20.0, 10.0));
+
+        //a fitted area takes the border and the padding around its subset.
+        let p = flow_page(
+            "flow=row",
+            "<area id=f border-width=2 padding=5 margin=1><pt id=a width=20 height=10>a</pt></area>\
+            <pt id=b width=10 height=10>b</pt>",
+        );
+        assert_eq!(bounds(&p, "f"), Bounds::new(1.0, 1.0, 34.0, 24.0));
+        assert_eq!(bounds(&p, "a"), Bounds::new(8.0, 8.0, 20.0, 10.0));
+        assert_eq!(bounds(&p, "b"), Bounds::new(36.0, 0.0, 10.0, 10.0));
+    }
+
+    #[test]
+    fn dump() {
+        let p = flow_page(
+            "column=\"200 200\" row=\"100 100\"",
+            "<pt id=a width=50 height=20>a</pt>\
+            <area id=f width=200 height=100 border-width=2 padding=3 flow=row gap=4>\
+            <pt id=b width=20 height=10>b</pt><pt id=c width=30 height=10>c</pt></area>\
+            <pt id=d width=40 height=20>d</pt>",
+        );
+        let l = p.layout_of(key(&p, "c")).unwrap();
+        assert_eq!(l.rect(), Bounds::new(229.0, 5.0, 30.0, 10.0));
+        assert_eq!(l.relative(), Bounds::new(29.0, 5.0, 30.0, 10.0));
+        assert_eq!(l.clip(), Some(Bounds::new(200.0, 0.0, 200.0, 100.0)));
+        let head = p.query_one("head").unwrap().unwrap();
+        assert!(p.layout_of(head).is_none());
+
+        let s = "body rect: (0,0,400,300), relative: (0,0,400,300), clip: none, scroll: (0,0), z: 0
+  pt#a rect: (0,0,50,20), relative: (0,0,50,20), clip: (0,0,400,300), scroll: (0,0), z: 0
+  area#f rect: (200,0,200,100), relative: (200,0,200,100), clip: (0,0,400,300), scroll: (0,0), z: 0
+    pt#b rect: (205,5,20,10), relative: (5,5,20,10), clip: (200,0,200,100), scroll: (0,0), z: 0
+    pt#c rect: (229,5,30,10), relative: (29,5,30,10), clip: (200,0,200,100), scroll: (0,0), z: 0
+  pt#d rect: (0,100,40,20), relative: (0,100,40,20), clip: (0,0,400,300), scroll: (0,0), z: 0
+";
+        assert_eq!(p.dump_layout(), s);
+    }
+
+    fn x_width(p: &Page, k: ElementKey) -> (f32, f32) {
+        let r = p.layout_of(k).unwrap().rect();
+        (r.x(), r.width())
+    }
+
+    #[test]
+    fn layout_after_edits() {
+        let mut p = flow_page(
+            "column=\"200 200\" row=\"100 100\"",
+            "<pt width=50 height=20>a</pt>\
+            <area id=b width=150 height=80 column=\"50 50 50\" row=\"40 40\">\
+            <pt id=c width=40 height=20>c</pt></area>",
+        );
+        let b = key(&p, "b");
+        let c = key(&p, "c");
+        assert_eq!(x_width(&p, c), (200.0, 40.0));
+
+        let d = p.insert_element(b, Some(0), pt("30")).unwrap();
+        assert_eq!(x_width(&p, d), (200.0, 30.0));
+        assert_eq!(x_width(&p, c), (250.0, 40.0));
+
+        let w = Attribute::from(&AttrName::WIDTH, &mut "45".to_string()).unwrap();
+        assert!(p.set_attribute(c, w));
+        assert_eq!(x_width(&p, c), (250.0, 45.0));
+        assert!(p.remove_attribute(c, &AttrName::WIDTH).is_some());
+        assert_eq!(x_width(&p, c).0, 250.0);
+
+        assert!(p.remove_element(d));
+        assert!(p.layout_of(d).is_none());
+        assert_eq!(x_width(&p, c).0, 200.0);
+
+        let body = p.query_one("body").unwrap().unwrap();
+        assert!(p.move_element(c, body, None));
+        assert_eq!(x_width(&p, c).0, 0.0);
+        assert_eq!(p.layout_of(c).unwrap().rect().y(), 100.0);
+    }
+
+    fn act(p: &mut Page, a: ActionKind) -> Vec<(EventKind, ElementKey)> {
+        let v = p.consume_action(a);
+        v.into_iter().map(|(_, e)| (e.kind(), e.target())).collect()
+    }
+
+    fn pressed(x: f32, y: f32) -> ActionKind {
+        ActionKind::Pressed((x, y).into(), 0)
+    }
+
+    fn sweep(a: (f32, f32), b: (f32, f32)) -> ActionKind {
+        ActionKind::Sweep(a.into(), b.into(), (a.0 - b.0, a.1 - b.1))
+    }
+
+    const FORM: &str = "<aht><head></head><body column=\"200 200\">\
+        <button id=a onclick=\"c\" onfocus=\"f\" onblur=\"b\">a</button>\
+        <select id=s onchange=\"c\" onfocus=\"f\" onblur=\"b\">\
+        <option id=o selected=true>o</option><option id=q>q</option></select>\
+        </body><style></style><script></script></aht>";
+
+    //"x" is met before "y" in drawing, but it's above "y".
+    const OVERLAY: &str = "<aht><head></head><body>\
+        <area id=p overlay=true position=\"0,0\" width=200 height=200>\
+        <area id=q position=\"0,0\" width=100 height=100>\
+        <button id=x overlay=true z=5 position=\"10,10\" width=50 height=50 radius=0 \
+        background=\"#ff0000\" onclick=\"c\">x</button></area>\
+        <button id=y overlay=true z=1 position=\"10,10\" width=80 height=80 radius=0 \
+        background=\"#00ff00\" onclick=\"c\">y</button>\
+        </area></body><style></style><script></script></aht>";
+
+    #[test]
+    fn overlay_z() {
+        let mut p = page(OVERLAY);
+        let i = crate::RasterRenderer::new(400, 300, 1.0)
+            .draw(&mut p)
+            .unwrap();
+        let px = i.peek_pixels().unwrap();
+        assert_eq!(
+            px.get_color((15, 55)),
+            skia_safe::Color::from_rgb(255, 0, 0)
+        );
+        assert_eq!(
+            px.get_color((75, 75)),
+            skia_safe::Color::from_rgb(0, 255, 0)
+        );
+    }
+
+    #[test]
+    fn overlay_action() {
+        let mut p = page(OVERLAY);
+        crate::RasterRenderer::new(400, 300, 1.0).draw(&mut p);
+        let (x, y) = (key(&p, "x"), key(&p, "y"));
+        act(&mut p, pressed(15.0, 55.0));
+        assert_eq!(
+            act(&mut p, ActionKind::Released(0)),
+            [(EventKind::Click, x)]
+        );
+        act(&mut p, pressed(75.0, 75.0));
+        assert_eq!(
+            act(&mut p, ActionKind::Released(0)),
+            [(EventKind::Click, y)]
+        );
+    }
+
+    #[test]
+    fn click_on_release() {
+        let mut p = page(FORM);
+        let a = key(&p, "a");
+        assert_eq!(act(&mut p, pressed(50.0, 30.0)), [(EventKind::Focus, a)]);
+        assert_eq!(
+            act(&mut p, ActionKind::Released(0)),
+            [(EventKind::Click, a)]
+        );
+
+        //moved off the button before released.
+        assert!(act(&mut p, pressed(50.0, 30.0)).is_empty());
+        assert!(act(&mut p, sweep((300.0, 150.0), (50.0, 30.0))).is_empty());
+        assert!(act(&mut p, ActionKind::Released(0)).is_empty());
+
+        //moved off and back.
+        act(&mut p, pressed(50.0, 30.0));
+        act(&mut p, sweep((300.0, 150.0), (50.0, 30.0)));
+        act(&mut p, sweep((60.0, 40.0), (300.0, 150.0)));
+        assert_eq!(
+            act(&mut p, ActionKind::Released(0)),
+            [(EventKind::Click, a)]
+        );
+
+        assert!(act(&mut p, ActionKind::Released(0)).is_empty());
+        assert_eq!(act(&mut p, pressed(300.0, 150.0)), [(EventKind::Blur, a)]);
+
+        assert!(p.set_attribute(a, Attribute::DISABLED(true)));
+        assert!(act(&mut p, pressed(50.0, 30.0)).is_empty());
+        assert!(act(&mut p, ActionKind::Released(0)).is_empty());
+    }
+
+    #[test]
+    fn select_change() {
+        let mut p = page(FORM);
+        let s = key(&p, "s");
+        let o = key(&p, "o");
+        let q = key(&p, "q");
+        let selected = |p: &Page, k| {
+            p.context
+                .read()
+                .unwrap()
+                .get(k)
+                .unwrap()
+                .selected()
+                .copied()
+        };
+
+        assert_eq!(act(&mut p, pressed(250.0, 50.0)), [(EventKind::Focus, s)]);
+        assert_eq!(
+            act(&mut p, ActionKind::Released(0)),
+            [(EventKind::Change, s)]
+        );
+        assert_eq!(selected(&p, o), None);
+        assert_eq!(selected(&p, q), Some(true));
+
+        act(&mut p, pressed(250.0, 50.0));
+        assert_eq!(
+            act(&mut p, ActionKind::Released(0)),
+            [(EventKind::Change, s)]
+        );
+        assert_eq!(selected(&p, o), Some(true));
+        assert_eq!(selected(&p, q), None);
+
+        act(&mut p, pressed(250.0, 50.0));
+        act(&mut p, sweep((100.0, 150.0), (250.0, 50.0)));
+        assert!(act(&mut p, ActionKind::Released(0)).is_empty());
+        assert_eq!(selected(&p, o), Some(true));
+    }
+
+    #[test]
+    fn inp_delete() {
+        let mut p = page(
+            "<aht><head></head><body column=\"200 200\">\
+            <inp id=i value=\"abc\" width=100 height=30 onchange=\"c\"></inp>\
+            </body><style></style><script></script></aht>",
+        );
+        let i = key(&p, "i");
+        let value = |p: &Page| p.context.read().unwrap().get(i).unwrap().value().cloned();
+
+        //not focused yet.
+        assert!(act(&mut p, ActionKind::DeleteFront(1)).is_empty());
+        assert_eq!(value(&p).as_deref(), Some("abc"));
+
+        act(&mut p, pressed(50.0, 15.0));
+        assert_eq!(
+            act(&mut p, ActionKind::DeleteFront(1)),
+            [(EventKind::Change, i)]
+        );
+        assert_eq!(value(&p).as_deref(), Some("ab"));
+        assert_eq!(
+            act(&mut p, ActionKind::DeleteBack(2)),
+            [(EventKind::Change, i)]
+        );
+        assert_eq!(value(&p).as_deref(), Some(""));
+
+        //nothing is left to remove.
+        assert!(act(&mut p, ActionKind::DeleteFront(1)).is_empty());
+    }
+
+    #[test]
+    fn focus_moves() {
+        let mut p = page(FORM);
+        let a = key(&p, "a");
+        let s = key(&p, "s");
+        let focused = |p: &Page, k| p.context.read().unwrap().get(k).unwrap().state().focus();
+
+        assert_eq!(act(&mut p, pressed(50.0, 30.0)), [(EventKind::Focus, a)]);
+        act(&mut p, ActionKind::Released(0));
+        assert!(focused(&p, a));
+
+        //the select is met first, the button is blurred without seeing the press.
+        assert_eq!(
+            act(&mut p, pressed(250.0, 50.0)),
+            [(EventKind::Blur, a), (EventKind::Focus, s)]
+        );
+        act(&mut p, ActionKind::Released(0));
+        assert!(!focused(&p, a));
+        assert!(focused(&p, s));
+
+        assert_eq!(
+            act(&mut p, pressed(50.0, 30.0)),
+            [(EventKind::Blur, s), (EventKind::Focus, a)]
+        );
+        assert!(focused(&p, a));
+        assert!(!focused(&p, s));
+    }
+
+    #[test]
+    fn restyle_paint() {
+        let mut p = page(
+            "<aht><head></head><body><button id=a>a</button></body>\
+            <style>button:hover{color:#ff0000;} button:pressed{width:150;}</style>\
+            <script></script></aht>",
+        );
+        let a = key(&p, "a");
+        let body = p.query_one("body").unwrap().unwrap();
+        let mut cx = p.context.write().unwrap();
+        cx.take_changes();
+        cx.take_restyles();
+        let mut track = |cx: &mut PageContext, o: ActionKind| {
+            assert!(cx.track_state(&o, &[a], &[]));
+            for (k, v) in p.style.restyle(cx) {
+                cx.push_restyled(k, &v);
+            }
+            (cx.take_changes(), cx.take_restyles())
+        };
+
+        let o = ActionKind::Cursor((50.0, 30.0).into(), (0.0, 0.0));
+        let (changes, restyles) = track(&mut cx, o);
+        assert!(changes.is_empty());
+        assert_eq!(restyles, [a]);
+        let (changes, restyles) = track(&mut cx, pressed(50.0, 30.0));
+        assert_eq!(changes, [(body, None)]);
+        assert_eq!(restyles, [a]);
+        assert!(cx.get(a).unwrap().state().pressed());
+
+        //a disabled button is hovered, but never pressed or focused.
+        assert!(cx.track_state(&ActionKind::Released(0), &[], &[]));
+        cx.get_mut(a)
+            .unwrap()
+            .attribute_insert(Attribute::DISABLED(true));
+        assert!(!cx.track_state(&pressed(50.0, 30.0), &[a], &[]));
+        let e = Event::new(EventKind::Focus, a, None);
+        assert!(!cx.track_state(&ActionKind::Focused(true), &[], &[e]));
+        assert!(!cx.get(a).unwrap().state().pressed());
+        assert!(!cx.get(a).unwrap().state().focus());
+    }
+}
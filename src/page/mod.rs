@@ -1,8 +1,14 @@
+mod animate;
 mod entity;
+mod watch;
 
+use self::animate::*;
 pub use self::entity::*;
+pub use self::watch::*;
 use crate::content::*;
 use crate::error::*;
+use crate::global::*;
 use crate::markup::*;
 use crate::metadata::*;
+use crate::style::{StyleOrigin, Viewport};
 use crate::utils::*;
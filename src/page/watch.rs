@@ -0,0 +1,49 @@
+use super::*;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+///Watches an external style file by polling its modified time, and reloads the style sheet of page when it changes.
+#[derive(Debug)]
+pub struct StyleWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    time_meter: Chronograph,
+}
+
+impl StyleWatcher {
+    ///Creates a new instance, the file is checked once per `interval` milliseconds at most.
+    pub fn new(path: impl Into<PathBuf>, interval: u64) -> Self {
+        let mut time_meter = Chronograph::new(interval);
+        time_meter.expire();
+        Self {
+            path: path.into(),
+            modified: None,
+            time_meter,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    ///Reloads the style sheet if the file changed since the last time, returns the errors of the new style sheet.
+    ///The file is loaded at the first time.
+    pub fn poll(&mut self, page: &mut Page) -> Option<ErrorHolder> {
+        if !self.time_meter.elapsed() {
+            return None;
+        }
+        self.time_meter.refresh();
+        let m = std::fs::metadata(&self.path)
+            .and_then(|o| o.modified())
+            .map_err(|e| warn!("StyleWatcher {:?}: {e}", self.path))
+            .ok()?;
+        if self.modified == Some(m) {
+            return None;
+        }
+        let s = std::fs::read_to_string(&self.path)
+            .map_err(|e| warn!("StyleWatcher {:?}: {e}", self.path))
+            .ok()?;
+        self.modified.replace(m);
+        Some(page.reload_style(&s))
+    }
+}
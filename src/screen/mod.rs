@@ -1,6 +1,8 @@
+mod raster;
 #[cfg(feature = "window")]
 mod window;
 
+pub use self::raster::*;
 #[cfg(feature = "window")]
 pub use self::window::*;
 use crate::error::*;
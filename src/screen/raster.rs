@@ -0,0 +1,110 @@
+use super::*;
+use skia_safe::gpu::DirectContext;
+use skia_safe::{EncodedImageFormat, Image, surfaces};
+
+///Represents the format of encoded image.
+#[derive(Clone, Copy, Debug)]
+pub enum ImageKind {
+    JPEG,
+    PNG,
+    WEBP,
+}
+
+impl From<ImageKind> for EncodedImageFormat {
+    fn from(o: ImageKind) -> Self {
+        match o {
+            ImageKind::JPEG => Self::JPEG,
+            ImageKind::PNG => Self::PNG,
+            ImageKind::WEBP => Self::WEBP,
+        }
+    }
+}
+
+///Represents renderer on CPU memory, it needs neither window nor GPU.
+#[derive(CopyGetters, Debug, Setters)]
+pub struct RasterRenderer {
+    #[getset(get_copy = "pub")]
+    width: u32,
+    #[getset(get_copy = "pub")]
+    height: u32,
+    #[getset(get_copy = "pub", set = "pub")]
+    scale_factor: f32,
+}
+
+impl RasterRenderer {
+    ///Creates a renderer with physical width and height.
+    pub fn new(width: u32, height: u32, scale_factor: f32) -> Self {
+        Self {
+            width,
+            height,
+            scale_factor,
+        }
+    }
+
+    ///Resize physical width and height.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.width = width;
+        self.height = height;
+    }
+
+    ///Render a page to an image. The page is laid out with logical width and height.
+    pub fn draw(&mut self, page: &mut Page) -> Option<Image> {
+        if self.width == 0 || self.height == 0 {
+            return None;
+        }
+        let scale = if self.scale_factor > 0.0 {
+            self.scale_factor
+        } else {
+            1.0
+        };
+        let mut surface = surfaces::raster_n32_premul((self.width as i32, self.height as i32))?;
+        page.set_scale_factor(scale);
+        page.resize(self.width as f32 / scale, self.height as f32 / scale);
+        surface.canvas().scale((scale, scale));
+        page.draw_body(surface.clone());
+        Some(surface.image_snapshot())
+    }
+
+    ///Render a page and encode the image, `quality` is in 0..=100.
+    pub fn encode(&mut self, page: &mut Page, kind: ImageKind, quality: u32) -> Option<Vec<u8>> {
+        let image = self.draw(page)?;
+        image
+            .encode(None::<&mut DirectContext>, kind.into(), quality)
+            .map(|d| d.as_bytes().to_vec())
+            .or_else(|| {
+                error!("encode {:?} failed", kind);
+                None
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn png() {
+        let s = "<aht><head></head><body column=[50],2><area></area><area></area></body><style></style><script></script></aht>";
+        let (page, err) = Page::parse(s);
+        assert!(err.is_empty(), "{err}");
+        let mut page = page.unwrap();
+        let mut r = RasterRenderer::new(200, 100, 2.0);
+        let v = r.encode(&mut page, ImageKind::PNG, 100).unwrap();
+        assert_eq!(&v[..4], b"\x89PNG");
+        let i = r.draw(&mut page).unwrap();
+        assert_eq!((i.width(), i.height()), (200, 100));
+    }
+
+    #[test]
+    fn theme() {
+        let s = "<aht><head></head><body><button>a</button></body><style></style><script></script></aht>";
+        let (page, _) = Page::parse(s);
+        let mut page = page.unwrap();
+        let mut r = RasterRenderer::new(100, 100, 1.0);
+        let v = r.encode(&mut page, ImageKind::PNG, 100).unwrap();
+        page.set_theme(crate::global::Theme::dark());
+        assert_eq!(page.read().unwrap().theme(), &crate::global::Theme::dark());
+        let v2 = r.encode(&mut page, ImageKind::PNG, 100).unwrap();
+        assert_ne!(v, v2);
+    }
+}
@@ -31,11 +31,14 @@ impl ScreenBuilder {
 ///Represents the context of window application.
 #[derive(Getters, MutGetters, Setters)]
 pub struct WindowContext {
-    #[getset(get = "pub", set = "pub")]
+    #[getset(get = "pub", get_mut = "pub", set = "pub")]
     page: Page,
     r: Option<(Renderer, Arc<Window>)>,
     #[getset(get = "pub", get_mut = "pub")]
     attributes: WindowAttributes,
+    ///Reloads the style sheet when an external file changes.
+    #[getset(get = "pub", set = "pub")]
+    style_watcher: Option<StyleWatcher>,
     fps_ctrl: FpsCtrl,
     fps_counter: FpsCounter,
     event_wrapper: WindowEventWrapper,
@@ -47,6 +50,7 @@ impl WindowContext {
             page,
             r: None,
             attributes,
+            style_watcher: None,
             fps_ctrl: WINDOW_FPS.into(),
             fps_counter: Default::default(),
             event_wrapper: Default::default(),
@@ -149,10 +153,20 @@ impl ApplicationHandler for WindowContext {
     }
 
     fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        if let Some(err) = self
+            .style_watcher
+            .as_mut()
+            .and_then(|o| o.poll(&mut self.page))
+        {
+            if !err.is_empty() {
+                warn!("{err}");
+            }
+        }
         if let Some((_, window)) = self.r.as_mut() {
             if let Some(o) = self.fps_ctrl.need_to_wait() {
                 event_loop.set_control_flow(ControlFlow::WaitUntil(o));
             } else {
+                self.page.animate();
                 window.request_redraw();
             }
         }
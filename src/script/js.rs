@@ -1,50 +1,39 @@
 use super::*;
+use slotmap::{Key, KeyData};
 use std::cell::OnceCell;
 use std::sync::mpsc::Receiver;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Once, RwLock};
 use v8::V8::{initialize, initialize_platform};
 use v8::{
-    Array, Context, ContextScope, External, Function, FunctionCallbackArguments, Global,
-    HandleScope, Isolate, Local, Object, ObjectTemplate, OwnedIsolate, PinScope,
-    PropertyDescriptor, ReturnValue, Script, String, new_default_platform,
+    Array, Context, ContextScope, Exception, External, Function, FunctionCallbackArguments, Global,
+    HandleScope, Isolate, Local, Number, Object, ObjectTemplate, OwnedIsolate, PinScope,
+    PropertyDescriptor, ReturnValue, Script, String, Value, new_default_platform,
 };
 
 const ROOT: &str = "page";
 
-#[derive(Default)]
-#[repr(transparent)]
-struct KeyAndExternal(Vec<ElementKey>);
+//the second internal field of element objects points to it, so other objects are rejected.
+static ELEMENT_BRAND: u8 = 0;
 
-impl KeyAndExternal {
-    fn get<'a>(
-        &mut self,
-        scope: &mut PinScope<'a, '_>,
-        k: ElementKey,
-    ) -> Option<Local<'a, External>> {
-        self.0.push(k);
-        let k = self.0.last()? as *const ElementKey;
-        let k = k as *mut core::ffi::c_void;
-        Some(External::new(scope, k))
-    }
-}
+static INIT: Once = Once::new();
 
 struct JSRuntime {
     isolate: OwnedIsolate,
     context: OnceCell<Global<Context>>,
     page: Arc<RwLock<PageContext>>,
-    v: KeyAndExternal,
 }
 
 impl JSRuntime {
     fn new(page: Arc<RwLock<PageContext>>) -> Self {
-        initialize_platform(new_default_platform(0, false).make_shared());
-        initialize();
+        INIT.call_once(|| {
+            initialize_platform(new_default_platform(0, false).make_shared());
+            initialize();
+        });
         let isolate = Isolate::new(Default::default());
         let mut o = Self {
             isolate,
             context: OnceCell::new(),
             page,
-            v: Default::default(),
         };
         o.context.get_or_init(|| {
             let scope = std::pin::pin!(HandleScope::new(&mut o.isolate));
@@ -75,7 +64,6 @@ impl JSRuntime {
             p.style_key(),
         )));
         let page = self.page.clone();
-        let v = &mut self.v as *mut KeyAndExternal;
         self.with_scope(|scope| {
             let t = ObjectTemplate::new(scope);
             t.set_internal_field_count(1);
@@ -83,10 +71,13 @@ impl JSRuntime {
             o.set_internal_field(0, External::new(scope, p).into());
 
             for k in ks {
-                let value = element_to_object(scope, k, &page, v)?;
+                let value = element_to_object(scope, k)?;
                 o.set(scope, to_key(scope, k, &page)?.into(), value.into());
             }
 
+            let func = Function::new(scope, func_create_element)?.into();
+            o.set(scope, String::new(scope, "createElement")?.into(), func);
+
             let global = scope.get_current_context().global(scope);
             global.set(scope, String::new(scope, ROOT)?.into(), o.into())
         });
@@ -100,6 +91,33 @@ impl JSRuntime {
             None
         });
     }
+
+    //runs a handler as a function with the target as `this` and `event` as argument.
+    fn dispatch(&mut self, s: &str, e: &Event) {
+        self.with_scope(|scope| {
+            let target = element_to_object(scope, e.target())?;
+            let o = Object::new(scope);
+            let k = String::new(scope, "type")?;
+            let a = String::new(scope, e.kind().as_str())?;
+            o.set(scope, k.into(), a.into());
+            let k = String::new(scope, "target")?;
+            o.set(scope, k.into(), target.into());
+            if let Some(c) = e.coord() {
+                let k = String::new(scope, "x")?;
+                let a = Number::new(scope, c.x() as f64);
+                o.set(scope, k.into(), a.into());
+                let k = String::new(scope, "y")?;
+                let a = Number::new(scope, c.y() as f64);
+                o.set(scope, k.into(), a.into());
+            }
+
+            let code = String::new(scope, &format!("(function(event) {{\n{s}\n}})"))?;
+            let f = Script::compile(scope, code, None)?.run(scope)?;
+            let f = <Local<'_, Function>>::try_from(f).ok()?;
+            f.call(scope, target.into(), &[o.into()])?;
+            None
+        });
+    }
 }
 
 #[inline]
@@ -112,18 +130,17 @@ fn to_key<'a>(
     String::new(scope, o.get(k)?.mark_type().as_str())
 }
 
-fn element_to_object<'a>(
-    scope: &mut PinScope<'a, '_>,
-    k: ElementKey,
-    page: &Arc<RwLock<PageContext>>,
-    v: *mut KeyAndExternal,
-) -> Option<Local<'a, Object>> {
+//the key is held as the value of an external instead of a pointer, so nothing is left behind
+//for an element after it's removed.
+fn element_to_object<'a>(scope: &mut PinScope<'a, '_>, k: ElementKey) -> Option<Local<'a, Object>> {
     let t = ObjectTemplate::new(scope);
-    t.set_internal_field_count(1);
+    t.set_internal_field_count(2);
     let obj = t.new_instance(scope)?;
 
-    let a = unsafe { v.as_mut()?.get(scope, k)? };
-    obj.set_internal_field(0, a.into());
+    let a = k.data().as_ffi() as usize as *mut core::ffi::c_void;
+    obj.set_internal_field(0, External::new(scope, a).into());
+    let b = &ELEMENT_BRAND as *const u8 as *mut core::ffi::c_void;
+    obj.set_internal_field(1, External::new(scope, b).into());
 
     let d = PropertyDescriptor::new_from_get_set(
         Function::new(scope, func_mark_type)?.into(),
@@ -133,20 +150,33 @@ fn element_to_object<'a>(
 
     let d = PropertyDescriptor::new_from_get_set(
         Function::new(scope, func_text)?.into(),
-        Function::new(scope, func_empty)?.into(),
+        Function::new(scope, func_set_text)?.into(),
     );
     obj.define_property(scope, String::new(scope, "text")?.into(), &d);
 
+    let d = PropertyDescriptor::new_from_get_set(
+        Function::new(scope, func_subset)?.into(),
+        Function::new(scope, func_empty)?.into(),
+    );
+    obj.define_property(scope, String::new(scope, "subset")?.into(), &d);
+
     let func = Function::new(scope, func_attribute)?.into();
     obj.set(scope, String::new(scope, "attribute")?.into(), func);
 
-    let subset = page.read().ok()?.get_subset(k);
-    let mut r = Vec::new();
-    for k in subset {
-        r.push(element_to_object(scope, k, page, v)?.into());
-    }
-    let subset = Array::new_with_elements(scope, &r);
-    obj.set(scope, String::new(scope, "subset")?.into(), subset.into());
+    let func = Function::new(scope, func_set_attribute)?.into();
+    obj.set(scope, String::new(scope, "setAttribute")?.into(), func);
+
+    let func = Function::new(scope, func_remove_attribute)?.into();
+    obj.set(scope, String::new(scope, "removeAttribute")?.into(), func);
+
+    let func = Function::new(scope, func_append_child)?.into();
+    obj.set(scope, String::new(scope, "appendChild")?.into(), func);
+
+    let func = Function::new(scope, func_insert_child)?.into();
+    obj.set(scope, String::new(scope, "insertChild")?.into(), func);
+
+    let func = Function::new(scope, func_remove_child)?.into();
+    obj.set(scope, String::new(scope, "removeChild")?.into(), func);
     Some(obj)
 }
 
@@ -168,6 +198,23 @@ fn func_text(scope: &mut PinScope, args: FunctionCallbackArguments, mut rv: Retu
     }
 }
 
+fn func_set_text(scope: &mut PinScope, args: FunctionCallbackArguments, _rv: ReturnValue) {
+    let k = option_return!(get_this_key(scope, &args));
+    let s = args.get(0).to_rust_string_lossy(scope);
+    write_page(scope, |p| Some(p.set_text(k, s)));
+}
+
+fn func_subset(scope: &mut PinScope, args: FunctionCallbackArguments, mut rv: ReturnValue) {
+    let k = option_return!(get_this_key(scope, &args));
+    let p = option_return!(get_page(scope).and_then(|p| unsafe { p.as_ref() }));
+    let subset = result_return!(p.read().map(|p| p.get_subset(k)));
+    let mut r = Vec::new();
+    for k in subset {
+        r.push(option_return!(element_to_object(scope, k)).into());
+    }
+    rv.set(Array::new_with_elements(scope, &r).into());
+}
+
 fn func_attribute(scope: &mut PinScope, args: FunctionCallbackArguments, mut rv: ReturnValue) {
     let n = args.get(0).to_rust_string_lossy(scope);
     if let Ok(n) = AttrName::try_from(&n) {
@@ -181,23 +228,144 @@ fn func_attribute(scope: &mut PinScope, args: FunctionCallbackArguments, mut rv:
     }
 }
 
+fn func_set_attribute(scope: &mut PinScope, args: FunctionCallbackArguments, mut rv: ReturnValue) {
+    let k = option_return!(get_this_key(scope, &args));
+    let n = args.get(0).to_rust_string_lossy(scope);
+    let mut s = to_string_or_empty(scope, args.get(1));
+    match Attribute::from_s(&n, &mut s) {
+        Ok(a) => {
+            let r = write_page(scope, |p| Some(p.set_attribute(k, a)));
+            rv.set_bool(r.unwrap_or(false));
+        }
+        Err(e) => throw(scope, &e.to_string()),
+    }
+}
+
+fn func_remove_attribute(
+    scope: &mut PinScope,
+    args: FunctionCallbackArguments,
+    mut rv: ReturnValue,
+) {
+    let k = option_return!(get_this_key(scope, &args));
+    let n = args.get(0).to_rust_string_lossy(scope);
+    match AttrName::try_from(&n) {
+        Ok(n) => {
+            let r = write_page(scope, |p| p.remove_attribute(k, &n));
+            rv.set_bool(r.is_some());
+        }
+        Err(e) => throw(scope, &e.to_string()),
+    }
+}
+
+//the element is kept in the page until it's dropped, even if it's never put into the tree.
+fn func_create_element(scope: &mut PinScope, args: FunctionCallbackArguments, mut rv: ReturnValue) {
+    let m = args.get(0).to_rust_string_lossy(scope);
+    let m = match Mark::try_from(&m) {
+        Ok(m) => m,
+        Err(e) => return throw(scope, &e.to_string()),
+    };
+    let s = to_string_or_empty(scope, args.get(1));
+    let e = Element::new(m, s, Default::default());
+    let k = option_return!(write_page(scope, |p| Some(p.create(e))));
+    if let Some(o) = element_to_object(scope, k) {
+        rv.set(o.into());
+    }
+}
+
+fn func_append_child(scope: &mut PinScope, args: FunctionCallbackArguments, mut rv: ReturnValue) {
+    let k = option_return!(get_this_key(scope, &args));
+    let c = option_return!(get_value_key(scope, args.get(0)));
+    let r = write_page(scope, |p| Some(p.insert_child(k, c, None)));
+    rv.set_bool(r.unwrap_or(false));
+}
+
+fn func_insert_child(scope: &mut PinScope, args: FunctionCallbackArguments, mut rv: ReturnValue) {
+    let k = option_return!(get_this_key(scope, &args));
+    let c = option_return!(get_value_key(scope, args.get(0)));
+    let n = args.get(1).uint32_value(scope).map(|n| n as usize);
+    let r = write_page(scope, |p| Some(p.insert_child(k, c, n)));
+    rv.set_bool(r.unwrap_or(false));
+}
+
+fn func_remove_child(scope: &mut PinScope, args: FunctionCallbackArguments, mut rv: ReturnValue) {
+    let k = option_return!(get_this_key(scope, &args));
+    let c = option_return!(get_value_key(scope, args.get(0)));
+    let r = write_page(scope, |p| Some(p.remove_child(k, c)));
+    rv.set_bool(r.unwrap_or(false));
+}
+
 fn func_empty(_scope: &mut PinScope, _args: FunctionCallbackArguments, _rv: ReturnValue) {}
 
+#[inline]
+fn throw(scope: &mut PinScope, s: &str) {
+    if let Some(s) = String::new(scope, s) {
+        let e = Exception::error(scope, s);
+        scope.throw_exception(e);
+    }
+}
+
+#[inline]
+fn to_string_or_empty(scope: &mut PinScope, a: Local<'_, Value>) -> std::string::String {
+    if a.is_undefined() {
+        std::string::String::new()
+    } else {
+        a.to_rust_string_lossy(scope)
+    }
+}
+
+#[inline]
+fn get_value_key(scope: &mut PinScope, a: Local<'_, Value>) -> Option<ElementKey> {
+    if !a.is_object() {
+        return None;
+    }
+    let a = a.to_object(scope)?;
+    get_object_key(scope, a)
+}
+
 #[inline]
 fn get_this_key(scope: &mut PinScope, args: &FunctionCallbackArguments) -> Option<ElementKey> {
-    let a = args.this().get_internal_field(scope, 0)?;
+    get_object_key(scope, args.this())
+}
+
+//only the objects made by `element_to_object` hold a key, they are told by the brand.
+//the key of a removed element is rejected.
+fn get_object_key(scope: &mut PinScope, o: Local<'_, Object>) -> Option<ElementKey> {
+    if o.internal_field_count() != 2 {
+        return None;
+    }
+    let b = o.get_internal_field(scope, 1)?;
+    let b = <Local<'_, External>>::try_from(b).ok()?;
+    if b.value() as *const u8 != &ELEMENT_BRAND as *const u8 {
+        return None;
+    }
+    let a = o.get_internal_field(scope, 0)?;
     let a = <Local<'_, External>>::try_from(a).ok()?;
-    return Some(unsafe { *(a.value() as *const ElementKey) });
+    let k = ElementKey::from(KeyData::from_ffi(a.value() as usize as u64));
+    let p = unsafe { get_page(scope)?.as_ref()? };
+    p.read().ok()?.get(k).map(|_| k)
 }
 
 #[inline]
-fn get_page(scope: &mut PinScope) -> Option<*const RwLock<PageContext>> {
+fn get_root_field(scope: &mut PinScope, i: usize) -> Option<*mut core::ffi::c_void> {
     let global = scope.get_current_context().global(scope);
     let p = global.get(scope, String::new(scope, ROOT)?.into())?;
     let p = p.to_object(scope)?;
-    let a = p.get_internal_field(scope, 0)?;
+    let a = p.get_internal_field(scope, i)?;
     let a = <Local<'_, External>>::try_from(a).ok()?;
-    return Some(a.value() as *const RwLock<PageContext>);
+    return Some(a.value());
+}
+
+#[inline]
+fn get_page(scope: &mut PinScope) -> Option<*const RwLock<PageContext>> {
+    get_root_field(scope, 0).map(|a| a as *const RwLock<PageContext>)
+}
+
+#[inline]
+fn write_page<T>(scope: &mut PinScope, f: impl FnOnce(&mut PageContext) -> Option<T>) -> Option<T> {
+    let p = get_page(scope)?;
+    let p = unsafe { p.as_ref()? };
+    let mut p = p.write().ok()?;
+    f(&mut p)
 }
 
 #[inline]
@@ -217,6 +385,9 @@ pub(super) fn run(page: Arc<RwLock<PageContext>>, receiver: Receiver<CommandKind
                 CommandKind::ExecuteScript(s) => {
                     jsrt.execute_script(&s);
                 }
+                CommandKind::Dispatch(s, e) => {
+                    jsrt.dispatch(&s, &e);
+                }
                 CommandKind::Rebuild => {
                     jsrt.build_root();
                 }
@@ -249,4 +420,83 @@ mod tests {
             jsrt.execute_script("sum(1,2)");
         }
     }
+
+    fn runtime(s: &str) -> (JSRuntime, Arc<RwLock<PageContext>>) {
+        let (e, err) = Page::parse(s);
+        assert!(err.is_empty(), "{err}");
+        let cx = (&*e.unwrap()).clone();
+        (JSRuntime::new(cx.clone()), cx)
+    }
+
+    fn width(p: &PageContext, k: ElementKey) -> Option<std::string::String> {
+        p.get(k)?
+            .attribute_get(&AttrName::WIDTH)
+            .map(|a| a.to_string())
+    }
+
+    const PAGE: &str = "<aht><head></head><body><pt>a</pt></body>\
+        <style></style><script></script></aht>";
+
+    #[test]
+    fn edit() {
+        let (mut jsrt, cx) = runtime(PAGE);
+        jsrt.execute_script(
+            "let a = page.body.subset[0];\
+            a.setAttribute('width', '40');\
+            let b = page.createElement('pt', 'b');\
+            page.body.appendChild(b);\
+            b.text = 'c';",
+        );
+        {
+            let p = cx.read().unwrap();
+            let v = p.get_subset(p.body_key());
+            assert_eq!(v.len(), 2);
+            assert_eq!(width(&p, v[0]).as_deref(), Some("40"));
+            assert_eq!(p.get(v[1]).unwrap().text(), "c");
+        }
+
+        jsrt.execute_script(
+            "a.removeAttribute('width');\
+            page.body.removeChild(a);",
+        );
+        let p = cx.read().unwrap();
+        let v = p.get_subset(p.body_key());
+        assert_eq!(v.len(), 1);
+        assert_eq!(p.get(v[0]).unwrap().text(), "c");
+        assert_eq!(width(&p, v[0]), None);
+    }
+
+    #[test]
+    fn reject_root() {
+        let (mut jsrt, cx) = runtime(PAGE);
+        //the root holds the page instead of a key.
+        jsrt.execute_script(
+            "page.body.appendChild(page);\
+            page.body.removeChild(page);\
+            page.body.setAttribute.call(page, 'width', '40');",
+        );
+        let p = cx.read().unwrap();
+        assert_eq!(p.get_subset(p.body_key()).len(), 1);
+        assert_eq!(width(&p, p.body_key()), None);
+    }
+
+    #[test]
+    fn removed_element() {
+        let (mut jsrt, cx) = runtime(PAGE);
+        //"b" may take the slot of "a", but the object of "a" is stale.
+        jsrt.execute_script(
+            "let a = page.body.subset[0];\
+            page.body.removeChild(a);\
+            let b = page.createElement('pt', 'b');\
+            page.body.appendChild(b);\
+            a.text = 'x';\
+            a.setAttribute('width', '40');\
+            page.body.appendChild(a);",
+        );
+        let p = cx.read().unwrap();
+        let v = p.get_subset(p.body_key());
+        assert_eq!(v.len(), 1);
+        assert_eq!(p.get(v[0]).unwrap().text(), "b");
+        assert_eq!(width(&p, v[0]), None);
+    }
 }
@@ -9,6 +9,7 @@ use std::sync::{Arc, RwLock};
 
 enum CommandKind {
     ExecuteScript(String),
+    Dispatch(String, Event),
     Rebuild,
     Shutdown,
 }
@@ -45,6 +46,10 @@ impl ScriptRuntime {
         let _ = self.sender.send(CommandKind::ExecuteScript(s));
     }
 
+    pub(crate) fn dispatch(&mut self, s: String, e: Event) {
+        let _ = self.sender.send(CommandKind::Dispatch(s, e));
+    }
+
     pub(crate) fn rebuild(&mut self) {
         let _ = self.sender.send(CommandKind::Rebuild);
     }
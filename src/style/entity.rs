@@ -1,6 +1,6 @@
 use super::*;
 use crate::utils::ascii::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::mem::take;
 use std::str::FromStr;
 
@@ -10,6 +10,12 @@ pub(super) struct StyleSheet {
     meta_rules: MetaRules,
     #[getset(get = "pub(crate)", get_mut = "pub(crate)")]
     style_rules: StyleRules,
+    ///Variables declared in `@vars` block, which are visible to all elements.
+    #[getset(get = "pub(crate)", get_mut = "pub(crate)")]
+    vars: HashMap<String, String>,
+    ///Blocks declared as `@keyframes name`, the names are in lowercase.
+    #[getset(get = "pub(crate)", get_mut = "pub(crate)")]
+    keyframes: HashMap<String, Keyframes>,
 }
 
 impl StyleSheet {
@@ -17,7 +23,70 @@ impl StyleSheet {
         Self {
             meta_rules,
             style_rules,
+            vars: Default::default(),
+            keyframes: Default::default(),
+        }
+    }
+}
+
+///Represents the stops of a `@keyframes` block, each one has an offset between 0 and 1.
+#[derive(Debug, Default)]
+#[repr(transparent)]
+pub(crate) struct Keyframes(Vec<(f32, Vec<Attribute>)>);
+
+deref!(Keyframes, Vec<(f32, Vec<Attribute>)>, 0);
+
+impl Keyframes {
+    ///Returns the values at `t` between 0 and 1, an attribute is interpolated between the nearest stops which declare it.
+    pub(crate) fn sample(&self, t: f32) -> Vec<Attribute> {
+        let mut names = Vec::new();
+        for a in self.0.iter().flat_map(|o| o.1.iter()) {
+            let n = a.name();
+            if !names.contains(&n) {
+                names.push(n);
+            }
+        }
+        let mut r = Vec::with_capacity(names.len());
+        for n in names {
+            let mut from = None;
+            let mut to = None;
+            for (o, v) in &self.0 {
+                let a = if let Some(a) = v.iter().find(|a| a.name() == n) {
+                    a
+                } else {
+                    continue;
+                };
+                if *o <= t {
+                    from = Some((*o, a));
+                }
+                if *o >= t && to.is_none() {
+                    to = Some((*o, a));
+                }
+            }
+            let a = match (from, to) {
+                (Some((x, a)), Some((y, b))) if y > x => a
+                    .interpolate(b, (t - x) / (y - x))
+                    .unwrap_or_else(|| a.clone()),
+                (Some((_, a)), _) | (None, Some((_, a))) => a.clone(),
+                _ => continue,
+            };
+            r.push(a);
         }
+        r
+    }
+}
+
+//converts `from`, `to` or a percentage to an offset between 0 and 1.
+fn to_offset(s: &str) -> Result<f32> {
+    let s = s.trim();
+    let n = match s {
+        FROM => Some(0.0),
+        TO => Some(100.0),
+        _ => to_f32(s.strip_suffix(PER_CENT).unwrap_or(s)).ok(),
+    };
+    match n {
+        Some(n) if (0.0..=100.0).contains(&n) => Ok(n / 100.0),
+        _ => Err((ErrorKind::Style, format!("invalid keyframe: {s}")).into()),
     }
 }
 
@@ -27,21 +96,118 @@ pub(super) struct MetaRules(Vec<MetaRule>);
 
 deref!(MetaRules, Vec<MetaRule>, 0);
 
+///A conditional block, style rules in it are applied when all conditions match the viewport.
 #[derive(Debug, Default, Getters, MutGetters)]
 pub(super) struct MetaRule {
     #[getset(get = "pub(crate)", get_mut = "pub(crate)")]
     key: String,
+    //None if the condition is invalid.
     #[getset(get = "pub(crate)", get_mut = "pub(crate)")]
-    attribute: HashMap<AttrName, Attribute>,
+    condition: Option<Vec<Condition>>,
 }
 
 impl MetaRule {
-    fn new(key: String) -> Self {
-        Self {
-            key,
-            attribute: Default::default(),
+    fn new(key: String, condition: Option<Vec<Condition>>) -> Self {
+        Self { key, condition }
+    }
+
+    pub(super) fn is_match(&self, v: &Viewport) -> bool {
+        self.condition
+            .as_ref()
+            .is_some_and(|o| o.iter().all(|c| c.is_match(v)))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum Compare {
+    Lt,
+    Le,
+    Eq,
+    Ge,
+    Gt,
+}
+
+impl Compare {
+    fn test(&self, a: f32, b: f32) -> bool {
+        match self {
+            Self::Lt => a < b,
+            Self::Le => a <= b,
+            Self::Eq => a == b,
+            Self::Ge => a >= b,
+            Self::Gt => a > b,
+        }
+    }
+}
+
+impl FromStr for Compare {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "<" => Self::Lt,
+            "<=" => Self::Le,
+            "=" => Self::Eq,
+            ">=" => Self::Ge,
+            ">" => Self::Gt,
+            _ => return Err((ErrorKind::Style, format!("unknown comparison: {s:?}")).into()),
+        })
+    }
+}
+
+///Represents a condition of meta rule, such as `width>800` or `orientation=portrait`.
+#[derive(Debug, PartialEq)]
+pub(super) enum Condition {
+    Width(Compare, f32),
+    Height(Compare, f32),
+    Scale(Compare, f32),
+    Orientation(bool),
+}
+
+impl Condition {
+    fn is_match(&self, v: &Viewport) -> bool {
+        match self {
+            Self::Width(c, n) => c.test(v.width(), *n),
+            Self::Height(c, n) => c.test(v.height(), *n),
+            Self::Scale(c, n) => c.test(v.scale(), *n),
+            Self::Orientation(o) => (v.width() >= v.height()) == *o,
         }
     }
+
+    fn parse(s: &str) -> Result<Vec<Self>> {
+        s.split(COMMA).map(Self::from_str).collect()
+    }
+}
+
+impl FromStr for Condition {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let i = option_return!(
+            s.find([LT, GT, EQUAL]),
+            Err((ErrorKind::Style, format!("no comparison: {s:?}")).into())
+        );
+        let (k, v) = s.split_at(i);
+        let j = v
+            .find(|c| c != LT && c != GT && c != EQUAL)
+            .unwrap_or(v.len());
+        let (c, v) = v.split_at(j);
+        let c = Compare::from_str(c)?;
+        let number = || {
+            v.parse::<f32>()
+                .map_err(|e| Error::from((ErrorKind::Style, format!("{k}: {v:?} {e}"))))
+        };
+        Ok(match k {
+            "width" => Self::Width(c, number()?),
+            "height" => Self::Height(c, number()?),
+            "scale" => Self::Scale(c, number()?),
+            "orientation" if c == Compare::Eq => match v {
+                "landscape" => Self::Orientation(true),
+                "portrait" => Self::Orientation(false),
+                _ => return Err((ErrorKind::Style, format!("{k}: {v:?}")).into()),
+            },
+            _ => return Err((ErrorKind::Style, format!("unknown condition: {s:?}")).into()),
+        })
+    }
 }
 
 #[derive(Debug, Default)]
@@ -62,6 +228,26 @@ pub(super) struct StyleRule {
     key: SelectorHolder,
     #[getset(get = "pub(crate)", get_mut = "pub(crate)")]
     attribute: HashMap<AttrName, Attribute>,
+    #[getset(get = "pub(crate)", get_mut = "pub(crate)")]
+    important: HashSet<AttrName>,
+    ///Values referring variables, which are parsed when applied.
+    #[getset(get = "pub(crate)", get_mut = "pub(crate)")]
+    pending: HashMap<AttrName, String>,
+    ///Variables declared as `--name`, which are visible to matched elements and their subsets.
+    #[getset(get = "pub(crate)", get_mut = "pub(crate)")]
+    vars: HashMap<String, String>,
+    ///Index of the meta rule it belongs to.
+    #[getset(get_copy = "pub(crate)", set = "pub(crate)")]
+    meta: Option<usize>,
+    ///Position of the first selector in source.
+    #[getset(get_copy = "pub(crate)", set = "pub(crate)")]
+    position: (usize, usize),
+    ///Positions of declarations in source.
+    #[getset(get = "pub(crate)", get_mut = "pub(crate)")]
+    positions: HashMap<AttrName, (usize, usize)>,
+    ///Declarations replaced by a later one in the same block.
+    #[getset(get = "pub(crate)", get_mut = "pub(crate)")]
+    duplicates: Vec<(AttrName, (usize, usize))>,
 }
 
 impl StyleRule {
@@ -69,6 +255,32 @@ impl StyleRule {
         Self {
             key,
             attribute: Default::default(),
+            important: Default::default(),
+            pending: Default::default(),
+            vars: Default::default(),
+            meta: None,
+            position: (0, 0),
+            positions: Default::default(),
+            duplicates: Default::default(),
+        }
+    }
+
+    pub(super) fn into_key(self) -> SelectorHolder {
+        self.key
+    }
+
+    //a rule of selector group shares the block with others, duplicates are kept by the last one.
+    fn with_key(&self, key: SelectorHolder, position: (usize, usize)) -> Self {
+        Self {
+            key,
+            attribute: self.attribute.clone(),
+            important: self.important.clone(),
+            pending: self.pending.clone(),
+            vars: self.vars.clone(),
+            meta: self.meta,
+            position,
+            positions: self.positions.clone(),
+            duplicates: Default::default(),
         }
     }
 }
@@ -78,14 +290,95 @@ pub(crate) enum Selector {
     Mark(Mark),
     Attribute(AttrName, AttrPattern),
     AttrName(AttrName),
+    State(State),
+    ///The position `an+b` in the subset of upper, counted from the end if `last` is true.
+    Nth {
+        a: i32,
+        b: i32,
+        last: bool,
+    },
+    Not(SelectorGroup),
     Any,
 }
 
+impl Selector {
+    //matching depends on the tree rather than the element only.
+    fn is_structural(&self) -> bool {
+        matches!(self, Self::Nth { .. } | Self::Not(_))
+    }
+
+    fn is_match(&self, k: ElementKey, eh: &ElementHolder) -> bool {
+        let e = option_return!(eh.get(k), false);
+        match self {
+            Self::Mark(o) => MarkEq::new(o).satisfy(e),
+            Self::Attribute(o, p) => AttrMatch::new(o, p).satisfy(e),
+            Self::AttrName(o) => AttrExists::new(o).satisfy(e),
+            Self::State(o) => StateMatch::new(o).satisfy(e),
+            &Self::Nth { a, b, last } => {
+                let i = match (*e.upper()).and_then(|o| eh.get(o)) {
+                    Some(o) => {
+                        let i = option_return!(o.subset_element_index(k), false);
+                        if last { o.subset().len() - i } else { i + 1 }
+                    }
+                    None => 1,
+                };
+                let n = i as i32 - b;
+                if a == 0 {
+                    n == 0
+                } else {
+                    n % a == 0 && n / a >= 0
+                }
+            }
+            Self::Not(o) => !o.iter().any(|o| o.is_match(k, eh)),
+            Self::Any => true,
+        }
+    }
+}
+
+impl std::fmt::Display for Selector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Mark(o) => write!(f, "{}", o.as_str()),
+            Self::Attribute(AttrName::CLASS, AttrPattern::Word(v)) => write!(f, ".{v}"),
+            Self::Attribute(n, p) => {
+                let (o, v) = match p {
+                    AttrPattern::Eq(v) => ("", v),
+                    AttrPattern::Contain(v) => ("*", v),
+                    AttrPattern::Word(v) => ("~", v),
+                    AttrPattern::StartsWith(v) => ("^", v),
+                    AttrPattern::EndsWith(v) => ("$", v),
+                };
+                write!(f, ".{}{o}={v}", n.as_str())
+            }
+            Self::AttrName(n) => write!(f, ".{}=", n.as_str()),
+            Self::State(o) => write!(f, ":{o}"),
+            Self::Nth {
+                a: 0,
+                b: 1,
+                last: false,
+            } => write!(f, ":{FIRST}"),
+            Self::Nth {
+                a: 0,
+                b: 1,
+                last: true,
+            } => write!(f, ":{LAST}"),
+            Self::Nth { a, b, last } => {
+                let n = if *last { NTH_LAST_CHILD } else { NTH_CHILD };
+                write!(f, ":{n}({a}n{b:+})")
+            }
+            Self::Not(o) => write!(f, ":{NOT}({o})"),
+            Self::Any => write!(f, "*"),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub(crate) enum Combiner {
     Descendant(usize),
     NextSibling(usize),
     PrecedingSibling(usize),
+    //the same element as the preceding selector.
+    Same,
 }
 
 impl Combiner {
@@ -109,7 +402,10 @@ impl Combiner {
                         f.elements(ks, eh, &mut r);
                     }
                 }
-                Selector::Any => {
+                Selector::State(o) => {
+                    FindDescendant::new(StateMatch::new(o), n).elements(ks, eh, &mut r);
+                }
+                Selector::Any | Selector::Nth { .. } | Selector::Not(_) => {
                     FindDescendant::new(AnyMatch, n).elements(ks, eh, &mut r);
                 }
             },
@@ -130,7 +426,10 @@ impl Combiner {
                         f.elements(ks, eh, &mut r);
                     }
                 }
-                Selector::Any => {
+                Selector::State(o) => {
+                    FindNextSibling::new(StateMatch::new(o), n).elements(ks, eh, &mut r);
+                }
+                Selector::Any | Selector::Nth { .. } | Selector::Not(_) => {
                     FindNextSibling::new(AnyMatch, n).elements(ks, eh, &mut r);
                 }
             },
@@ -151,10 +450,20 @@ impl Combiner {
                         f.elements(ks, eh, &mut r);
                     }
                 }
-                Selector::Any => {
+                Selector::State(o) => {
+                    FindPrecedingSibling::new(StateMatch::new(o), n).elements(ks, eh, &mut r);
+                }
+                Selector::Any | Selector::Nth { .. } | Selector::Not(_) => {
                     FindPrecedingSibling::new(AnyMatch, n).elements(ks, eh, &mut r);
                 }
             },
+            Self::Same => {
+                r.extend(ks.iter().filter(|&&k| s.is_match(k, eh)));
+                return r;
+            }
+        }
+        if s.is_structural() {
+            r.retain(|&k| s.is_match(k, eh));
         }
         r
     }
@@ -172,6 +481,9 @@ impl FromStr for Combiner {
                 QUESTION => {
                     return Ok(Self::Descendant(n));
                 }
+                GT if n == 0 => {
+                    return Ok(Self::Descendant(1));
+                }
                 PLUS => {
                     return Ok(Self::NextSibling(n));
                 }
@@ -185,13 +497,81 @@ impl FromStr for Combiner {
     }
 }
 
+impl std::fmt::Display for Combiner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Descendant(0) => write!(f, " "),
+            Self::Descendant(1) => write!(f, " > "),
+            Self::Descendant(n) => write!(f, " ?{n} "),
+            Self::NextSibling(0) => write!(f, " + "),
+            Self::NextSibling(n) => write!(f, " +{n} "),
+            Self::PrecedingSibling(0) => write!(f, " - "),
+            Self::PrecedingSibling(n) => write!(f, " -{n} "),
+            Self::Same => Ok(()),
+        }
+    }
+}
+
 #[derive(Debug, Default)]
 #[repr(transparent)]
 pub(crate) struct SelectorHolder(Vec<(Combiner, Selector)>);
 
 deref!(SelectorHolder, Vec<(Combiner, Selector)>, 0);
 
+impl std::fmt::Display for SelectorHolder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (c, s)) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, "{c}")?;
+            }
+            write!(f, "{s}")?;
+        }
+        Ok(())
+    }
+}
+
 impl SelectorHolder {
+    ///Returns the number of id, attribute and mark selectors.
+    pub(crate) fn specificity(&self) -> (usize, usize, usize) {
+        let mut r = (0, 0, 0);
+        for (_, s) in self.iter() {
+            match s {
+                Selector::Attribute(AttrName::ID, _) => r.0 += 1,
+                Selector::Attribute(..)
+                | Selector::AttrName(_)
+                | Selector::State(_)
+                | Selector::Nth { .. } => r.1 += 1,
+                Selector::Mark(_) => r.2 += 1,
+                Selector::Not(o) => {
+                    let o = o.iter().map(|o| o.specificity()).max().unwrap_or_default();
+                    r = (r.0 + o.0, r.1 + o.1, r.2 + o.2);
+                }
+                Selector::Any => {}
+            }
+        }
+        r
+    }
+
+    ///Returns true if it matches a state changed by actions.
+    pub(crate) fn is_interactive(&self) -> bool {
+        self.iter().any(|(_, s)| match s {
+            Selector::State(o) => o.is_interactive(),
+            Selector::Not(o) => o.iter().any(|o| o.is_interactive()),
+            _ => false,
+        })
+    }
+
+    ///Returns true if all selectors are on the same element, such as `pt.a:hover`.
+    pub(crate) fn is_compound(&self) -> bool {
+        self.iter()
+            .skip(1)
+            .all(|(c, _)| matches!(c, Combiner::Same))
+    }
+
+    fn is_match(&self, k: ElementKey, eh: &ElementHolder) -> bool {
+        self.iter().all(|(_, s)| s.is_match(k, eh))
+    }
+
     pub(crate) fn find(&self, eh: &ElementHolder) -> Vec<ElementKey> {
         let mut v = Vec::new();
         let mut i = self.iter();
@@ -213,10 +593,16 @@ impl SelectorHolder {
                         f.all_with_first_root(eh, &mut v);
                     }
                 }
-                Selector::Any => {
+                Selector::State(o) => {
+                    FindDescendant::new(StateMatch::new(o), 0).all_with_first_root(eh, &mut v);
+                }
+                Selector::Any | Selector::Nth { .. } | Selector::Not(_) => {
                     FindDescendant::new(AnyMatch, 0).all_with_first_root(eh, &mut v);
                 }
             }
+            if s.is_structural() {
+                v.retain(|&k| s.is_match(k, eh));
+            }
         }
         for (c, s) in i {
             v = c.find(s, &v, eh);
@@ -225,85 +611,504 @@ impl SelectorHolder {
     }
 }
 
-#[derive(Debug)]
-enum RuleKind {
-    MetaRule,
-    StyleRule,
+///Represents selectors separated by comma, which match elements matched by any of them.
+#[derive(Debug, Default)]
+#[repr(transparent)]
+pub(crate) struct SelectorGroup(Vec<SelectorHolder>);
+
+deref!(SelectorGroup, Vec<SelectorHolder>, 0);
+
+impl std::fmt::Display for SelectorGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, o) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{o}")?;
+        }
+        Ok(())
+    }
+}
+
+impl SelectorGroup {
+    pub(crate) fn new(v: Vec<SelectorHolder>) -> Self {
+        Self(v)
+    }
+
+    pub(crate) fn find(&self, eh: &ElementHolder) -> Vec<ElementKey> {
+        let mut v = Vec::new();
+        for o in self.iter() {
+            for k in o.find(eh) {
+                if !v.contains(&k) {
+                    v.push(k);
+                }
+            }
+        }
+        v
+    }
 }
 
-impl Default for RuleKind {
-    fn default() -> Self {
-        Self::StyleRule
+const FIRST: &str = "first";
+const LAST: &str = "last";
+const NTH_CHILD: &str = "nth-child";
+const NTH_LAST_CHILD: &str = "nth-last-child";
+const NOT: &str = "not";
+const ODD: &str = "odd";
+const EVEN: &str = "even";
+
+///Parses `an+b` of `:nth-child`, "odd" and "even" are accepted.
+fn to_nth(s: &str) -> Result<(i32, i32)> {
+    let s: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+    let to_i32 = |s: &str| -> Result<i32> {
+        i32::from_str(s).map_err(|_| (ErrorKind::Style, format!("invalid nth: {s:?}")).into())
+    };
+    match s.as_str() {
+        ODD => Ok((2, 1)),
+        EVEN => Ok((2, 0)),
+        _ => match s.split_once('n') {
+            Some((a, b)) => {
+                let a = match a {
+                    "" | "+" => 1,
+                    "-" => -1,
+                    _ => to_i32(a)?,
+                };
+                let b = if b.is_empty() { 0 } else { to_i32(b)? };
+                Ok((a, b))
+            }
+            None => to_i32(&s).map(|b| (0, b)),
+        },
     }
 }
 
-impl RuleKind {}
+//parses a selector written after colon, such as `hover` and `nth-child(2n+1)`.
+fn to_pseudo(s: &str) -> Result<Selector> {
+    let (name, arg) = match s
+        .strip_suffix(RIGHT_PARENTHESIS)
+        .and_then(|o| o.split_once(LEFT_PARENTHESIS))
+    {
+        Some((n, a)) => (n, Some(a)),
+        None => (s, None),
+    };
+    let nth = |s, last| to_nth(s).map(|(a, b)| Selector::Nth { a, b, last });
+    match (name, arg) {
+        (FIRST, None) => Ok(Selector::Nth {
+            a: 0,
+            b: 1,
+            last: false,
+        }),
+        (LAST, None) => Ok(Selector::Nth {
+            a: 0,
+            b: 1,
+            last: true,
+        }),
+        (NTH_CHILD, Some(o)) => nth(o, false),
+        (NTH_LAST_CHILD, Some(o)) => nth(o, true),
+        (NOT, Some(o)) => {
+            let g = parse_selector(o)?;
+            if g.iter().all(|o| o.is_compound()) {
+                Ok(Selector::Not(g))
+            } else {
+                Err((ErrorKind::Style, format!("not a compound selector: {o}")).into())
+            }
+        }
+        (_, None) => State::from_str(name).map(Selector::State),
+        _ => Err((ErrorKind::Style, format!("invalid selector: :{s}")).into()),
+    }
+}
+
+const IMPORTANT: &str = "!important";
+const VAR: &str = "var(";
+pub(super) const VARS: &str = "vars";
+pub(super) const KEYFRAMES: &str = "keyframes";
+const FROM: &str = "from";
+const TO: &str = "to";
+
+///Returns the name of variable without leading `--`.
+pub(super) fn var_name(s: &str) -> &str {
+    s.trim().trim_start_matches(HYPHEN)
+}
+
+///Replaces each `var(name)` in a value with `f(name)`.
+pub(super) fn substitute(s: &str, mut f: impl FnMut(&str) -> Result<String>) -> Result<String> {
+    let mut r = String::new();
+    let mut t = s;
+    while let Some(i) = t.find(VAR) {
+        r.push_str(&t[..i]);
+        let o = &t[i + VAR.len()..];
+        let j = option_return!(
+            o.find(RIGHT_PARENTHESIS),
+            Err((ErrorKind::Style, format!("unclosed variable: {s:?}")).into())
+        );
+        r.push_str(&f(var_name(&o[..j]))?);
+        t = &o[j + 1..];
+    }
+    r.push_str(t);
+    Ok(r)
+}
 
 #[derive(Debug, Default)]
 pub(super) struct StyleSheetBuilder {
-    a: MetaRule,
     b: StyleRule,
-    k: RuleKind,
+    m: Option<usize>,
+    //selectors before comma, with their positions.
+    group: Vec<(SelectorHolder, (usize, usize))>,
+    //in `@vars` block.
+    vars: bool,
+    //variable and its references, keyed by the scope declaring it, which is None for `@vars` block
+    //or the index of the rule.
+    var_graph: HashMap<(Option<usize>, String), (HashSet<String>, (usize, usize), usize)>,
+    var_refs: Vec<(String, (usize, usize), usize)>,
+    //in `@keyframes` block, with its name.
+    keyframes: Option<(String, Keyframes)>,
+    //offsets of the current keyframe, empty if it's invalid.
+    stops: Vec<f32>,
+    frame: Vec<Attribute>,
     rst: StyleSheet,
     error: ErrorHolder,
 }
 
 impl StyleSheetBuilder {
-    pub(super) fn take(self) -> (StyleSheet, ErrorHolder) {
+    pub(super) fn take(mut self) -> (StyleSheet, ErrorHolder) {
+        self.check_vars();
         (self.rst, self.error)
     }
 
+    //records the variables referred by a value, returns None if it's illegal.
+    fn refer(&mut self, v: &str, p: (usize, usize), n: usize) -> Option<HashSet<String>> {
+        let mut r = HashSet::new();
+        let o = substitute(v, |k| {
+            r.insert(k.to_string());
+            Ok(String::new())
+        });
+        if let Err(e) = o {
+            self.error_at(e, ErrorCode::InvalidValue, p, n);
+            return None;
+        }
+        for k in &r {
+            self.var_refs.push((k.clone(), p, n));
+        }
+        Some(r)
+    }
+
+    fn declare(&mut self, k: &str, v: &str, p: (usize, usize)) -> bool {
+        let n = k.chars().count();
+        let r = option_return!(self.refer(v, p, n), false);
+        let scope = (!self.vars).then(|| self.rst.style_rules().len());
+        let o = self
+            .var_graph
+            .entry((scope, var_name(k).to_string()))
+            .or_insert_with(|| (Default::default(), p, n));
+        o.0.extend(r);
+        true
+    }
+
+    //reports undefined variables and cyclic references.
+    fn check_vars(&mut self) {
+        let names: HashSet<_> = self.var_graph.keys().map(|o| o.1.clone()).collect();
+        for (k, p, n) in take(&mut self.var_refs) {
+            if !names.contains(&k) {
+                let e: Error = (ErrorKind::Style, format!("undefined variable: {k}")).into();
+                self.error_at(e, ErrorCode::UndefinedVariable, p, n);
+            }
+        }
+        let g = take(&mut self.var_graph);
+        //a reference is followed into the same rule or `@vars` block only, which rules
+        //declare it on the element are known when it's applied.
+        let edges: HashMap<_, Vec<_>> = g
+            .iter()
+            .map(|(k, o)| {
+                let v = o.0.iter().filter_map(|a| {
+                    let same = (k.0, a.clone());
+                    let global = (None, a.clone());
+                    g.get_key_value(&same)
+                        .or_else(|| g.get_key_value(&global))
+                        .map(|o| o.0)
+                });
+                (k, v.collect())
+            })
+            .collect();
+        let mut v: Vec<_> = g.iter().collect();
+        v.sort_by_key(|o| (o.1).1);
+        //false while a variable is on the path, true after all its references are visited,
+        //a reference to one on the path closes a cycle through the ones after it.
+        let mut visited: HashMap<_, bool> = HashMap::new();
+        let mut cyclic = HashSet::new();
+        for &(k, _) in &v {
+            if visited.contains_key(k) {
+                continue;
+            }
+            visited.insert(k, false);
+            let mut path = vec![(k, edges[k].iter())];
+            while let Some((o, next)) = path.last_mut() {
+                let o = *o;
+                match next.next() {
+                    Some(&a) => match visited.get(a) {
+                        Some(false) => {
+                            if let Some(i) = path.iter().position(|p| p.0 == a) {
+                                cyclic.extend(path[i..].iter().map(|p| p.0));
+                            }
+                        }
+                        Some(true) => {}
+                        None => {
+                            visited.insert(a, false);
+                            path.push((a, edges[a].iter()));
+                        }
+                    },
+                    None => {
+                        visited.insert(o, true);
+                        path.pop();
+                    }
+                }
+            }
+        }
+        for (k, (_, p, n)) in v {
+            if cyclic.contains(k) {
+                let e: Error = (ErrorKind::Style, format!("cyclic variable: {}", k.1)).into();
+                self.error_at(e, ErrorCode::CyclicVariable, *p, *n);
+            }
+        }
+    }
+
+    fn push_key(&mut self, k: (Combiner, Selector), p: (usize, usize)) {
+        if self.b.key().is_empty() {
+            self.b.set_position(p);
+        }
+        self.b.key_mut().push(k);
+    }
+
+    fn frame_attribute(&mut self, k: String, mut v: String, p: (usize, usize)) {
+        if self.stops.is_empty() {
+            return;
+        }
+        let n = k.chars().count();
+        let name = result_return!(AttrName::from_str(&k).map_err(|e| self.error_at(
+            e,
+            ErrorCode::InvalidAttribute,
+            p,
+            n
+        )));
+        if !name.is_animatable() {
+            let e: Error = (ErrorKind::Style, format!("not animatable: {k}")).into();
+            return self.error_at(e, ErrorCode::InvalidKeyframe, p, n);
+        }
+        let a = result_return!(Attribute::from(&name, &mut v).map_err(|e| {
+            let e: Error = (ErrorKind::Style, format!("{k}: {v:?} {}", e.message())).into();
+            self.error_at(e, ErrorCode::InvalidValue, p, n)
+        }));
+        self.frame.retain(|o| o.name() != name);
+        self.frame.push(a);
+    }
+
+    fn error_at(&mut self, e: Error, code: ErrorCode, p: (usize, usize), n: usize) {
+        self.error.push(e.with_code(code).with_span(p, n));
+    }
+
     pub(super) fn build(s: &str) -> (StyleSheet, ErrorHolder) {
         Parser::new(Self::default()).parse_str(s).take()
     }
 }
 
 impl Output for StyleSheetBuilder {
-    fn meta(&mut self, s: String) {
-        self.a.key_mut().push_str(&s);
-        self.k = RuleKind::MetaRule;
+    fn meta(&mut self, s: String, p: (usize, usize)) {
+        if s == VARS {
+            self.vars = true;
+            return;
+        }
+        let n = s.chars().count();
+        let mut i = s.splitn(2, SPACE);
+        if i.next() == Some(KEYFRAMES) {
+            let name = i.next().unwrap_or_default().trim();
+            if name.is_empty() {
+                let e: Error = (ErrorKind::Style, "no keyframes name").into();
+                self.error_at(e, ErrorCode::InvalidKeyframe, p, n);
+            }
+            self.keyframes
+                .replace((name.to_string(), Default::default()));
+            return;
+        }
+        //an empty one is an illegal condition reported by parser.
+        let c = if s.is_empty() {
+            None
+        } else {
+            Condition::parse(&s)
+                .map_err(|e| self.error_at(e, ErrorCode::InvalidCondition, p, n))
+                .ok()
+        };
+        self.m = Some(self.rst.meta_rules().len());
+        self.rst.meta_rules_mut().push(MetaRule::new(s, c));
     }
 
-    fn mark_selector(&mut self, c: String, s: String) {
-        let c = result_return!(Combiner::from_str(&c).map_err(|e| self.error(e)));
-        let m = result_return!(Mark::try_from(&s).map_err(|e| self.error(e)));
-        let k = (c, Selector::Mark(m));
-        self.b.key_mut().push(k);
+    fn end_meta(&mut self) {
+        if let Some((k, mut o)) = self.keyframes.take() {
+            o.sort_by(|a, b| a.0.total_cmp(&b.0));
+            if !k.is_empty() {
+                self.rst.keyframes_mut().insert(k, o);
+            }
+        }
+        self.m = None;
     }
 
-    fn attribute_selector(&mut self, c: String, k: String, v: String) {
-        let c = result_return!(Combiner::from_str(&c).map_err(|e| self.error(e)));
+    fn keyframe(&mut self, s: String, p: (usize, usize)) {
+        let n = s.chars().count();
+        let o: Result<Vec<_>> = s.split(COMMA).map(to_offset).collect();
+        self.stops = o
+            .map_err(|e| self.error_at(e, ErrorCode::InvalidKeyframe, p, n))
+            .unwrap_or_default();
+    }
+
+    fn mark_selector(&mut self, c: String, s: String, p: (usize, usize)) {
+        let n = s.chars().count();
+        let c = result_return!(Combiner::from_str(&c).map_err(|e| self.error_at(
+            e,
+            ErrorCode::InvalidCombiner,
+            p,
+            n
+        )));
+        let m = result_return!(Mark::try_from(&s).map_err(|e| self.error_at(
+            e,
+            ErrorCode::InvalidMark,
+            p,
+            n
+        )));
+        self.push_key((c, Selector::Mark(m)), p);
+    }
+
+    fn attribute_selector(
+        &mut self,
+        c: String,
+        k: String,
+        o: String,
+        v: String,
+        p: (usize, usize),
+    ) {
+        let n = k.chars().count() + o.chars().count() + v.chars().count() + 1;
+        let c = result_return!(Combiner::from_str(&c).map_err(|e| self.error_at(
+            e,
+            ErrorCode::InvalidCombiner,
+            p,
+            n
+        )));
         let a = if k.is_empty() {
             AttrName::CLASS
         } else {
-            result_return!(AttrName::from_str(&k).map_err(|e| self.error(e)))
+            result_return!(AttrName::from_str(&k).map_err(|e| self.error_at(
+                e,
+                ErrorCode::InvalidAttribute,
+                p,
+                n
+            )))
         };
-        let k = (c, Selector::Attribute(a, AttrPattern::Contain(v)));
-        self.b.key_mut().push(k);
+        let v = match o.chars().next() {
+            None => AttrPattern::Word(v),
+            Some(EQUAL) => AttrPattern::Eq(v),
+            Some(ASTERISK) => AttrPattern::Contain(v),
+            Some(TILDE) => AttrPattern::Word(v),
+            Some(CIRCUMFLEX_ACCENT) => AttrPattern::StartsWith(v),
+            Some(DOLLAR_SIGN) => AttrPattern::EndsWith(v),
+            Some(_) => {
+                let e: Error = (ErrorKind::Style, format!("invalid operator: {o}")).into();
+                return self.error_at(e, ErrorCode::InvalidAttribute, p, n);
+            }
+        };
+        self.push_key((c, Selector::Attribute(a, v)), p);
     }
 
-    fn attribute(&mut self, k: String, mut v: String) {
-        let a = result_return!(Attribute::from_s(&k, &mut v).map_err(|e| self.error(e)));
-        match self.k {
-            RuleKind::MetaRule => {
-                self.a.attribute_mut().insert(a.name(), a);
-            }
-            RuleKind::StyleRule => {
-                self.b.attribute_mut().insert(a.name(), a);
+    fn state_selector(&mut self, c: Option<String>, s: String, p: (usize, usize)) {
+        let n = s.chars().count() + 1;
+        let c = match c {
+            Some(c) => result_return!(Combiner::from_str(&c).map_err(|e| self.error_at(
+                e,
+                ErrorCode::InvalidCombiner,
+                p,
+                n
+            ))),
+            None => Combiner::Same,
+        };
+        let o = result_return!(to_pseudo(&s).map_err(|e| self.error_at(
+            e,
+            ErrorCode::InvalidState,
+            p,
+            n
+        )));
+        self.push_key((c, o), p);
+    }
+
+    fn attribute(&mut self, k: String, mut v: String, p: (usize, usize)) {
+        if self.keyframes.is_some() {
+            return self.frame_attribute(k, v, p);
+        }
+        if self.vars || k.starts_with(HYPHEN) {
+            if self.declare(&k, &v, p) {
+                let k = var_name(&k).to_string();
+                if self.vars {
+                    self.rst.vars_mut().insert(k, v);
+                } else {
+                    self.b.vars_mut().insert(k, v);
+                }
             }
+            return;
+        }
+        let important = if let Some(o) = v.trim_end().strip_suffix(IMPORTANT) {
+            v = o.trim_end().to_string();
+            true
+        } else {
+            false
+        };
+        let n = k.chars().count();
+        let name = result_return!(AttrName::from_str(&k).map_err(|e| self.error_at(
+            e,
+            ErrorCode::InvalidAttribute,
+            p,
+            n
+        )));
+        if v.contains(VAR) {
+            option_return!(self.refer(&v, p, n));
+            self.b.attribute_mut().remove(&name);
+            self.b.pending_mut().insert(name.clone(), v);
+        } else {
+            let a = result_return!(Attribute::from(&name, &mut v).map_err(|e| {
+                let e: Error = (ErrorKind::Style, format!("{k}: {v:?} {}", e.message())).into();
+                self.error_at(e, ErrorCode::InvalidValue, p, n)
+            }));
+            self.b.pending_mut().remove(&name);
+            self.b.attribute_mut().insert(name.clone(), a);
+        }
+        if self.b.positions_mut().insert(name.clone(), p).is_some() {
+            self.b.duplicates_mut().push((name.clone(), p));
+        }
+        if important {
+            self.b.important_mut().insert(name);
+        } else {
+            self.b.important_mut().remove(&name);
         }
     }
 
     fn end_block(&mut self) {
-        match self.k {
-            RuleKind::MetaRule => {
-                self.rst.meta_rules_mut().push(take(&mut self.a));
-            }
-            RuleKind::StyleRule => {
-                self.rst.style_rules_mut().push(take(&mut self.b));
+        if self.vars {
+            self.vars = false;
+            return;
+        }
+        if let Some((_, o)) = self.keyframes.as_mut() {
+            let v = take(&mut self.frame);
+            for n in take(&mut self.stops) {
+                o.push((n, v.clone()));
             }
+            return;
+        }
+        let mut b = take(&mut self.b);
+        b.set_meta(self.m);
+        for (k, p) in take(&mut self.group) {
+            let o = b.with_key(k, p);
+            self.rst.style_rules_mut().push(o);
+        }
+        self.rst.style_rules_mut().push(b);
+    }
+
+    fn group(&mut self) {
+        let k = take(self.b.key_mut());
+        if !k.is_empty() {
+            self.group.push((k, self.b.position()));
         }
-        self.k = RuleKind::StyleRule;
     }
 
     fn error(&mut self, e: Error) {
@@ -3,13 +3,25 @@ use crate::utils::ascii::*;
 use std::mem::take;
 
 pub(super) trait Output {
-    fn meta(&mut self, s: String);
+    fn meta(&mut self, s: String, p: (usize, usize));
 
-    fn mark_selector(&mut self, c: String, s: String);
+    fn end_meta(&mut self);
 
-    fn attribute_selector(&mut self, c: String, k: String, v: String);
+    ///`s` is the offsets of a stop in `@keyframes` block, separated by comma.
+    fn keyframe(&mut self, s: String, p: (usize, usize));
 
-    fn attribute(&mut self, k: String, v: String);
+    fn mark_selector(&mut self, c: String, s: String, p: (usize, usize));
+
+    ///`o` is the operator such as `=` and `^=`, empty if it's a class.
+    fn attribute_selector(&mut self, c: String, k: String, o: String, v: String, p: (usize, usize));
+
+    ///`c` is None if the state is attached to the preceding selector.
+    fn state_selector(&mut self, c: Option<String>, s: String, p: (usize, usize));
+
+    ///Starts the next selector of a group.
+    fn group(&mut self);
+
+    fn attribute(&mut self, k: String, v: String, p: (usize, usize));
 
     fn end_block(&mut self);
 
@@ -26,6 +38,13 @@ where
     counter: CharCounter,
     temporary: (String, String),
     temporary_c: String,
+    temporary_o: String,
+    p: (usize, usize),
+    nested: bool,
+    //in `@keyframes` block, where blocks start with offsets instead of selectors.
+    keyframes: bool,
+    //a state follows a selector without space.
+    attached: bool,
     output: T,
 }
 
@@ -41,6 +60,11 @@ where
             counter: Default::default(),
             temporary: (String::new(), String::new()),
             temporary_c: String::new(),
+            temporary_o: String::new(),
+            p: (0, 0),
+            nested: false,
+            keyframes: false,
+            attached: false,
             output,
         }
     }
@@ -59,11 +83,19 @@ where
     }
 
     fn output_at(&mut self) {
-        if self.temporary.0.is_empty() {
-            self.temporary.1.clear();
+        let s = self.temporary.0.drain(..).as_str().to_lowercase();
+        self.temporary.1.clear();
+        //variables are declared directly in `@vars` block.
+        self.nested = s != VARS;
+        self.keyframes = s.split(SPACE).next() == Some(KEYFRAMES);
+        self.output.meta(s, self.p);
+    }
+
+    fn after_at(&self) -> fn(&mut Self) {
+        if self.nested {
+            Self::start
         } else {
-            let s = self.temporary.0.drain(..).as_str().to_lowercase();
-            self.output.meta(s);
+            Self::attr_expr_0
         }
     }
 
@@ -74,29 +106,55 @@ where
         } else {
             let c = take(&mut self.temporary_c);
             let s = self.temporary.0.drain(..).as_str().to_lowercase();
-            self.output.mark_selector(c, s);
+            self.output.mark_selector(c, s, self.p);
         }
     }
 
     fn output_a_selector(&mut self) {
         if self.temporary.1.is_empty() {
             self.temporary_c.clear();
+            self.temporary_o.clear();
             self.temporary.0.clear();
         } else {
             let c = take(&mut self.temporary_c);
+            let o = take(&mut self.temporary_o);
             let n = self.temporary.0.drain(..).as_str().to_lowercase();
             let v = take(&mut self.temporary.1);
-            self.output.attribute_selector(c, n, v);
+            self.output.attribute_selector(c, n, o, v, self.p);
+        }
+    }
+
+    fn output_s_selector(&mut self) {
+        let c = if take(&mut self.attached) {
+            None
+        } else {
+            Some(take(&mut self.temporary_c))
+        };
+        let mut s = take(&mut self.temporary.0);
+        //the argument in parentheses keeps its case.
+        let i = s.find(LEFT_PARENTHESIS).unwrap_or(s.len());
+        s.replace_range(..i, &s[..i].to_lowercase());
+        if s.is_empty() {
+            self.output_error(ErrorCode::IllegalChar);
+        } else {
+            self.output.state_selector(c, s, self.p);
         }
     }
 
+    fn output_group(&mut self) {
+        self.temporary_c.clear();
+        self.output.group();
+        self.current_function = Self::ignore;
+        self.next_function = Self::next_1;
+    }
+
     fn output_attr(&mut self) {
         if self.temporary.0.is_empty() {
             self.temporary.1.clear();
         } else {
             let n = self.temporary.0.drain(..).as_str().to_lowercase();
             let v = take(&mut self.temporary.1);
-            self.output.attribute(n, v);
+            self.output.attribute(n, v, self.p);
         }
     }
 
@@ -105,11 +163,21 @@ where
         self.output.end_block();
     }
 
-    fn output_error(&mut self, s: &str) {
-        let e = self.counter.to_error(ErrorKind::Style, s);
+    fn output_error(&mut self, code: ErrorCode) {
+        let e = self
+            .counter
+            .to_error(
+                ErrorKind::Style,
+                format!("{}: {:?}", code.describe(), self.c),
+            )
+            .with_code(code);
         self.output.error(e);
     }
 
+    fn mark_p(&mut self) {
+        self.p = self.counter.position();
+    }
+
     fn ignore(&mut self) {
         match self.c {
             SPACE | LF | CR => {}
@@ -123,18 +191,35 @@ where
     fn start(&mut self) {
         let c = self.c;
         match c {
-            EXCLAMATION => {
+            AT if !self.nested => {
                 self.current_function = Self::meta_0;
             }
+            RIGHT_CURLY_BRACKET if self.nested => {
+                self.output.end_meta();
+                self.nested = false;
+                self.keyframes = false;
+                self.current_function = Self::ignore;
+                self.next_function = Self::start;
+            }
+            'A'..='Z' | 'a'..='z' | '0'..='9' if self.keyframes => {
+                self.mark_p();
+                self.current_function = Self::stop;
+                self.stop();
+            }
             'A'..='Z' | 'a'..='z' => {
                 self.current_function = Self::m_selector;
                 self.m_selector();
             }
             FULL_STOP => {
+                self.mark_p();
                 self.current_function = Self::a_selector_0;
             }
+            COLON => {
+                self.mark_p();
+                self.current_function = Self::s_selector;
+            }
             _ => {
-                self.output_error("illegal char");
+                self.output_error(ErrorCode::IllegalChar);
                 self.current_function = Self::ignore;
                 self.next_function = Self::start;
             }
@@ -149,18 +234,26 @@ where
                 self.m_selector();
             }
             FULL_STOP => {
+                self.mark_p();
                 self.current_function = Self::a_selector_0;
             }
-            QUESTION | PLUS | HYPHEN => {
+            COLON => {
+                self.mark_p();
+                self.current_function = Self::s_selector;
+            }
+            QUESTION | PLUS | HYPHEN | GT => {
                 self.temporary_c.push(c);
                 self.current_function = Self::combiner;
             }
+            COMMA => {
+                self.output_group();
+            }
             LEFT_CURLY_BRACKET => {
                 self.current_function = Self::ignore;
                 self.next_function = Self::attr_expr_0;
             }
             _ => {
-                self.output_error("illegal char");
+                self.output_error(ErrorCode::IllegalChar);
                 self.current_function = Self::next_0_1;
             }
         }
@@ -184,14 +277,19 @@ where
                 self.m_selector();
             }
             FULL_STOP => {
+                self.mark_p();
                 self.current_function = Self::a_selector_0;
             }
+            COLON => {
+                self.mark_p();
+                self.current_function = Self::s_selector;
+            }
             LEFT_CURLY_BRACKET => {
                 self.current_function = Self::ignore;
                 self.next_function = Self::attr_expr_0;
             }
             _ => {
-                self.output_error("illegal char");
+                self.output_error(ErrorCode::IllegalChar);
                 self.current_function = Self::next_1_1;
             }
         }
@@ -210,14 +308,14 @@ where
     fn meta_0(&mut self) {
         let c = self.c;
         match c {
-            'A'..='Z' | 'a'..='z' | HYPHEN => {
+            'A'..='Z' | 'a'..='z' => {
                 self.current_function = Self::meta_1;
                 self.meta_1();
             }
             _ => {
-                self.output_error("illegal char");
-                self.current_function = Self::ignore;
-                self.next_function = Self::meta_0_1;
+                self.output_error(ErrorCode::IllegalChar);
+                self.current_function = Self::meta_0_1;
+                self.meta_0_1();
             }
         }
     }
@@ -225,48 +323,65 @@ where
     fn meta_1(&mut self) {
         let c = self.c;
         match c {
-            'A'..='Z' | 'a'..='z' | HYPHEN => {
+            'A'..='Z' | 'a'..='z' | '0'..='9' | HYPHEN | FULL_STOP | COMMA | LT | GT | EQUAL => {
+                if self.temporary.0.is_empty() {
+                    self.mark_p();
+                }
                 self.temporary.0.push(c);
             }
-            SPACE => {
+            SPACE | LF | CR => {
+                //the name of keyframes is separated from the keyword.
+                if self.temporary.0.eq_ignore_ascii_case(KEYFRAMES) {
+                    self.temporary.0.push(SPACE);
+                }
+                self.current_function = Self::ignore;
+                self.next_function = Self::meta_1;
+            }
+            LEFT_CURLY_BRACKET => {
                 self.output_at();
                 self.current_function = Self::ignore;
-                self.next_function = Self::meta_2;
+                self.next_function = self.after_at();
             }
             _ => {
-                self.current_function = Self::meta_2;
-                self.meta_2();
+                self.output_error(ErrorCode::IllegalChar);
+                self.current_function = Self::meta_0_1;
             }
         }
     }
 
-    fn meta_2(&mut self) {
+    //skips an illegal condition, the block is kept but never applied.
+    fn meta_0_1(&mut self) {
         match self.c {
             LEFT_CURLY_BRACKET => {
+                self.temporary.0.clear();
                 self.output_at();
                 self.current_function = Self::ignore;
-                self.next_function = Self::attr_expr_0;
-            }
-            RIGHT_CURLY_BRACKET => {
-                self.output_error("illegal char");
-                self.current_function = Self::ignore;
-                self.next_function = Self::start;
-            }
-            _ => {
-                self.output_error("illegal char");
-                self.current_function = Self::ignore;
-                self.next_function = Self::meta_0_1;
+                self.next_function = self.after_at();
             }
+            _ => {}
         }
     }
 
-    fn meta_0_1(&mut self) {
-        match self.c {
-            RIGHT_CURLY_BRACKET => {
+    //the offsets of a keyframe such as `from`, `50%` and `0%, 100%`.
+    fn stop(&mut self) {
+        let c = self.c;
+        match c {
+            'A'..='Z' | 'a'..='z' | '0'..='9' | FULL_STOP | PER_CENT | COMMA => {
+                self.temporary.0.push(c);
+            }
+            SPACE | LF | CR => {
                 self.current_function = Self::ignore;
-                self.next_function = Self::start;
+                self.next_function = Self::stop;
+            }
+            LEFT_CURLY_BRACKET => {
+                let s = self.temporary.0.drain(..).as_str().to_lowercase();
+                self.output.keyframe(s, self.p);
+                self.current_function = Self::ignore;
+                self.next_function = Self::attr_expr_0;
+            }
+            _ => {
+                self.output_error(ErrorCode::IllegalChar);
             }
-            _ => {}
         }
     }
 
@@ -274,6 +389,9 @@ where
         let c = self.c;
         match c {
             'A'..='Z' | 'a'..='z' => {
+                if self.temporary.0.is_empty() {
+                    self.mark_p();
+                }
                 self.temporary.0.push(c);
             }
             SPACE => {
@@ -286,11 +404,19 @@ where
                 self.current_function = Self::ignore;
                 self.next_function = Self::attr_expr_0;
             }
+            COLON => {
+                self.output_m_selector();
+                self.attach();
+            }
+            COMMA => {
+                self.output_m_selector();
+                self.output_group();
+            }
             RIGHT_CURLY_BRACKET => {
-                self.output_error("illegal char");
+                self.output_error(ErrorCode::IllegalChar);
             }
             _ => {
-                self.output_error("invalid mark");
+                self.output_error(ErrorCode::InvalidMark);
             }
         }
     }
@@ -306,7 +432,7 @@ where
                 self.a_selector_2();
             }
             _ => {
-                self.output_error("invalid attribute");
+                self.output_error(ErrorCode::InvalidAttribute);
             }
         }
     }
@@ -314,13 +440,17 @@ where
     fn a_selector_1(&mut self) {
         let c = self.c;
         match c {
-            'A'..='Z' | 'a'..='z' => {
+            'A'..='Z' | 'a'..='z' | '0'..='9' | HYPHEN | LOW_LINE => {
                 self.temporary.1.push(c);
             }
             EQUAL => {
                 self.current_function = Self::a_selector_2;
                 self.a_selector_2();
             }
+            TILDE | ASTERISK | CIRCUMFLEX_ACCENT | DOLLAR_SIGN => {
+                self.temporary_o.push(c);
+                self.current_function = Self::a_selector_2;
+            }
             SPACE => {
                 self.current_function = Self::ignore;
                 self.next_function = Self::a_selector_2;
@@ -329,8 +459,16 @@ where
                 self.current_function = Self::a_selector_4;
                 self.a_selector_4();
             }
+            COLON => {
+                self.output_a_selector();
+                self.attach();
+            }
+            COMMA => {
+                self.output_a_selector();
+                self.output_group();
+            }
             _ => {
-                self.output_error("invalid attribute");
+                self.output_error(ErrorCode::InvalidAttribute);
             }
         }
     }
@@ -338,6 +476,7 @@ where
     fn a_selector_2(&mut self) {
         match self.c {
             EQUAL => {
+                self.temporary_o.push(EQUAL);
                 std::mem::swap(&mut self.temporary.0, &mut self.temporary.1);
                 self.current_function = Self::ignore;
                 self.next_function = Self::a_selector_3;
@@ -358,7 +497,7 @@ where
     fn a_selector_3(&mut self) {
         let c = self.c;
         match c {
-            'A'..='Z' | 'a'..='z' => {
+            'A'..='Z' | 'a'..='z' | '0'..='9' | HYPHEN | LOW_LINE => {
                 self.temporary.1.push(c);
             }
             SPACE => {
@@ -370,9 +509,17 @@ where
                 self.current_function = Self::a_selector_4;
                 self.a_selector_4();
             }
+            COLON => {
+                self.output_a_selector();
+                self.attach();
+            }
+            COMMA => {
+                self.output_a_selector();
+                self.output_group();
+            }
             _ => {
                 self.output_a_selector();
-                self.output_error("invalid attribute");
+                self.output_error(ErrorCode::InvalidAttribute);
             }
         }
     }
@@ -385,9 +532,61 @@ where
                 self.next_function = Self::attr_expr_0;
             }
             _ => {
+                self.current_function = Self::next_0;
+                self.next_0();
+            }
+        }
+    }
+
+    fn attach(&mut self) {
+        self.mark_p();
+        self.attached = true;
+        self.current_function = Self::s_selector;
+    }
+
+    fn s_selector(&mut self) {
+        let c = self.c;
+        match c {
+            'A'..='Z' | 'a'..='z' | HYPHEN => {
+                self.temporary.0.push(c);
+            }
+            LEFT_PARENTHESIS => {
+                self.temporary.0.push(c);
+                self.current_function = Self::s_selector_1;
+            }
+            COMMA => {
+                self.output_s_selector();
+                self.output_group();
+            }
+            COLON => {
+                self.output_s_selector();
+                self.attach();
+            }
+            SPACE | LF | CR => {
+                self.output_s_selector();
                 self.current_function = Self::ignore;
                 self.next_function = Self::next_0;
             }
+            LEFT_CURLY_BRACKET => {
+                self.output_s_selector();
+                self.current_function = Self::ignore;
+                self.next_function = Self::attr_expr_0;
+            }
+            _ => {
+                self.output_error(ErrorCode::IllegalChar);
+            }
+        }
+    }
+
+    //the argument in parentheses, which may be nested.
+    fn s_selector_1(&mut self) {
+        let c = self.c;
+        self.temporary.0.push(c);
+        if c == RIGHT_PARENTHESIS {
+            let o = &self.temporary.0;
+            if o.matches(LEFT_PARENTHESIS).count() == o.matches(RIGHT_PARENTHESIS).count() {
+                self.current_function = Self::s_selector;
+            }
         }
     }
 
@@ -408,7 +607,7 @@ where
             }
             _ => {
                 self.temporary_c.clear();
-                self.output_error("illegal char");
+                self.output_error(ErrorCode::IllegalChar);
                 self.current_function = Self::ignore;
                 self.next_function = Self::next_1;
             }
@@ -435,7 +634,7 @@ where
                 self.attr_expr_2();
             }
             _ => {
-                self.output_error("illegal char");
+                self.output_error(ErrorCode::IllegalChar);
                 self.current_function = Self::attr_expr_0_2;
                 self.attr_expr_0_2();
             }
@@ -449,7 +648,7 @@ where
                 self.attr_expr_0_2();
             }
             _ => {
-                self.output_error("illegal char");
+                self.output_error(ErrorCode::IllegalChar);
                 self.current_function = Self::attr_expr_0_2;
                 self.attr_expr_0_2();
             }
@@ -491,7 +690,7 @@ where
                 self.attr_expr_2();
             }
             _ => {
-                self.output_error("illegal char");
+                self.output_error(ErrorCode::IllegalChar);
                 self.current_function = Self::attr_expr_1_0;
                 self.attr_expr_1_0();
             }
@@ -512,6 +711,9 @@ where
         let c = self.c;
         match c {
             'A'..='Z' | 'a'..='z' | HYPHEN => {
+                if self.temporary.0.is_empty() {
+                    self.mark_p();
+                }
                 self.temporary.0.push(c);
             }
             SPACE => {
@@ -533,7 +735,7 @@ where
                 self.next_function = Self::start;
             }
             _ => {
-                self.output_error("illegal char");
+                self.output_error(ErrorCode::IllegalChar);
                 self.current_function = Self::attr_expr_1_0;
                 self.attr_expr_1_0();
             }
@@ -552,7 +754,7 @@ where
                 self.attr_expr_2();
             }
             _ => {
-                self.output_error("illegal char");
+                self.output_error(ErrorCode::IllegalChar);
                 self.current_function = Self::attr_expr_1_0;
                 self.attr_expr_1_0();
             }
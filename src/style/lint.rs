@@ -0,0 +1,152 @@
+use super::*;
+
+///Checks a style sheet against elements, returns the errors of the style sheet followed by
+///rules which match nothing, attributes which take effect on none of the matched elements,
+///declarations which are duplicated or always overridden, and ids used more than once.
+///
+///Attributes of elements are regarded as inline ones, and rules in `@` blocks are checked as if the conditions matched.
+///Rules with "hover", "focus" or "pressed" state are only checked for duplicates.
+pub fn lint_style(s: &str, eh: &ElementHolder) -> ErrorHolder {
+    let (style_sheet, mut err) = StyleSheetBuilder::build(s);
+    let rules = style_sheet.style_rules();
+    let found: Vec<_> = rules
+        .iter()
+        .map(|o| (!o.key().is_interactive()).then(|| o.key().find(eh)))
+        .collect();
+    //the highest declaration of each attribute on each element, separately for each meta rule.
+    let mut best = HashMap::new();
+    for (i, sr) in rules.iter().enumerate() {
+        let ks = if let Some(o) = &found[i] {
+            o
+        } else {
+            continue;
+        };
+        for n in sr.attribute().keys().chain(sr.pending().keys()) {
+            let o = (sr.important().contains(n), sr.key().specificity(), i);
+            for &k in ks {
+                let v = best.entry((k, n, sr.meta())).or_insert(o);
+                if o > *v {
+                    *v = o;
+                }
+            }
+        }
+    }
+    for (i, sr) in rules.iter().enumerate() {
+        for (n, p) in sr.duplicates() {
+            let e = format!("duplicate declaration: {}", n.as_str());
+            lint(
+                &mut err,
+                e,
+                ErrorCode::OverriddenDeclaration,
+                *p,
+                n.as_str(),
+            );
+        }
+        //the selector is invalid, which is reported by the parser.
+        if sr.key().is_empty() {
+            continue;
+        }
+        let ks = if let Some(o) = &found[i] {
+            o
+        } else {
+            continue;
+        };
+        let selector = sr.key().to_string();
+        if ks.is_empty() {
+            let e = format!("rule matches nothing: {selector}");
+            lint(
+                &mut err,
+                e,
+                ErrorCode::UnmatchedRule,
+                sr.position(),
+                &selector,
+            );
+            continue;
+        }
+        let mut names: Vec<_> = sr.attribute().keys().chain(sr.pending().keys()).collect();
+        names.sort_by_key(|n| sr.positions().get(*n));
+        for n in names {
+            let p = sr.positions().get(n).copied().unwrap_or(sr.position());
+            let marks = ks.iter().filter_map(|&k| eh.get(k).map(|e| e.mark_type()));
+            if !marks.clone().any(|m| m.accepts(n)) {
+                let mut v: Vec<_> = marks.map(|m| m.as_str()).collect();
+                v.sort();
+                v.dedup();
+                let e = format!(
+                    "{} takes no effect on {}: {selector}",
+                    n.as_str(),
+                    v.join(", ")
+                );
+                lint(&mut err, e, ErrorCode::InapplicableAttribute, p, n.as_str());
+                continue;
+            }
+            let o = (sr.important().contains(n), sr.key().specificity(), i);
+            //an inline attribute or a winning rule out of `@` blocks or in the same one replaces it on every matched element.
+            let wins = |k, m| best.get(&(k, n, m)).is_some_and(|b| *b > o);
+            let overridden = ks.iter().all(|&k| {
+                (!o.0 && eh.get(k).is_some_and(|e| e.attribute_get(n).is_some()))
+                    || wins(k, None)
+                    || (sr.meta().is_some() && wins(k, sr.meta()))
+            });
+            if overridden {
+                let e = format!("{} is always overridden: {selector}", n.as_str());
+                lint(&mut err, e, ErrorCode::OverriddenDeclaration, p, n.as_str());
+            }
+        }
+    }
+
+    let mut ks = Vec::new();
+    FindDescendant::new(AttrExists::new(&AttrName::ID), 0).all_with_first_root(eh, &mut ks);
+    let mut ids: Vec<(&String, usize)> = Vec::new();
+    for k in ks {
+        let id = if let Some(o) = eh.get(k).and_then(|e| e.id()) {
+            o
+        } else {
+            continue;
+        };
+        match ids.iter_mut().find(|o| o.0 == id) {
+            Some(o) => o.1 += 1,
+            None => ids.push((id, 1)),
+        }
+    }
+    for (id, n) in ids.into_iter().filter(|o| o.1 > 1) {
+        let e: Error = (
+            ErrorKind::Style,
+            format!("duplicate id: {id}, used by {n} elements"),
+        )
+            .into();
+        err.push(e.with_code(ErrorCode::DuplicateId));
+    }
+    err
+}
+
+fn lint(err: &mut ErrorHolder, s: String, code: ErrorCode, p: (usize, usize), o: &str) {
+    let e: Error = (ErrorKind::Style, s).into();
+    err.push(e.with_code(code).with_span(p, o.chars().count()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lint() {
+        let s = "<body><button id=a width=10>x</button><pt id=a>y</pt><inp/></body>";
+        let eh = ElementHolder::parse(s, MarkNumber::Double).0.unwrap();
+        let s = "video{width:1;} button{column:1;} pt, inp{height:1;height:2;} \
+            button:hover{width:1;} button{width:5;} pt{color:#000;} body pt{color:#fff;} foo{}";
+        let err = lint_style(s, &eh);
+        let code = |c| err.iter().filter(|e| e.code() == Some(c)).count();
+        assert_eq!(code(ErrorCode::InvalidMark), 1);
+        assert_eq!(code(ErrorCode::UnmatchedRule), 1);
+        assert_eq!(code(ErrorCode::InapplicableAttribute), 1);
+        assert_eq!(code(ErrorCode::OverriddenDeclaration), 3);
+        assert_eq!(code(ErrorCode::DuplicateId), 1);
+        assert_eq!(err.len(), 7);
+        let e = err
+            .iter()
+            .find(|e| e.code() == Some(ErrorCode::UnmatchedRule));
+        assert_eq!(e.unwrap().position(), Some((0, 0)));
+        assert_eq!(e.unwrap().length(), 5);
+    }
+}
@@ -1,41 +1,695 @@
 mod entity;
 mod format;
+mod lint;
 
+pub(crate) use self::entity::Keyframes;
 use self::entity::*;
 use self::format::*;
+pub use self::lint::*;
 use crate::error::*;
 use crate::markup::*;
 use crate::utils::*;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+
+//variables declared on each element, with the specificity and index of the rule.
+type Vars = HashMap<ElementKey, HashMap<String, ((usize, usize, usize), usize, String)>>;
+
+//the winning rule of each attribute on elements, as importance, specificity and index.
+type Cascade = HashMap<(ElementKey, AttrName), (bool, (usize, usize, usize), usize)>;
+
+//attributes of elements as strings, to find which of them changed.
+type Snapshot = HashMap<ElementKey, HashMap<AttrName, String>>;
 
 #[derive(Debug)]
 pub(crate) struct StyleContext {
     style_sheet: StyleSheet,
+    applied: HashMap<ElementKey, HashMap<AttrName, Applied>>,
+    viewport: Option<Viewport>,
+    //elements matched by each rule in the last cascade, empty for inactive rules.
+    matched: Vec<Vec<ElementKey>>,
+    vars: Vars,
+    //values which failed in cascade, such as a variable declared in rules but not on the element,
+    //each declaration is reported once.
+    error: ErrorHolder,
+    failed: HashSet<(usize, AttrName)>,
+}
+
+///Represents the size and scale factor of window, which meta rules are evaluated against.
+#[derive(Clone, Copy, Debug, PartialEq, CopyGetters)]
+pub(crate) struct Viewport {
+    #[getset(get_copy = "pub(crate)")]
+    width: f32,
+    #[getset(get_copy = "pub(crate)")]
+    height: f32,
+    #[getset(get_copy = "pub(crate)")]
+    scale: f32,
+}
+
+impl Viewport {
+    pub(crate) fn new(width: f32, height: f32, scale: f32) -> Self {
+        Self {
+            width,
+            height,
+            scale,
+        }
+    }
+}
+
+//an attribute supplied by a style rule.
+#[derive(Debug)]
+struct Applied {
+    rule: usize,
+    value: String,
+    inline: Option<Attribute>,
 }
 
 impl StyleContext {
-    pub(crate) fn new(s: &str) -> Self {
+    pub(crate) fn new(s: &str) -> (Self, ErrorHolder) {
         let (style_sheet, err) = StyleSheetBuilder::build(s);
         if err.len() > 0 {
             info!("{}", err);
         }
-        Self { style_sheet }
+        let o = Self {
+            style_sheet,
+            applied: Default::default(),
+            viewport: None,
+            matched: Default::default(),
+            vars: Default::default(),
+            error: Default::default(),
+            failed: Default::default(),
+        };
+        (o, err)
+    }
+
+    ///Replaces the style sheet and applies it, the attributes supplied by the previous one are removed at first.
+    pub(crate) fn reload(&mut self, s: &str, eh: &mut ElementHolder) -> ErrorHolder {
+        self.clear_style(eh);
+        let (o, mut err) = Self::new(s);
+        self.style_sheet = o.style_sheet;
+        self.failed.clear();
+        self.set_style(eh);
+        err.append(&mut self.take_error());
+        err
+    }
+
+    ///Takes the errors found in cascade since the last call.
+    pub(crate) fn take_error(&mut self) -> ErrorHolder {
+        std::mem::take(&mut self.error)
     }
 
+    ///Applies style rules by cascade. For an attribute, an important rule wins over others,
+    ///then the higher specificity, then the later one in source. Inline attributes are kept unless the rule is important.
     pub(crate) fn set_style(&mut self, eh: &mut ElementHolder) {
-        let ss = self.style_sheet.style_rules_mut().iter_mut();
-        for sr in ss {
-            let ks = sr.key().find(eh);
-            debug!("{:?} : {:?}", sr.key(), ks);
-            if ks.is_empty() {
+        self.clear_style(eh);
+        let active = self.active();
+        let rules = self.style_sheet.style_rules();
+        self.matched = rules
+            .iter()
+            .map(|sr| {
+                if sr.meta().is_some_and(|o| !active[o]) {
+                    return Vec::new();
+                }
+                let ks = sr.key().find(eh);
+                debug!("{:?} : {:?}", sr.key(), ks);
+                ks
+            })
+            .collect();
+        let mut vars: Vars = HashMap::new();
+        for (i, (sr, ks)) in rules.iter().zip(&self.matched).enumerate() {
+            let sp = sr.key().specificity();
+            for &k in ks {
+                for (n, v) in sr.vars() {
+                    let o = vars.entry(k).or_default();
+                    if o.get(n).is_none_or(|o| (sp, i) >= (o.0, o.1)) {
+                        o.insert(n.clone(), (sp, i, v.clone()));
+                    }
+                }
+            }
+        }
+        self.vars = vars;
+        let m = self.cascade(|_| true);
+        self.apply(eh, m);
+    }
+
+    ///Applies the rules with states again to the elements whose matches changed, others are untouched.
+    ///Returns the elements with the attributes changed on them.
+    pub(crate) fn restyle(&mut self, eh: &mut ElementHolder) -> Vec<(ElementKey, Vec<AttrName>)> {
+        let active = self.active();
+        let mut ks = HashSet::new();
+        let mut vars = false;
+        for (i, sr) in self.style_sheet.style_rules().iter().enumerate() {
+            if !sr.key().is_interactive() || sr.meta().is_some_and(|o| !active[o]) {
                 continue;
             }
-            for k in ks {
-                if let Some(e) = eh.get_mut(k) {
-                    for a in sr.attribute().values() {
-                        e.attribute_insert(a.clone());
+            let v = sr.key().find(eh);
+            let old = &self.matched[i];
+            let n = ks.len();
+            ks.extend(v.iter().filter(|k| !old.contains(k)));
+            ks.extend(old.iter().filter(|k| !v.contains(k)));
+            vars |= ks.len() > n && !sr.vars().is_empty();
+            self.matched[i] = v;
+        }
+        if ks.is_empty() {
+            return Vec::new();
+        }
+        //variables declared with a state reach the subset, so all rules are applied again.
+        if vars {
+            ks.extend(self.matched.iter().flatten());
+            let before = snapshot(eh, &ks);
+            self.set_style(eh);
+            return changed(before, snapshot(eh, &ks));
+        }
+        let before = snapshot(eh, &ks);
+        for &k in &ks {
+            self.clear_element(eh, k);
+        }
+        let m = self.cascade(|k| ks.contains(k));
+        self.apply(eh, m);
+        changed(before, snapshot(eh, &ks))
+    }
+
+    //picks the winning rule of each attribute on the elements accepted by `f`.
+    fn cascade(&self, f: impl Fn(&ElementKey) -> bool) -> Cascade {
+        let mut m = HashMap::new();
+        let rules = self.style_sheet.style_rules();
+        for (i, (sr, ks)) in rules.iter().zip(&self.matched).enumerate() {
+            let sp = sr.key().specificity();
+            for &k in ks.iter().filter(|&k| f(k)) {
+                for n in sr.attribute().keys().chain(sr.pending().keys()) {
+                    let o = (sr.important().contains(n), sp, i);
+                    match m.entry((k, n.clone())) {
+                        Entry::Occupied(mut e) => {
+                            if &o >= e.get() {
+                                e.insert(o);
+                            }
+                        }
+                        Entry::Vacant(e) => {
+                            e.insert(o);
+                        }
+                    }
+                }
+            }
+        }
+        m
+    }
+
+    //resolves variables of the winning rules, then writes them into elements.
+    fn apply(&mut self, eh: &mut ElementHolder, m: Cascade) {
+        let rules = self.style_sheet.style_rules();
+        let mut resolved = Vec::with_capacity(m.len());
+        for ((k, n), (important, _, i)) in m {
+            let v = match rules
+                .get(i)
+                .map(|o| (o.attribute().get(&n), o.pending().get(&n)))
+            {
+                Some((Some(a), _)) => {
+                    resolved.push((k, n, important, i, a.clone()));
+                    continue;
+                }
+                Some((None, Some(v))) => v,
+                _ => continue,
+            };
+            let mut stack = Vec::new();
+            let a = substitute(v, |o| self.resolve(eh, &self.vars, k, o, &mut stack))
+                .and_then(|v| Attribute::from(&n, &mut v.trim().to_string()));
+            match a {
+                Ok(a) => resolved.push((k, n, important, i, a)),
+                Err(e) => {
+                    if self.failed.insert((i, n.clone())) {
+                        let p = rules[i].positions().get(&n).copied().unwrap_or_default();
+                        let code = e.code().unwrap_or(ErrorCode::InvalidValue);
+                        let e: Error =
+                            (ErrorKind::Style, format!("{n}: {v:?} {}", e.message())).into();
+                        let e = e.with_code(code).with_span(p, n.as_str().chars().count());
+                        self.error.push(e);
                     }
                 }
             }
         }
+
+        for (k, n, important, i, a) in resolved {
+            let e = if let Some(e) = eh.get_mut(k) {
+                e
+            } else {
+                continue;
+            };
+            let inline = e.attribute_get(&n).cloned();
+            if inline.is_some() && !important {
+                continue;
+            }
+            let value = a.to_string();
+            e.attribute_insert(a);
+            let o = Applied {
+                rule: i,
+                value,
+                inline,
+            };
+            self.applied.entry(k).or_default().insert(n, o);
+        }
+    }
+
+    //finds a variable on the element and its uppers, then in `@vars` block.
+    fn resolve(
+        &self,
+        eh: &ElementHolder,
+        vars: &Vars,
+        mut k: ElementKey,
+        name: &str,
+        stack: &mut Vec<String>,
+    ) -> Result<String> {
+        if stack.iter().any(|o| o == name) {
+            let e: Error = (ErrorKind::Style, format!("cyclic variable: {name}")).into();
+            return Err(e.with_code(ErrorCode::CyclicVariable));
+        }
+        let v = loop {
+            if let Some(o) = vars.get(&k).and_then(|o| o.get(name)) {
+                break Some((Some(k), &o.2));
+            }
+            match eh.get(k).and_then(|e| *e.upper()) {
+                Some(o) => k = o,
+                None => break self.style_sheet.vars().get(name).map(|o| (None, o)),
+            }
+        };
+        let (scope, v) = option_return!(v, Err(undefined(name)));
+        stack.push(name.to_string());
+        //variables in a value are looked up from where it's declared.
+        let r = substitute(v, |o| match scope {
+            Some(k) => self.resolve(eh, vars, k, o, stack),
+            None => self.resolve_global(o, stack),
+        });
+        stack.pop();
+        r
+    }
+
+    fn resolve_global(&self, name: &str, stack: &mut Vec<String>) -> Result<String> {
+        if stack.iter().any(|o| o == name) {
+            let e: Error = (ErrorKind::Style, format!("cyclic variable: {name}")).into();
+            return Err(e.with_code(ErrorCode::CyclicVariable));
+        }
+        let v = option_return!(self.style_sheet.vars().get(name), Err(undefined(name)));
+        stack.push(name.to_string());
+        let r = substitute(v, |o| self.resolve_global(o, stack));
+        stack.pop();
+        r
+    }
+
+    ///Returns the block declared as `@keyframes name`.
+    pub(crate) fn keyframes(&self, name: &str) -> Option<&Keyframes> {
+        self.style_sheet.keyframes().get(name)
+    }
+
+    ///Returns true if any rule matches a state changed by actions.
+    pub(crate) fn is_interactive(&self) -> bool {
+        self.style_sheet
+            .style_rules()
+            .iter()
+            .any(|o| o.key().is_interactive())
+    }
+
+    ///Evaluates meta rules against a new viewport, applies style rules again if any of them changed.
+    ///Returns true if it applied.
+    pub(crate) fn respond(&mut self, v: Viewport, eh: &mut ElementHolder) -> bool {
+        if self.viewport == Some(v) {
+            return false;
+        }
+        let a = self.active();
+        self.viewport.replace(v);
+        if a == self.active() {
+            return false;
+        }
+        self.set_style(eh);
+        true
+    }
+
+    //meta rules never match before the page gets a size.
+    fn active(&self) -> Vec<bool> {
+        self.style_sheet
+            .meta_rules()
+            .iter()
+            .map(|o| self.viewport.as_ref().is_some_and(|v| o.is_match(v)))
+            .collect()
+    }
+
+    ///Removes the attributes supplied by style rules, restores the inline ones they replaced.
+    pub(crate) fn clear_style(&mut self, eh: &mut ElementHolder) {
+        let ks: Vec<_> = self.applied.keys().cloned().collect();
+        for k in ks {
+            self.clear_element(eh, k);
+        }
+    }
+
+    fn clear_element(&mut self, eh: &mut ElementHolder, k: ElementKey) {
+        let m = option_return!(self.applied.remove(&k));
+        let e = option_return!(eh.get_mut(k));
+        for (n, o) in m {
+            //it was changed after applying, keeps the new one.
+            if e.attribute_get(&n).map(|a| a.to_string()) != Some(o.value) {
+                continue;
+            }
+            if let Some(a) = o.inline {
+                e.attribute_insert(a);
+            } else {
+                e.attribute_remove(&n);
+            }
+        }
+    }
+
+    ///Returns the rule which supplied an attribute of an element.
+    pub(crate) fn origin(&self, k: ElementKey, n: &AttrName) -> Option<StyleOrigin> {
+        let o = self.applied.get(&k)?.get(n)?;
+        let sr = self.style_sheet.style_rules().get(o.rule)?;
+        Some(StyleOrigin {
+            rule: o.rule,
+            selector: sr.key().to_string(),
+            specificity: sr.key().specificity(),
+            important: sr.important().contains(n),
+            inline: o.inline.is_some(),
+        })
+    }
+}
+
+fn undefined(name: &str) -> Error {
+    let e: Error = (ErrorKind::Style, format!("undefined variable: {name}")).into();
+    e.with_code(ErrorCode::UndefinedVariable)
+}
+
+fn snapshot(eh: &ElementHolder, ks: &HashSet<ElementKey>) -> Snapshot {
+    let f = |k: &ElementKey| {
+        let e = eh.get(*k)?;
+        let m = e.attribute().iter();
+        Some((*k, m.map(|(n, a)| (n.clone(), a.to_string())).collect()))
+    };
+    ks.iter().filter_map(f).collect()
+}
+
+fn changed(before: Snapshot, after: Snapshot) -> Vec<(ElementKey, Vec<AttrName>)> {
+    let mut r = Vec::new();
+    for (k, a) in after {
+        let b = before.get(&k);
+        let mut v: Vec<_> = a
+            .iter()
+            .filter(|(n, s)| b.and_then(|b| b.get(*n)) != Some(*s))
+            .map(|o| o.0.clone())
+            .collect();
+        if let Some(b) = b {
+            v.extend(b.keys().filter(|n| !a.contains_key(*n)).cloned());
+        }
+        if !v.is_empty() {
+            r.push((k, v));
+        }
+    }
+    r
+}
+
+///Represents the style rule which supplied an attribute.
+#[derive(Clone, Debug, CopyGetters, Getters)]
+pub struct StyleOrigin {
+    ///Index of the rule in source order.
+    #[getset(get_copy = "pub")]
+    rule: usize,
+    #[getset(get = "pub")]
+    selector: String,
+    ///The number of id, attribute and mark selectors.
+    #[getset(get_copy = "pub")]
+    specificity: (usize, usize, usize),
+    #[getset(get_copy = "pub")]
+    important: bool,
+    ///Returns true if it replaced an inline attribute.
+    #[getset(get_copy = "pub")]
+    inline: bool,
+}
+
+///Parses selectors separated by comma in the syntax of style sheet.
+pub(crate) fn parse_selector(s: &str) -> Result<SelectorGroup> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Err((ErrorKind::Style, "empty selector").into());
+    }
+    let (mut style_sheet, mut err) = StyleSheetBuilder::build(&format!("{s}{{}}"));
+    if !err.is_empty() {
+        return Err(err.remove(0));
+    }
+    let v: Vec<_> = style_sheet
+        .style_rules_mut()
+        .drain(..)
+        .map(|o| o.into_key())
+        .filter(|o| !o.is_empty())
+        .collect();
+    if v.is_empty() {
+        return Err((ErrorKind::Style, format!("invalid selector: {s}")).into());
+    }
+    Ok(SelectorGroup::new(v))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn elements(s: &str) -> ElementHolder {
+        let (eh, err) = ElementHolder::parse(s, MarkNumber::Double);
+        assert!(err.is_empty(), "{err}");
+        eh.unwrap()
+    }
+
+    fn key(eh: &ElementHolder, s: &str) -> ElementKey {
+        eh.query_one(s).unwrap().unwrap()
+    }
+
+    fn attr(eh: &ElementHolder, k: ElementKey, n: AttrName) -> Option<String> {
+        eh.get(k)?.attribute_get(&n).map(|a| a.to_string())
+    }
+
+    #[test]
+    fn cascade() {
+        let mut eh = elements(
+            "<body><area><pt>x</pt></area><button class=a position=1,2>ok</button></body>",
+        );
+        let s = "pt{width:1;height:1;} area pt{width:2;} .a{position:3,3;} \
+            button{position:4,4 !important;} button{position:5,5;}";
+        let (mut sc, err) = StyleContext::new(s);
+        assert!(err.is_empty());
+        let b = key(&eh, "button");
+        let inline = attr(&eh, b, AttrName::POSITION);
+        sc.set_style(&mut eh);
+
+        let pt = key(&eh, "pt");
+        let o = sc.origin(pt, &AttrName::WIDTH).unwrap();
+        assert_eq!(o.rule(), 1);
+        assert_eq!(o.selector(), "area pt");
+        assert_eq!(o.specificity(), (0, 0, 2));
+        assert_eq!(sc.origin(pt, &AttrName::HEIGHT).unwrap().rule(), 0);
+
+        let o = sc.origin(b, &AttrName::POSITION).unwrap();
+        assert_eq!(o.rule(), 3);
+        assert!(o.important() && o.inline());
+        assert_ne!(attr(&eh, b, AttrName::POSITION), inline);
+
+        sc.clear_style(&mut eh);
+        assert_eq!(attr(&eh, b, AttrName::POSITION), inline);
+        assert!(attr(&eh, pt, AttrName::WIDTH).is_none());
+        assert!(sc.origin(pt, &AttrName::WIDTH).is_none());
+    }
+
+    #[test]
+    fn meta() {
+        let mut eh = elements("<body><pt>x</pt></body>");
+        let s = "@width>800, orientation=landscape { pt{width:2;} } pt{height:1;} @foo<1 { pt{width:3;} }";
+        let (mut sc, err) = StyleContext::new(s);
+        assert_eq!(err.len(), 1);
+        assert_eq!(err[0].code(), Some(ErrorCode::InvalidCondition));
+        sc.set_style(&mut eh);
+        let pt = key(&eh, "pt");
+        let has = |eh: &ElementHolder, n| attr(eh, pt, n).is_some();
+        assert!(!has(&eh, AttrName::WIDTH));
+        assert!(has(&eh, AttrName::HEIGHT));
+
+        assert!(sc.respond(Viewport::new(1000.0, 600.0, 1.0), &mut eh));
+        assert!(has(&eh, AttrName::WIDTH));
+        assert!(!sc.respond(Viewport::new(900.0, 600.0, 1.0), &mut eh));
+        assert!(sc.respond(Viewport::new(900.0, 1000.0, 1.0), &mut eh));
+        assert!(!has(&eh, AttrName::WIDTH));
+        assert!(has(&eh, AttrName::HEIGHT));
+    }
+
+    #[test]
+    fn vars() {
+        let mut eh = elements("<body><area><pt>x</pt><img></img></area><inp/></body>");
+        let s = "@vars { gap: 8; side: var(gap); } area{--side: 240;} \
+            pt{width:var(side);height:var(--gap);} img{width:var(side);} inp{width:var(side);}";
+        let (mut sc, err) = StyleContext::new(s);
+        assert!(err.is_empty());
+        sc.set_style(&mut eh);
+        let width = |eh: &ElementHolder, m| attr(eh, key(eh, m), AttrName::WIDTH);
+        let d = |s: &str| {
+            let a = Attribute::from(&AttrName::WIDTH, &mut s.to_string()).unwrap();
+            Some(a.to_string())
+        };
+        assert_eq!(width(&eh, "pt"), d("240"));
+        assert_eq!(width(&eh, "img"), d("240"));
+        assert_eq!(width(&eh, "inp"), d("8"));
+
+        let s = "pt{width:var(none);} @vars{a:var(b);b:var(a);}";
+        let err = StyleContext::new(s).1;
+        let code = |c| err.iter().filter(|e| e.code() == Some(c)).count();
+        assert_eq!(code(ErrorCode::UndefinedVariable), 1);
+        assert_eq!(code(ErrorCode::CyclicVariable), 2);
+    }
+
+    #[test]
+    fn vars_diamond() {
+        let s = "@vars { a: var(b) var(c); b: var(d); c: var(d); d: 1; }";
+        let err = StyleContext::new(s).1;
+        assert!(err.is_empty(), "{err}");
+
+        let s =
+            "@vars { a: var(b) var(c); b: var(d); c: var(d); d: var(e); e: var(c); f: var(f); }";
+        let err = StyleContext::new(s).1;
+        let v: Vec<_> = err.iter().map(|e| e.message().to_string()).collect();
+        let cyclic = |k| format!("cyclic variable: {k}");
+        assert_eq!(v, [cyclic("c"), cyclic("d"), cyclic("e"), cyclic("f")]);
+    }
+
+    #[test]
+    fn vars_scope() {
+        //the same names in different rules aren't the same variables.
+        let s = "area{--x:var(--y);} pt{--y:var(--x);} img{--x:1;}";
+        let err = StyleContext::new(s).1;
+        assert!(err.is_empty(), "{err}");
+
+        let s = "area{--x:var(--y);--y:var(--x);} pt{--y:1;}";
+        let err = StyleContext::new(s).1;
+        let v: Vec<_> = err.iter().map(|e| e.message().to_string()).collect();
+        assert_eq!(v, ["cyclic variable: x", "cyclic variable: y"]);
+    }
+
+    #[test]
+    fn vars_unresolved() {
+        let mut eh = elements("<body><area><pt>x</pt></area><img></img><img></img></body>");
+        let s = "area{--side:240;} pt{width:var(side);} img{width:var(side);}";
+        let (mut sc, err) = StyleContext::new(s);
+        assert!(err.is_empty(), "{err}");
+        sc.set_style(&mut eh);
+        let width = |eh: &ElementHolder, m| attr(eh, key(eh, m), AttrName::WIDTH);
+        assert!(width(&eh, "pt").is_some());
+        assert!(width(&eh, "img").is_none());
+
+        //"side" isn't declared on either img, it's reported once.
+        let err = sc.take_error();
+        assert_eq!(err.len(), 1);
+        assert_eq!(err[0].code(), Some(ErrorCode::UndefinedVariable));
+        assert_eq!(err[0].position(), Some((0, 43)));
+        assert_eq!(err[0].length(), 5);
+        sc.set_style(&mut eh);
+        assert!(sc.take_error().is_empty());
+    }
+
+    #[test]
+    fn state() {
+        let mut eh =
+            elements("<body><button class=a>ok</button><area><pt>x</pt><img></img></area></body>");
+        let s = "button:hover{width:1;} area :pressed{width:2;} img:empty{height:3;} \
+            pt:empty{height:3;} .a:focus:hover{height:4;} inp:foo{}";
+        let (mut sc, err) = StyleContext::new(s);
+        assert_eq!(err.len(), 1);
+        assert_eq!(err[0].code(), Some(ErrorCode::InvalidState));
+        assert!(sc.is_interactive());
+        sc.set_style(&mut eh);
+        let b = key(&eh, "button");
+        let img = key(&eh, "img");
+        let pt = key(&eh, "pt");
+        assert!(sc.origin(b, &AttrName::WIDTH).is_none());
+        let o = sc.origin(img, &AttrName::HEIGHT).unwrap();
+        assert_eq!(o.selector(), "img:empty");
+        assert!(sc.origin(pt, &AttrName::HEIGHT).is_none());
+
+        eh.get_mut(b).unwrap().state_mut().set(State::Hover, true);
+        let e = eh.get_mut(img).unwrap();
+        e.state_mut().set(State::Pressed, true);
+        sc.set_style(&mut eh);
+        let o = sc.origin(b, &AttrName::WIDTH).unwrap();
+        assert_eq!(o.selector(), "button:hover");
+        assert!(sc.origin(b, &AttrName::HEIGHT).is_none());
+        assert_eq!(sc.origin(img, &AttrName::WIDTH).unwrap().rule(), 1);
+
+        eh.get_mut(b).unwrap().state_mut().set(State::Focus, true);
+        sc.set_style(&mut eh);
+        let o = sc.origin(b, &AttrName::HEIGHT).unwrap();
+        assert_eq!(o.specificity(), (0, 3, 0));
+    }
+
+    #[test]
+    fn restyle() {
+        let mut eh = elements(
+            "<body><area class=a><pt>x</pt></area><button>ok</button><img></img><inp/></body>",
+        );
+        let s = "button:hover{color:#ff0000;} .a:hover pt{width:2;} img{width:3;} \
+            @vars{w:1;} inp:hover{--w:5;} inp{height:var(w);}";
+        let (mut sc, err) = StyleContext::new(s);
+        assert!(err.is_empty());
+        sc.set_style(&mut eh);
+        let b = key(&eh, "button");
+        let area = key(&eh, "area");
+        let pt = key(&eh, "pt");
+        let img = key(&eh, "img");
+        let inp = key(&eh, "inp");
+        assert!(sc.restyle(&mut eh).is_empty());
+
+        //no rule depends on the state of img.
+        let mut hover = |eh: &mut ElementHolder, k, o| {
+            eh.get_mut(k).unwrap().state_mut().set(State::Hover, o);
+            sc.restyle(eh)
+        };
+        assert!(hover(&mut eh, img, true).is_empty());
+        assert_eq!(hover(&mut eh, b, true), [(b, vec![AttrName::COLOR])]);
+        assert_eq!(hover(&mut eh, area, true), [(pt, vec![AttrName::WIDTH])]);
+        assert_eq!(hover(&mut eh, area, false), [(pt, vec![AttrName::WIDTH])]);
+        assert!(attr(&eh, pt, AttrName::WIDTH).is_none());
+        assert!(attr(&eh, b, AttrName::COLOR).is_some());
+        assert!(attr(&eh, img, AttrName::WIDTH).is_some());
+
+        let height = attr(&eh, inp, AttrName::HEIGHT);
+        assert_eq!(hover(&mut eh, inp, true), [(inp, vec![AttrName::HEIGHT])]);
+        assert_ne!(attr(&eh, inp, AttrName::HEIGHT), height);
+        assert!(attr(&eh, img, AttrName::WIDTH).is_some());
+    }
+
+    #[test]
+    fn reload() {
+        let mut eh =
+            elements("<body><area><pt>x</pt></area><button position=1,2>ok</button></body>");
+        let (mut sc, _) = StyleContext::new("pt{width:1;} button{position:4,4 !important;}");
+        let b = key(&eh, "button");
+        let inline = attr(&eh, b, AttrName::POSITION);
+        sc.set_style(&mut eh);
+        let pt = key(&eh, "pt");
+        let a = Attribute::from(&AttrName::HEIGHT, &mut "3".to_string()).unwrap();
+        eh.get_mut(pt).unwrap().attribute_insert(a);
+
+        let err = sc.reload("area{width:2;} foo{}", &mut eh);
+        assert_eq!(err.len(), 1);
+        assert!(attr(&eh, pt, AttrName::WIDTH).is_none());
+        assert!(attr(&eh, pt, AttrName::HEIGHT).is_some());
+        assert_eq!(attr(&eh, b, AttrName::POSITION), inline);
+        let area = key(&eh, "area");
+        assert!(sc.origin(area, &AttrName::WIDTH).is_some());
+    }
+
+    #[test]
+    fn keyframes() {
+        let s = "@keyframes Pulse { from{width:10;opacity:0;} 50%{background:#ff0000;} to{width:30;opacity:1;} } \
+            @keyframes bad { 120%{width:1;} 0%, 100%{class:a;} } pt{animation:pulse, 1000, loop;}";
+        let (sc, err) = StyleContext::new(s);
+        let code = |c| err.iter().filter(|e| e.code() == Some(c)).count();
+        assert_eq!(code(ErrorCode::InvalidKeyframe), 2);
+        assert_eq!(err.len(), 2);
+        assert!(sc.keyframes("bad").is_some_and(|o| o.len() == 2));
+
+        let o = sc.keyframes("pulse").unwrap();
+        assert_eq!(o.len(), 3);
+        let v: Vec<_> = o.sample(0.25).iter().map(|a| a.to_string()).collect();
+        assert_eq!(v, ["15", "0.25", "#ff0000"]);
+        let v: Vec<_> = o.sample(1.0).iter().map(|a| a.to_string()).collect();
+        assert_eq!(v, ["30", "1", "#ff0000"]);
     }
 }
@@ -14,6 +14,8 @@ pub(crate) mod ascii {
     pub(crate) const QUOTATION: char = '"';
     ///#
     pub(crate) const NUMBER_SIGN: char = '#';
+    ///$
+    pub(crate) const DOLLAR_SIGN: char = '$';
     ///%
     pub(crate) const PER_CENT: char = '%';
     ///&
@@ -70,6 +72,8 @@ pub(crate) mod ascii {
     pub(crate) const RIGHT_CURLY_BRACKET: char = '}';
     ///|
     pub(crate) const VERTICAL_LINE: char = '|';
+    ///~
+    pub(crate) const TILDE: char = '~';
 
     use crate::error::*;
 
@@ -104,6 +108,10 @@ pub(crate) mod ascii {
             }
         }
 
+        pub(crate) fn position(&self) -> (usize, usize) {
+            (self.row, self.column)
+        }
+
         pub(crate) fn to_error(&self, k: ErrorKind, s: impl ToString) -> Error {
             (k, self.row, self.column, s).into()
         }
@@ -160,6 +168,26 @@ impl Chronograph {
     pub(crate) fn refresh(&mut self) {
         self.t = Instant::now();
     }
+
+    //makes it elapsed at once.
+    pub(crate) fn expire(&mut self) {
+        let n = Duration::from_millis(self.n);
+        self.t = Instant::now().checked_sub(n).unwrap_or(self.t);
+    }
+
+    //moves the start back, as if `n` milliseconds passed.
+    pub(crate) fn pass(&mut self, n: u64) {
+        let n = Duration::from_millis(n);
+        self.t = self.t.checked_sub(n).unwrap_or(self.t);
+    }
+
+    //the elapsed time divided by the interval, it's infinite if the interval is 0.
+    pub(crate) fn ratio(&self) -> f32 {
+        if self.n == 0 {
+            return f32::INFINITY;
+        }
+        self.t.elapsed().as_secs_f32() * 1000.0 / self.n as f32
+    }
 }
 
 #[derive(Debug)]